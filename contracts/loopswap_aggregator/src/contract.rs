@@ -0,0 +1,302 @@
+use crate::error::ContractError;
+use crate::route::{find_routes, shares_pair, to_operations, Hop, Route};
+use crate::state::{Config, CONFIG};
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult, Uint128,
+};
+use cw2::set_contract_version;
+use loopswap::aggregator::{
+    BestRouteResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    RouteResponse,
+};
+use loopswap::asset::{Asset, AssetInfo, PairInfo};
+use loopswap::pair::{PauseInfoResponse, QueryMsg as PairQueryMsg};
+use loopswap::querier::{query_pairs, simulate};
+use std::cmp::Reverse;
+
+const CONTRACT_NAME: &str = "crates.io:loopswap-aggregator";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const DEFAULT_MAX_HOPS: u32 = 3;
+const MAX_HOPS: u32 = 4;
+const MAX_SPLITS: u32 = 3;
+/// Only the shortest routes are simulated, keeps the query gas bounded
+const MAX_CANDIDATE_ROUTES: usize = 20;
+/// Partial paths the route search extends before giving up on longer routes
+const MAX_EXPLORED_PATHS: usize = 500;
+/// Pairs loaded from the factory, in its `pairs` order
+const MAX_PAIRS: usize = 300;
+/// A split offer is handed out in this many parts, each to the route paying the most for it
+const SPLIT_PARTS: u128 = 10;
+const PAIRS_PAGE_LIMIT: u32 = 30;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut<Empty>,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: info.sender,
+            loopswap_factory: deps.api.addr_validate(&msg.loopswap_factory)?,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut<Empty>,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig {
+            owner,
+            loopswap_factory,
+        } => update_config(deps, info, owner, loopswap_factory),
+    }
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    loopswap_factory: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+    if let Some(loopswap_factory) = loopswap_factory {
+        config.loopswap_factory = deps.api.addr_validate(&loopswap_factory)?;
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps<Empty>, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Config {} => Ok(to_binary(&query_config(deps)?)?),
+        QueryMsg::BestRoute {
+            offer_asset,
+            ask_asset_info,
+            max_hops,
+            max_splits,
+        } => Ok(to_binary(&query_best_route(
+            deps,
+            offer_asset,
+            ask_asset_info,
+            max_hops,
+            max_splits,
+        )?)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: config.owner.to_string(),
+        loopswap_factory: config.loopswap_factory.to_string(),
+    })
+}
+
+pub fn query_best_route(
+    deps: Deps,
+    offer_asset: Asset,
+    ask_asset_info: AssetInfo,
+    max_hops: Option<u32>,
+    max_splits: Option<u32>,
+) -> Result<BestRouteResponse, ContractError> {
+    if offer_asset.amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    if offer_asset.info.equal(&ask_asset_info) {
+        return Err(ContractError::SameAsset {});
+    }
+
+    let max_hops = max_hops.unwrap_or(DEFAULT_MAX_HOPS).clamp(1, MAX_HOPS) as usize;
+    let max_splits = max_splits.unwrap_or(1).clamp(1, MAX_SPLITS) as usize;
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let pairs = load_pairs(deps, &config.loopswap_factory)?;
+
+    // a route through a pair that can't simulate the swap, e.g. an empty pool, is ignored
+    let mut candidates: Vec<(Route, Uint128)> = find_routes(
+        &pairs,
+        &offer_asset.info,
+        &ask_asset_info,
+        max_hops,
+        MAX_CANDIDATE_ROUTES,
+        MAX_EXPLORED_PATHS,
+        |pair| is_open(deps, pair),
+    )
+    .into_iter()
+    .filter_map(|route| {
+        simulate_route(deps, &route, offer_asset.amount)
+            .ok()
+            .map(|return_amount| (route, return_amount))
+    })
+    .collect();
+    candidates.sort_by_key(|(_, return_amount)| Reverse(*return_amount));
+
+    let (best_route, best_return) = match candidates.first() {
+        Some((route, return_amount)) if !return_amount.is_zero() => (route.clone(), *return_amount),
+        _ => return Err(ContractError::NoRouteFound {}),
+    };
+
+    let mut selected: Vec<Route> = vec![];
+    for (route, _) in candidates.into_iter() {
+        if selected.len() == max_splits {
+            break;
+        }
+        if selected.iter().all(|other| !shares_pair(other, &route)) {
+            selected.push(route);
+        }
+    }
+
+    if selected.len() > 1 {
+        let routes = split_offer(deps, &selected, offer_asset.amount)?;
+        let return_amount = routes
+            .iter()
+            .fold(Uint128::zero(), |acc, route| acc + route.return_amount);
+        if return_amount > best_return {
+            return Ok(BestRouteResponse {
+                routes,
+                return_amount,
+            });
+        }
+    }
+
+    Ok(BestRouteResponse {
+        routes: vec![RouteResponse {
+            operations: to_operations(&best_route),
+            offer_amount: offer_asset.amount,
+            return_amount: best_return,
+        }],
+        return_amount: best_return,
+    })
+}
+
+/// Hands the offer out part by part, each part to the route with the largest marginal return
+fn split_offer(
+    deps: Deps,
+    routes: &[Route],
+    offer_amount: Uint128,
+) -> Result<Vec<RouteResponse>, ContractError> {
+    let part = offer_amount.multiply_ratio(1u128, SPLIT_PARTS);
+    let mut offer_amounts = vec![Uint128::zero(); routes.len()];
+    let mut return_amounts = vec![Uint128::zero(); routes.len()];
+    let mut remaining = offer_amount;
+
+    while !remaining.is_zero() {
+        let size = if part.is_zero() || remaining < part + part {
+            remaining
+        } else {
+            part
+        };
+
+        let mut best: Option<(usize, Uint128, Uint128)> = None;
+        for (i, route) in routes.iter().enumerate() {
+            let return_amount = match simulate_route(deps, route, offer_amounts[i] + size) {
+                Ok(return_amount) => return_amount,
+                Err(_) => continue,
+            };
+            let gain = return_amount.saturating_sub(return_amounts[i]);
+            match best {
+                Some((_, best_gain, _)) if best_gain >= gain => {}
+                _ => best = Some((i, gain, return_amount)),
+            }
+        }
+
+        let (i, _, return_amount) = best.ok_or(ContractError::NoRouteFound {})?;
+        offer_amounts[i] += size;
+        return_amounts[i] = return_amount;
+        remaining = remaining.checked_sub(size)?;
+    }
+
+    Ok(routes
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !offer_amounts[*i].is_zero())
+        .map(|(i, route)| RouteResponse {
+            operations: to_operations(route),
+            offer_amount: offer_amounts[i],
+            return_amount: return_amounts[i],
+        })
+        .collect())
+}
+
+/// Return of swapping `offer_amount` along the route, hop by hop with each pair Simulation
+fn simulate_route(deps: Deps, route: &[Hop], offer_amount: Uint128) -> StdResult<Uint128> {
+    let mut amount = offer_amount;
+    for hop in route.iter() {
+        let res = simulate(
+            &deps.querier,
+            Addr::unchecked(hop.pair_contract.clone()),
+            &Asset {
+                info: hop.offer_asset_info.clone(),
+                amount,
+            },
+        )?;
+        amount = res.return_amount;
+    }
+
+    Ok(amount)
+}
+
+/// Pairs that don't answer the pause query predate it and can't be paused
+fn is_open(deps: Deps, pair: &PairInfo) -> bool {
+    let res: StdResult<PauseInfoResponse> = deps
+        .querier
+        .query_wasm_smart(pair.contract_addr.clone(), &PairQueryMsg::PauseInfo {});
+    res.map(|res| !res.paused).unwrap_or(true)
+}
+
+/// The first `MAX_PAIRS` pairs of the factory
+fn load_pairs(deps: Deps, factory: &Addr) -> StdResult<Vec<PairInfo>> {
+    let mut pairs: Vec<PairInfo> = vec![];
+    let mut start_after: Option<[AssetInfo; 2]> = None;
+    while pairs.len() < MAX_PAIRS {
+        let page = query_pairs(
+            &deps.querier,
+            factory.clone(),
+            start_after,
+            Some(PAIRS_PAGE_LIMIT),
+        )?
+        .pairs;
+        let last = page.last().map(|pair| pair.asset_infos.clone());
+        let done = page.len() < PAIRS_PAGE_LIMIT as usize;
+        pairs.extend(page);
+
+        match last {
+            Some(last) if !done => start_after = Some(last),
+            _ => break,
+        }
+    }
+
+    Ok(pairs)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
@@ -0,0 +1,8 @@
+pub mod contract;
+pub mod route;
+pub mod state;
+
+mod error;
+
+#[cfg(test)]
+mod testing;
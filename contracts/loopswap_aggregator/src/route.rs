@@ -0,0 +1,104 @@
+use loopswap::asset::{AssetInfo, PairInfo};
+use loopswap::router::SwapOperation;
+use std::collections::BTreeMap;
+
+/// One swap of a route, through `pair_contract`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hop {
+    pub pair_contract: String,
+    pub offer_asset_info: AssetInfo,
+    pub ask_asset_info: AssetInfo,
+}
+
+pub type Route = Vec<Hop>;
+
+/// Paths from `offer` to `ask` of at most `max_hops` pairs that visit an asset only once.
+/// The search goes breadth first, so shorter routes come first, and stops once `max_routes`
+/// routes are found or `max_paths` paths are explored. Pairs `is_open` rejects are skipped,
+/// it is asked once per pair the search reaches
+pub fn find_routes<F>(
+    pairs: &[PairInfo],
+    offer: &AssetInfo,
+    ask: &AssetInfo,
+    max_hops: usize,
+    max_routes: usize,
+    max_paths: usize,
+    mut is_open: F,
+) -> Vec<Route>
+where
+    F: FnMut(&PairInfo) -> bool,
+{
+    // pairs by the assets they trade
+    let mut edges: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, pair) in pairs.iter().enumerate() {
+        for asset_info in pair.asset_infos.iter() {
+            edges.entry(asset_info.to_string()).or_default().push(i);
+        }
+    }
+    let mut open: BTreeMap<usize, bool> = BTreeMap::new();
+
+    let mut routes: Vec<Route> = vec![];
+    let mut paths: Vec<Route> = vec![vec![]];
+    let mut explored: usize = 0;
+    for _ in 0..max_hops {
+        let mut next_paths: Vec<Route> = vec![];
+        for path in paths.iter() {
+            let current = path.last().map(|hop| &hop.ask_asset_info).unwrap_or(offer);
+            for i in edges.get(&current.to_string()).into_iter().flatten() {
+                let pair = &pairs[*i];
+                let next = if pair.asset_infos[0].equal(current) {
+                    &pair.asset_infos[1]
+                } else {
+                    &pair.asset_infos[0]
+                };
+                if next.equal(offer) || path.iter().any(|hop| hop.ask_asset_info.equal(next)) {
+                    continue;
+                }
+                if !*open.entry(*i).or_insert_with(|| is_open(pair)) {
+                    continue;
+                }
+
+                let mut route = path.clone();
+                route.push(Hop {
+                    pair_contract: pair.contract_addr.clone(),
+                    offer_asset_info: current.clone(),
+                    ask_asset_info: next.clone(),
+                });
+                if next.equal(ask) {
+                    routes.push(route);
+                    if routes.len() == max_routes {
+                        return routes;
+                    }
+                } else {
+                    next_paths.push(route);
+                }
+
+                explored += 1;
+                if explored == max_paths {
+                    return routes;
+                }
+            }
+        }
+        paths = next_paths;
+    }
+
+    routes
+}
+
+/// Routes sharing a pair move the same pool, they can't be simulated independently
+pub fn shares_pair(a: &[Hop], b: &[Hop]) -> bool {
+    a.iter().any(|hop| {
+        b.iter()
+            .any(|other| other.pair_contract == hop.pair_contract)
+    })
+}
+
+pub fn to_operations(route: &[Hop]) -> Vec<SwapOperation> {
+    route
+        .iter()
+        .map(|hop| SwapOperation::TerraSwap {
+            offer_asset_info: hop.offer_asset_info.clone(),
+            ask_asset_info: hop.ask_asset_info.clone(),
+        })
+        .collect()
+}
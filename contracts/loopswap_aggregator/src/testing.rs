@@ -0,0 +1,340 @@
+use crate::contract::{execute, instantiate, query_best_route, query_config};
+use crate::error::ContractError;
+use crate::route::find_routes;
+
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, ContractResult, Empty, OwnedDeps, Querier, QuerierResult,
+    QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+};
+use loopswap::aggregator::{ExecuteMsg, InstantiateMsg};
+use loopswap::asset::{Asset, AssetInfo, PairInfo};
+use loopswap::factory::{PairsResponse, QueryMsg as FactoryQueryMsg};
+use loopswap::pair::{PauseInfoResponse, QueryMsg as PairQueryMsg, SimulationResponse};
+use loopswap::router::SwapOperation;
+use std::marker::PhantomData;
+
+/// Factory listing `pools` and constant product pairs with a 0.3% commission
+struct PoolQuerier {
+    base: MockQuerier<Empty>,
+    pools: Vec<(PairInfo, [Uint128; 2])>,
+    paused: Vec<String>,
+}
+
+impl Querier for PoolQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        match &request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                if contract_addr == "factory0000" =>
+            {
+                match from_binary(msg).unwrap() {
+                    FactoryQueryMsg::Pairs { start_after, limit } => {
+                        let start = match start_after {
+                            Some(start_after) => {
+                                self.pools
+                                    .iter()
+                                    .position(|(pair, _)| pair.asset_infos == start_after)
+                                    .unwrap()
+                                    + 1
+                            }
+                            None => 0,
+                        };
+                        let pairs = self
+                            .pools
+                            .iter()
+                            .skip(start)
+                            .take(limit.unwrap() as usize)
+                            .map(|(pair, _)| pair.clone())
+                            .collect();
+                        SystemResult::Ok(ContractResult::from(to_binary(&PairsResponse { pairs })))
+                    }
+                    _ => panic!("DO NOT ENTER HERE"),
+                }
+            }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                let (pair, pool) = self
+                    .pools
+                    .iter()
+                    .find(|(pair, _)| &pair.contract_addr == contract_addr)
+                    .unwrap();
+                match from_binary(msg).unwrap() {
+                    PairQueryMsg::Simulation { offer_asset, .. } => {
+                        let (offer_pool, ask_pool) = if offer_asset.info.equal(&pair.asset_infos[0])
+                        {
+                            (pool[0], pool[1])
+                        } else {
+                            (pool[1], pool[0])
+                        };
+                        let return_amount = ask_pool
+                            - ask_pool.multiply_ratio(offer_pool, offer_pool + offer_asset.amount);
+                        let commission_amount = return_amount.multiply_ratio(3u128, 1000u128);
+                        SystemResult::Ok(ContractResult::from(to_binary(&SimulationResponse {
+                            return_amount: return_amount - commission_amount,
+                            spread_amount: Uint128::zero(),
+                            commission_amount,
+                            commission_rate: Default::default(),
+                            referral_amount: Uint128::zero(),
+                        })))
+                    }
+                    PairQueryMsg::PauseInfo {} => {
+                        SystemResult::Ok(ContractResult::from(to_binary(&PauseInfoResponse {
+                            paused: self.paused.contains(contract_addr),
+                            max_price_impact: None,
+                        })))
+                    }
+                    _ => panic!("DO NOT ENTER HERE"),
+                }
+            }
+            _ => self.base.handle_query(&request),
+        }
+    }
+}
+
+fn native(denom: &str) -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    }
+}
+
+fn pool(contract_addr: &str, assets: [&str; 2], amounts: [u128; 2]) -> (PairInfo, [Uint128; 2]) {
+    (
+        PairInfo {
+            asset_infos: [native(assets[0]), native(assets[1])],
+            contract_addr: contract_addr.to_string(),
+            liquidity_token: format!("{}_lp", contract_addr),
+            asset_decimals: [6u8, 6u8],
+        },
+        [Uint128::from(amounts[0]), Uint128::from(amounts[1])],
+    )
+}
+
+fn mock_aggregator(
+    pools: Vec<(PairInfo, [Uint128; 2])>,
+) -> OwnedDeps<MockStorage, MockApi, PoolQuerier, Empty> {
+    let mut deps = OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: PoolQuerier {
+            base: MockQuerier::new(&[]),
+            pools,
+            paused: vec![],
+        },
+        custom_query_type: PhantomData,
+    };
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner0000", &[]),
+        InstantiateMsg {
+            loopswap_factory: "factory0000".to_string(),
+        },
+    )
+    .unwrap();
+
+    deps
+}
+
+fn offer(denom: &str, amount: u128) -> Asset {
+    Asset {
+        info: native(denom),
+        amount: Uint128::from(amount),
+    }
+}
+
+fn operation(offer: &str, ask: &str) -> SwapOperation {
+    SwapOperation::TerraSwap {
+        offer_asset_info: native(offer),
+        ask_asset_info: native(ask),
+    }
+}
+
+#[test]
+fn update_config() {
+    let mut deps = mock_aggregator(vec![]);
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: Some("owner0001".to_string()),
+        loopswap_factory: Some("factory0001".to_string()),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+    let config = query_config(deps.as_ref()).unwrap();
+    assert_eq!(config.owner, "owner0001");
+    assert_eq!(config.loopswap_factory, "factory0001");
+}
+
+#[test]
+fn best_route_through_deeper_pools() {
+    let deps = mock_aggregator(vec![
+        pool("pair0000", ["uluna", "uusd"], [10_000, 10_000]),
+        pool("pair0001", ["uluna", "ukrw"], [10_000_000, 10_000_000]),
+        pool("pair0002", ["ukrw", "uusd"], [10_000_000, 10_000_000]),
+        pool("pair0003", ["uatom", "uusd"], [10_000_000, 10_000_000]),
+    ]);
+
+    // the direct pool is shallow, going through ukrw pays more
+    let res = query_best_route(
+        deps.as_ref(),
+        offer("uluna", 1_000),
+        native("uusd"),
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(res.routes.len(), 1);
+    assert_eq!(
+        res.routes[0].operations,
+        vec![operation("uluna", "ukrw"), operation("ukrw", "uusd")]
+    );
+    assert_eq!(res.routes[0].offer_amount, Uint128::from(1_000u128));
+    assert_eq!(res.return_amount, Uint128::from(995u128));
+
+    // a single hop only leaves the direct pool
+    let res = query_best_route(
+        deps.as_ref(),
+        offer("uluna", 1_000),
+        native("uusd"),
+        Some(1),
+        None,
+    )
+    .unwrap();
+    assert_eq!(res.routes[0].operations, vec![operation("uluna", "uusd")]);
+    assert_eq!(res.return_amount, Uint128::from(908u128));
+
+    let err = query_best_route(
+        deps.as_ref(),
+        offer("uluna", 1_000),
+        native("ujpy"),
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoRouteFound {});
+
+    let err = query_best_route(
+        deps.as_ref(),
+        offer("uluna", 1_000),
+        native("uluna"),
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::SameAsset {});
+}
+
+#[test]
+fn best_route_splits_across_parallel_routes() {
+    let deps = mock_aggregator(vec![
+        pool("pair0000", ["uluna", "uusd"], [1_000_000, 1_000_000]),
+        pool("pair0001", ["uluna", "ukrw"], [1_000_000, 1_000_000]),
+        pool("pair0002", ["ukrw", "uusd"], [1_000_000, 1_000_000]),
+    ]);
+
+    let single = query_best_route(
+        deps.as_ref(),
+        offer("uluna", 200_000),
+        native("uusd"),
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(single.routes.len(), 1);
+    assert_eq!(
+        single.routes[0].operations,
+        vec![operation("uluna", "uusd")]
+    );
+
+    let split = query_best_route(
+        deps.as_ref(),
+        offer("uluna", 200_000),
+        native("uusd"),
+        None,
+        Some(2),
+    )
+    .unwrap();
+    assert_eq!(split.routes.len(), 2);
+    assert_eq!(split.routes[0].operations, vec![operation("uluna", "uusd")]);
+    assert_eq!(
+        split.routes[1].operations,
+        vec![operation("uluna", "ukrw"), operation("ukrw", "uusd")]
+    );
+    assert_eq!(
+        split.routes[0].offer_amount + split.routes[1].offer_amount,
+        Uint128::from(200_000u128)
+    );
+    assert!(split.return_amount > single.return_amount);
+}
+
+#[test]
+fn best_route_skips_paused_pairs() {
+    let mut deps = mock_aggregator(vec![
+        pool("pair0000", ["uluna", "uusd"], [10_000, 10_000]),
+        pool("pair0001", ["uluna", "ukrw"], [10_000_000, 10_000_000]),
+        pool("pair0002", ["ukrw", "uusd"], [10_000_000, 10_000_000]),
+    ]);
+    deps.querier.paused = vec!["pair0002".to_string()];
+
+    let res = query_best_route(
+        deps.as_ref(),
+        offer("uluna", 1_000),
+        native("uusd"),
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(res.routes[0].operations, vec![operation("uluna", "uusd")]);
+    assert_eq!(res.return_amount, Uint128::from(908u128));
+
+    deps.querier.paused = vec!["pair0000".to_string(), "pair0002".to_string()];
+    let err = query_best_route(
+        deps.as_ref(),
+        offer("uluna", 1_000),
+        native("uusd"),
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoRouteFound {});
+}
+
+#[test]
+fn find_routes_stops_at_limits() {
+    let pairs: Vec<PairInfo> = vec![
+        pool("pair0000", ["uluna", "uusd"], [1, 1]).0,
+        pool("pair0001", ["uluna", "ukrw"], [1, 1]).0,
+        pool("pair0002", ["ukrw", "uusd"], [1, 1]).0,
+        pool("pair0003", ["uluna", "uatom"], [1, 1]).0,
+        pool("pair0004", ["uatom", "uusd"], [1, 1]).0,
+    ];
+
+    let routes = find_routes(&pairs, &native("uluna"), &native("uusd"), 3, 20, 100, |_| true);
+    assert_eq!(routes.len(), 3);
+    assert_eq!(routes[0].len(), 1);
+
+    // the shortest route is found first
+    let routes = find_routes(&pairs, &native("uluna"), &native("uusd"), 3, 1, 100, |_| true);
+    assert_eq!(routes.len(), 1);
+    assert_eq!(routes[0][0].pair_contract, "pair0000");
+
+    // two explored paths only reach the direct pair and ukrw
+    let routes = find_routes(&pairs, &native("uluna"), &native("uusd"), 3, 20, 2, |_| true);
+    assert_eq!(routes.len(), 1);
+}
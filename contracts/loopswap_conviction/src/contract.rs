@@ -0,0 +1,510 @@
+use std::convert::TryFrom;
+
+use crate::error::ContractError;
+use crate::state::{Config, FundingRequest, CONFIG, REQUESTS, REQUEST_COUNT, SUPPORTS};
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env, Fraction,
+    MessageInfo, Order, Response, StdResult, Uint128, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::Bound;
+use loopswap::asset::{Asset, AssetInfo};
+use loopswap::conviction::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, RequestResponse,
+    RequestStatus, RequestsResponse, SupportResponse,
+};
+use loopswap::querier::query_token_info;
+
+const CONTRACT_NAME: &str = "crates.io:loopswap-conviction";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut<Empty>,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        owner: info.sender,
+        lopo_token: deps.api.addr_validate(&msg.lopo_token)?,
+        funding_asset: msg.funding_asset,
+        decay: msg.decay,
+        max_ratio: msg.max_ratio,
+        weight: msg.weight,
+    };
+    assert_parameters(&config)?;
+    CONFIG.save(deps.storage, &config)?;
+    REQUEST_COUNT.save(deps.storage, &0u64)?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut<Empty>,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::CreateRequest {
+            title,
+            description,
+            beneficiary,
+            amount,
+        } => create_request(deps, env, info, title, description, beneficiary, amount),
+        ExecuteMsg::WithdrawSupport { request_id, amount } => {
+            withdraw_support(deps, env, info, request_id, amount)
+        }
+        ExecuteMsg::ExecuteRequest { request_id } => execute_request(deps, env, request_id),
+        ExecuteMsg::CancelRequest { request_id } => cancel_request(deps, info, request_id),
+        ExecuteMsg::UpdateConfig {
+            owner,
+            decay,
+            max_ratio,
+            weight,
+        } => update_config(deps, info, owner, decay, max_ratio, weight),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut<Empty>,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::StakeSupport { request_id } => {
+            let config: Config = CONFIG.load(deps.storage)?;
+            if config.lopo_token != info.sender {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let staker = deps.api.addr_validate(&cw20_msg.sender)?;
+            stake_support(deps, env, config, staker, request_id, cw20_msg.amount)
+        }
+    }
+}
+
+pub fn create_request(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    title: String,
+    description: String,
+    beneficiary: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let id = REQUEST_COUNT.load(deps.storage)? + 1;
+    REQUEST_COUNT.save(deps.storage, &id)?;
+
+    let beneficiary = deps.api.addr_validate(&beneficiary)?;
+    REQUESTS.save(
+        deps.storage,
+        id,
+        &FundingRequest {
+            title,
+            description,
+            proposer: info.sender.clone(),
+            beneficiary: beneficiary.clone(),
+            amount,
+            status: RequestStatus::Open,
+            total_support: Uint128::zero(),
+            conviction: Uint128::zero(),
+            last_update: env.block.time.seconds(),
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "create_request"),
+        ("request_id", &id.to_string()),
+        ("proposer", info.sender.as_str()),
+        ("beneficiary", beneficiary.as_str()),
+        ("amount", &amount.to_string()),
+    ]))
+}
+
+pub fn stake_support(
+    deps: DepsMut,
+    env: Env,
+    config: Config,
+    staker: Addr,
+    request_id: u64,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let mut request = REQUESTS.load(deps.storage, request_id)?;
+    if request.status != RequestStatus::Open {
+        return Err(ContractError::RequestNotOpen {});
+    }
+
+    // Conviction built with the old support is kept, the new support
+    // only counts from now on
+    update_conviction(&config, &mut request, env.block.time.seconds())?;
+    request.total_support += amount;
+    SUPPORTS.update(
+        deps.storage,
+        (request_id, &staker),
+        |staked| -> StdResult<_> { Ok(staked.unwrap_or_default() + amount) },
+    )?;
+
+    let payout = try_pass(deps.as_ref(), &env, &config, &mut request)?;
+    REQUESTS.save(deps.storage, request_id, &request)?;
+
+    Ok(Response::new().add_messages(payout).add_attributes(vec![
+        ("action", "stake_support"),
+        ("request_id", &request_id.to_string()),
+        ("staker", staker.as_str()),
+        ("amount", &amount.to_string()),
+        ("conviction", &request.conviction.to_string()),
+    ]))
+}
+
+pub fn withdraw_support(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    request_id: u64,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut request = REQUESTS.load(deps.storage, request_id)?;
+
+    let staked = SUPPORTS
+        .may_load(deps.storage, (request_id, &info.sender))?
+        .unwrap_or_default();
+    let amount = amount.unwrap_or(staked);
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    if amount > staked {
+        return Err(ContractError::InsufficientSupport { staked });
+    }
+
+    // A request that passed with the support being withdrawn is still paid out
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if request.status == RequestStatus::Open {
+        update_conviction(&config, &mut request, env.block.time.seconds())?;
+        messages.extend(try_pass(deps.as_ref(), &env, &config, &mut request)?);
+    }
+    request.total_support = request.total_support.checked_sub(amount)?;
+    REQUESTS.save(deps.storage, request_id, &request)?;
+
+    let remaining = staked.checked_sub(amount)?;
+    if remaining.is_zero() {
+        SUPPORTS.remove(deps.storage, (request_id, &info.sender));
+    } else {
+        SUPPORTS.save(deps.storage, (request_id, &info.sender), &remaining)?;
+    }
+
+    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: config.lopo_token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: info.sender.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    }));
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "withdraw_support"),
+        ("request_id", &request_id.to_string()),
+        ("staker", info.sender.as_str()),
+        ("amount", &amount.to_string()),
+    ]))
+}
+
+/// Pays out a request once the conviction it built up over time exceeds its threshold
+pub fn execute_request(
+    deps: DepsMut,
+    env: Env,
+    request_id: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut request = REQUESTS.load(deps.storage, request_id)?;
+    if request.status != RequestStatus::Open {
+        return Err(ContractError::RequestNotOpen {});
+    }
+
+    update_conviction(&config, &mut request, env.block.time.seconds())?;
+    let payout = match try_pass(deps.as_ref(), &env, &config, &mut request)? {
+        Some(payout) => payout,
+        None => {
+            return Err(ContractError::ThresholdNotReached {
+                conviction: request.conviction,
+            })
+        }
+    };
+    REQUESTS.save(deps.storage, request_id, &request)?;
+
+    Ok(Response::new().add_message(payout).add_attributes(vec![
+        ("action", "execute_request"),
+        ("request_id", &request_id.to_string()),
+        ("conviction", &request.conviction.to_string()),
+    ]))
+}
+
+pub fn cancel_request(
+    deps: DepsMut,
+    info: MessageInfo,
+    request_id: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut request = REQUESTS.load(deps.storage, request_id)?;
+    if request.proposer != info.sender && config.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if request.status != RequestStatus::Open {
+        return Err(ContractError::RequestNotOpen {});
+    }
+
+    request.status = RequestStatus::Cancelled;
+    REQUESTS.save(deps.storage, request_id, &request)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "cancel_request"),
+        ("request_id", &request_id.to_string()),
+    ]))
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    decay: Option<Decimal>,
+    max_ratio: Option<Decimal>,
+    weight: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+    if let Some(decay) = decay {
+        config.decay = decay;
+    }
+    if let Some(max_ratio) = max_ratio {
+        config.max_ratio = max_ratio;
+    }
+    if let Some(weight) = weight {
+        config.weight = weight;
+    }
+    assert_parameters(&config)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+fn assert_parameters(config: &Config) -> Result<(), ContractError> {
+    let in_range = |value: Decimal| !value.is_zero() && value < Decimal::one();
+    if !in_range(config.decay) || !in_range(config.max_ratio) || !in_range(config.weight) {
+        return Err(ContractError::InvalidParameters {});
+    }
+    // Staked support would otherwise be counted as funds and paid out.
+    if let AssetInfo::Token { contract_addr } = &config.funding_asset {
+        if *contract_addr == config.lopo_token {
+            return Err(ContractError::FundingAssetIsLopo {});
+        }
+    }
+    Ok(())
+}
+
+/// Brings the conviction of a request forward to `now`. Every second the
+/// conviction decays and the staked support is added again:
+/// `y(t) = decay^t * y(0) + support * (1 - decay^t) / (1 - decay)`
+fn update_conviction(config: &Config, request: &mut FundingRequest, now: u64) -> StdResult<()> {
+    request.conviction = conviction_at(config, request, now)?;
+    request.last_update = now;
+    Ok(())
+}
+
+fn conviction_at(config: &Config, request: &FundingRequest, now: u64) -> StdResult<Uint128> {
+    let elapsed = now.saturating_sub(request.last_update);
+    let decayed = config
+        .decay
+        .checked_pow(u32::try_from(elapsed).unwrap_or(u32::MAX))?;
+
+    // Both fractions share the decimal denominator
+    let growth = request.total_support.multiply_ratio(
+        (Decimal::one() - decayed).numerator(),
+        (Decimal::one() - config.decay).numerator(),
+    );
+    Ok(request.conviction * decayed + growth)
+}
+
+/// Conviction a request has to exceed:
+/// `weight * lopo_supply / ((1 - decay) * (max_ratio - amount / funds)^2)`.
+/// None if the request asks for `max_ratio` of the funds or more.
+fn threshold(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    amount: Uint128,
+) -> StdResult<Option<Uint128>> {
+    let funds =
+        config
+            .funding_asset
+            .query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+    if funds.is_zero() {
+        return Ok(None);
+    }
+    let ratio = Decimal::from_ratio(amount, funds);
+    if ratio >= config.max_ratio {
+        return Ok(None);
+    }
+
+    let supply = query_token_info(&deps.querier, config.lopo_token.clone())?.total_supply;
+    let distance = config.max_ratio - ratio;
+    let divisor = (Decimal::one() - config.decay) * distance * distance;
+    if divisor.is_zero() {
+        return Ok(None);
+    }
+    // An overflowing threshold can't be reached either
+    Ok((supply * config.weight)
+        .checked_multiply_ratio(divisor.denominator(), divisor.numerator())
+        .ok())
+}
+
+/// Marks the request executed and returns its payout if its conviction exceeds the threshold
+fn try_pass(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    request: &mut FundingRequest,
+) -> StdResult<Option<CosmosMsg>> {
+    let passed = match threshold(deps, env, config, request.amount)? {
+        Some(threshold) => request.conviction > threshold,
+        None => false,
+    };
+    if !passed {
+        return Ok(None);
+    }
+
+    request.status = RequestStatus::Executed;
+    let payout = Asset {
+        info: config.funding_asset.clone(),
+        amount: request.amount,
+    };
+    Ok(Some(payout.into_msg(request.beneficiary.clone())?))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps<Empty>, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Config {} => Ok(to_binary(&query_config(deps)?)?),
+        QueryMsg::Request { request_id } => Ok(to_binary(&query_request(deps, env, request_id)?)?),
+        QueryMsg::Requests { start_after, limit } => {
+            Ok(to_binary(&query_requests(deps, env, start_after, limit)?)?)
+        }
+        QueryMsg::Support { request_id, staker } => {
+            Ok(to_binary(&query_support(deps, request_id, staker)?)?)
+        }
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: config.owner.to_string(),
+        lopo_token: config.lopo_token.to_string(),
+        funding_asset: config.funding_asset,
+        decay: config.decay,
+        max_ratio: config.max_ratio,
+        weight: config.weight,
+    })
+}
+
+pub fn query_request(deps: Deps, env: Env, request_id: u64) -> StdResult<RequestResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let request = REQUESTS.load(deps.storage, request_id)?;
+    to_request_response(deps, &env, &config, request_id, request)
+}
+
+pub fn query_requests(
+    deps: Deps,
+    env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<RequestsResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let requests = REQUESTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (id, request) = item?;
+            to_request_response(deps, &env, &config, id, request)
+        })
+        .collect::<StdResult<Vec<RequestResponse>>>()?;
+
+    Ok(RequestsResponse { requests })
+}
+
+pub fn query_support(deps: Deps, request_id: u64, staker: String) -> StdResult<SupportResponse> {
+    let staker = deps.api.addr_validate(&staker)?;
+    let amount = SUPPORTS
+        .may_load(deps.storage, (request_id, &staker))?
+        .unwrap_or_default();
+    Ok(SupportResponse { amount })
+}
+
+fn to_request_response(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    id: u64,
+    request: FundingRequest,
+) -> StdResult<RequestResponse> {
+    // Only open requests still build up conviction
+    let (conviction, threshold) = if request.status == RequestStatus::Open {
+        (
+            conviction_at(config, &request, env.block.time.seconds())?,
+            threshold(deps, env, config, request.amount)?,
+        )
+    } else {
+        (request.conviction, None)
+    };
+
+    Ok(RequestResponse {
+        id,
+        title: request.title,
+        description: request.description,
+        proposer: request.proposer.to_string(),
+        beneficiary: request.beneficiary.to_string(),
+        amount: request.amount,
+        status: request.status,
+        total_support: request.total_support,
+        conviction,
+        threshold,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
@@ -0,0 +1,32 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Decay, max ratio and weight must be between 0 and 1")]
+    InvalidParameters {},
+
+    #[error("Funding asset cannot be the staked lopo token")]
+    FundingAssetIsLopo {},
+
+    #[error("Request is not open")]
+    RequestNotOpen {},
+
+    #[error("Only {staked} is staked on the request")]
+    InsufficientSupport { staked: Uint128 },
+
+    #[error("Conviction {conviction} has not exceeded the threshold")]
+    ThresholdNotReached { conviction: Uint128 },
+}
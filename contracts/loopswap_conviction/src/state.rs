@@ -0,0 +1,36 @@
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw_storage_plus::{Item, Map};
+use loopswap::asset::AssetInfo;
+use loopswap::conviction::RequestStatus;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const REQUEST_COUNT: Item<u64> = Item::new("request_count");
+pub const REQUESTS: Map<u64, FundingRequest> = Map::new("requests");
+/// Lopo staked by an address on a request
+pub const SUPPORTS: Map<(u64, &Addr), Uint128> = Map::new("supports");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Config {
+    pub owner: Addr,
+    pub lopo_token: Addr,
+    pub funding_asset: AssetInfo,
+    pub decay: Decimal,
+    pub max_ratio: Decimal,
+    pub weight: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct FundingRequest {
+    pub title: String,
+    pub description: String,
+    pub proposer: Addr,
+    pub beneficiary: Addr,
+    pub amount: Uint128,
+    pub status: RequestStatus,
+    pub total_support: Uint128,
+    /// Conviction as of `last_update`
+    pub conviction: Uint128,
+    pub last_update: u64,
+}
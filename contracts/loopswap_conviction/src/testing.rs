@@ -0,0 +1,259 @@
+use crate::contract::{execute, instantiate, query_request, query_support};
+use crate::error::ContractError;
+use loopswap::mock_querier::{mock_dependencies, WasmMockQuerier};
+
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{
+    to_binary, BankMsg, Coin, CosmosMsg, Decimal, Empty, Env, OwnedDeps, SubMsg, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use loopswap::asset::AssetInfo;
+use loopswap::conviction::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, RequestStatus};
+
+fn init_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        lopo_token: "lopo0000".to_string(),
+        funding_asset: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        decay: Decimal::percent(90),
+        max_ratio: Decimal::percent(50),
+        weight: Decimal::percent(2),
+    }
+}
+
+/// 10000 uusd of funds and a lopo supply of 3000
+fn mock_conviction() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier, Empty> {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(10000u128),
+    }]);
+    deps.querier.with_token_balances(&[(
+        &"lopo0000".to_string(),
+        &[
+            (&"addr0000".to_string(), &Uint128::from(1000u128)),
+            (&"addr0001".to_string(), &Uint128::from(2000u128)),
+        ],
+    )]);
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner0000", &[]),
+        init_msg(),
+    )
+    .unwrap();
+
+    deps
+}
+
+fn create_request(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier, Empty>,
+    amount: u128,
+) {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("proposer0000", &[]),
+        ExecuteMsg::CreateRequest {
+            title: "grant".to_string(),
+            description: "community grant".to_string(),
+            beneficiary: "grantee0000".to_string(),
+            amount: Uint128::from(amount),
+        },
+    )
+    .unwrap();
+}
+
+fn stake_msg(staker: &str, request_id: u64, amount: u128) -> ExecuteMsg {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: staker.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&Cw20HookMsg::StakeSupport { request_id }).unwrap(),
+    })
+}
+
+fn env_after(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(seconds);
+    env
+}
+
+#[test]
+fn proper_initialization() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        decay: Decimal::one(),
+        ..init_msg()
+    };
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidParameters {});
+
+    let msg = InstantiateMsg {
+        funding_asset: AssetInfo::Token {
+            contract_addr: "lopo0000".to_string(),
+        },
+        ..init_msg()
+    };
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::FundingAssetIsLopo {});
+}
+
+#[test]
+fn conviction_passes_request() {
+    let mut deps = mock_conviction();
+    create_request(&mut deps, 1000);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("token0000", &[]),
+        stake_msg("addr0000", 1, 1000),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("lopo0000", &[]),
+        stake_msg("addr0000", 1, 1000),
+    )
+    .unwrap();
+
+    // asking for 10% of the funds: 3000 * 0.02 / (0.1 * 0.4^2) = 3750
+    let res = query_request(deps.as_ref(), env_after(4), 1).unwrap();
+    assert_eq!(res.threshold, Some(Uint128::from(3750u128)));
+    // 1000 * (1 - 0.9^4) / 0.1
+    assert_eq!(res.conviction, Uint128::from(3439u128));
+    let err = execute(
+        deps.as_mut(),
+        env_after(4),
+        mock_info("keeper0000", &[]),
+        ExecuteMsg::ExecuteRequest { request_id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ThresholdNotReached {
+            conviction: Uint128::from(3439u128)
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env_after(5),
+        mock_info("keeper0000", &[]),
+        ExecuteMsg::ExecuteRequest { request_id: 1 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "grantee0000".to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+        }))]
+    );
+    let res = query_request(deps.as_ref(), env_after(5), 1).unwrap();
+    assert_eq!(res.status, RequestStatus::Executed);
+    assert_eq!(res.conviction, Uint128::from(4095u128));
+
+    // supporters get their lopo back once the request passed
+    let res = execute(
+        deps.as_mut(),
+        env_after(6),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::WithdrawSupport {
+            request_id: 1,
+            amount: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "lopo0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    let res = query_support(deps.as_ref(), 1, "addr0000".to_string()).unwrap();
+    assert_eq!(res.amount, Uint128::zero());
+}
+
+#[test]
+fn request_above_max_ratio() {
+    let mut deps = mock_conviction();
+    create_request(&mut deps, 5000);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("lopo0000", &[]),
+        stake_msg("addr0001", 1, 2000),
+    )
+    .unwrap();
+    let res = query_request(deps.as_ref(), env_after(1000), 1).unwrap();
+    assert_eq!(res.threshold, None);
+    assert_eq!(res.status, RequestStatus::Open);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::CancelRequest { request_id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("proposer0000", &[]),
+        ExecuteMsg::CancelRequest { request_id: 1 },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("lopo0000", &[]),
+        stake_msg("addr0000", 1, 1000),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::RequestNotOpen {});
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::WithdrawSupport {
+            request_id: 1,
+            amount: Some(Uint128::from(2001u128)),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientSupport {
+            staked: Uint128::from(2000u128)
+        }
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::WithdrawSupport {
+            request_id: 1,
+            amount: Some(Uint128::from(500u128)),
+        },
+    )
+    .unwrap();
+    let res = query_support(deps.as_ref(), 1, "addr0001".to_string()).unwrap();
+    assert_eq!(res.amount, Uint128::from(1500u128));
+}
@@ -0,0 +1,193 @@
+use crate::error::ContractError;
+use crate::state::{Config, CONFIG, PROPOSAL_MODULES};
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use cosmwasm_std::{
+    to_binary, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Order, Response,
+    StdResult, Storage,
+};
+use cw2::set_contract_version;
+use cw_storage_plus::Bound;
+use loopswap::asset::{Asset, AssetInfo};
+use loopswap::dao::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, ProposalModuleResponse,
+    ProposalModuleStatus, ProposalModulesResponse, QueryMsg, TreasuryResponse,
+};
+
+const CONTRACT_NAME: &str = "crates.io:loopswap-dao";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut<Empty>,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    CONFIG.save(deps.storage, &Config { name: msg.name })?;
+    for module in msg.proposal_modules {
+        let module = deps.api.addr_validate(&module)?;
+        PROPOSAL_MODULES.save(deps.storage, &module, &ProposalModuleStatus::Enabled)?;
+    }
+    assert_enabled_proposal_module(deps.storage)?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut<Empty>,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::ExecuteProposalHook { msgs } => execute_proposal_hook(deps, info, msgs),
+        ExecuteMsg::UpdateProposalModules { to_add, to_disable } => {
+            update_proposal_modules(deps, env, info, to_add, to_disable)
+        }
+        ExecuteMsg::UpdateConfig { name } => update_config(deps, env, info, name),
+    }
+}
+
+pub fn execute_proposal_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    msgs: Vec<CosmosMsg<Empty>>,
+) -> Result<Response, ContractError> {
+    let status = PROPOSAL_MODULES.may_load(deps.storage, &info.sender)?;
+    if status != Some(ProposalModuleStatus::Enabled) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "execute_proposal_hook")
+        .add_attribute("proposal_module", info.sender))
+}
+
+pub fn update_proposal_modules(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    to_add: Vec<String>,
+    to_disable: Vec<String>,
+) -> Result<Response, ContractError> {
+    // Changes to the DAO itself have to go through a proposal
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for module in to_add {
+        let module = deps.api.addr_validate(&module)?;
+        PROPOSAL_MODULES.save(deps.storage, &module, &ProposalModuleStatus::Enabled)?;
+    }
+    for module in to_disable {
+        let module = deps.api.addr_validate(&module)?;
+        if PROPOSAL_MODULES.has(deps.storage, &module) {
+            PROPOSAL_MODULES.save(deps.storage, &module, &ProposalModuleStatus::Disabled)?;
+        }
+    }
+    assert_enabled_proposal_module(deps.storage)?;
+
+    Ok(Response::new().add_attribute("action", "update_proposal_modules"))
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    CONFIG.save(deps.storage, &Config { name })?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+/// Without an enabled module nothing could be executed through the DAO anymore
+fn assert_enabled_proposal_module(storage: &dyn Storage) -> Result<(), ContractError> {
+    let enabled = PROPOSAL_MODULES
+        .range(storage, None, None, Order::Ascending)
+        .any(|item| matches!(item, Ok((_, ProposalModuleStatus::Enabled))));
+    if !enabled {
+        return Err(ContractError::NoEnabledProposalModule {});
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps<Empty>, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Config {} => Ok(to_binary(&query_config(deps)?)?),
+        QueryMsg::ProposalModules { start_after, limit } => Ok(to_binary(
+            &query_proposal_modules(deps, start_after, limit)?,
+        )?),
+        QueryMsg::Treasury { asset_infos } => {
+            Ok(to_binary(&query_treasury(deps, env, asset_infos)?)?)
+        }
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse { name: config.name })
+}
+
+pub fn query_proposal_modules(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ProposalModulesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let proposal_modules = PROPOSAL_MODULES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (address, status) = item?;
+            Ok(ProposalModuleResponse {
+                address: address.to_string(),
+                status,
+            })
+        })
+        .collect::<StdResult<Vec<ProposalModuleResponse>>>()?;
+
+    Ok(ProposalModulesResponse { proposal_modules })
+}
+
+pub fn query_treasury(
+    deps: Deps,
+    env: Env,
+    asset_infos: Vec<AssetInfo>,
+) -> StdResult<TreasuryResponse> {
+    let balances = asset_infos
+        .into_iter()
+        .map(|info| {
+            let amount = info.query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+            Ok(Asset { info, amount })
+        })
+        .collect::<StdResult<Vec<Asset>>>()?;
+
+    Ok(TreasuryResponse { balances })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
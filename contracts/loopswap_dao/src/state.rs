@@ -0,0 +1,13 @@
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+use loopswap::dao::ProposalModuleStatus;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const PROPOSAL_MODULES: Map<&Addr, ProposalModuleStatus> = Map::new("proposal_modules");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Config {
+    pub name: String,
+}
@@ -0,0 +1,206 @@
+use crate::contract::{execute, instantiate, query_proposal_modules, query_treasury};
+use crate::error::ContractError;
+use loopswap::mock_querier::{mock_dependencies, WasmMockQuerier};
+
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    to_binary, BankMsg, Coin, CosmosMsg, Empty, OwnedDeps, SubMsg, Uint128, WasmMsg,
+};
+use loopswap::asset::{Asset, AssetInfo};
+use loopswap::dao::{ExecuteMsg, InstantiateMsg, ProposalModuleResponse, ProposalModuleStatus};
+use loopswap::factory::ExecuteMsg as FactoryExecuteMsg;
+
+fn mock_dao() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier, Empty> {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(500u128),
+    }]);
+
+    let msg = InstantiateMsg {
+        name: "loopswap".to_string(),
+        proposal_modules: vec!["single0000".to_string(), "multiple0000".to_string()],
+    };
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator0000", &[]),
+        msg,
+    )
+    .unwrap();
+
+    deps
+}
+
+fn module(address: &str, status: ProposalModuleStatus) -> ProposalModuleResponse {
+    ProposalModuleResponse {
+        address: address.to_string(),
+        status,
+    }
+}
+
+#[test]
+fn proper_initialization() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        name: "loopswap".to_string(),
+        proposal_modules: vec![],
+    };
+    let err = instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator0000", &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoEnabledProposalModule {});
+}
+
+#[test]
+fn only_enabled_modules_execute() {
+    let mut deps = mock_dao();
+
+    let msgs = vec![CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "factory0000".to_string(),
+        msg: to_binary(&FactoryExecuteMsg::UpdateConfig {
+            owner: Some("new_owner0000".to_string()),
+            token_code_id: None,
+            pair_code_id: None,
+            admin: None,
+            lbp_code_id: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    })];
+    let hook = ExecuteMsg::ExecuteProposalHook { msgs: msgs.clone() };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        hook.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("single0000", &[]),
+        hook.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(msgs[0].clone())]);
+
+    // module changes have to come from the DAO itself
+    let update = ExecuteMsg::UpdateProposalModules {
+        to_add: vec!["single0001".to_string()],
+        to_disable: vec!["single0000".to_string()],
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("single0000", &[]),
+        update.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        update,
+    )
+    .unwrap();
+
+    let res = query_proposal_modules(deps.as_ref(), None, None).unwrap();
+    assert_eq!(
+        res.proposal_modules,
+        vec![
+            module("multiple0000", ProposalModuleStatus::Enabled),
+            module("single0000", ProposalModuleStatus::Disabled),
+            module("single0001", ProposalModuleStatus::Enabled),
+        ]
+    );
+    let res =
+        query_proposal_modules(deps.as_ref(), Some("multiple0000".to_string()), Some(1)).unwrap();
+    assert_eq!(
+        res.proposal_modules,
+        vec![module("single0000", ProposalModuleStatus::Disabled)]
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("single0000", &[]),
+        hook,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // the last enabled modules can't be disabled
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::UpdateProposalModules {
+            to_add: vec![],
+            to_disable: vec!["single0001".to_string(), "multiple0000".to_string()],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoEnabledProposalModule {});
+}
+
+#[test]
+fn treasury_spend() {
+    let mut deps = mock_dao();
+    deps.querier.with_token_balances(&[(
+        &"loop0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000u128))],
+    )]);
+
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    let loop_token = AssetInfo::Token {
+        contract_addr: "loop0000".to_string(),
+    };
+    let res = query_treasury(
+        deps.as_ref(),
+        mock_env(),
+        vec![uusd.clone(), loop_token.clone()],
+    )
+    .unwrap();
+    assert_eq!(
+        res.balances,
+        vec![
+            Asset {
+                info: uusd,
+                amount: Uint128::from(500u128),
+            },
+            Asset {
+                info: loop_token,
+                amount: Uint128::from(1000u128),
+            },
+        ]
+    );
+
+    let spend = CosmosMsg::Bank(BankMsg::Send {
+        to_address: "grantee0000".to_string(),
+        amount: vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(200u128),
+        }],
+    });
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("multiple0000", &[]),
+        ExecuteMsg::ExecuteProposalHook {
+            msgs: vec![spend.clone()],
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(spend)]);
+}
@@ -0,0 +1,469 @@
+use crate::error::ContractError;
+use crate::state::{Config, Schedule, CONFIG, OWNER_SCHEDULES, SCHEDULES, SCHEDULE_COUNT};
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, Response, StdResult, Uint128, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::Bound;
+use loopswap::asset::{Asset, AssetInfo};
+use loopswap::dca::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    ScheduleResponse, SchedulesResponse,
+};
+use loopswap::pair::{Cw20HookMsg as PairCw20HookMsg, ExecuteMsg as PairExecuteMsg};
+use loopswap::querier::query_pair_info;
+
+const CONTRACT_NAME: &str = "crates.io:loopswap-dca";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const MAX_KEEPER_TIP_RATE: u64 = 1; // percent
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut<Empty>,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if msg.keeper_tip_rate > Decimal::percent(MAX_KEEPER_TIP_RATE) {
+        return Err(ContractError::InvalidKeeperTipRate {});
+    }
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: info.sender,
+            loopswap_factory: deps.api.addr_validate(&msg.loopswap_factory)?,
+            keeper_tip_rate: msg.keeper_tip_rate,
+        },
+    )?;
+    SCHEDULE_COUNT.save(deps.storage, &0u64)?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut<Empty>,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::CreateSchedule {
+            budget,
+            ask_asset_info,
+            amount_per_swap,
+            interval,
+            belief_price,
+            max_spread,
+            start_time,
+        } => {
+            if !budget.is_native_token() {
+                return Err(ContractError::Unauthorized {});
+            }
+            budget.assert_sent_native_token_balance(&info)?;
+
+            create_schedule(
+                deps,
+                env,
+                info.sender,
+                budget,
+                ask_asset_info,
+                amount_per_swap,
+                interval,
+                belief_price,
+                max_spread,
+                start_time,
+            )
+        }
+        ExecuteMsg::ExecuteSchedule { id } => execute_schedule(deps, env, info, id),
+        ExecuteMsg::CancelSchedule { id } => cancel_schedule(deps, info, id),
+        ExecuteMsg::UpdateSchedule {
+            id,
+            belief_price,
+            max_spread,
+        } => update_schedule(deps, info, id, belief_price, max_spread),
+        ExecuteMsg::UpdateConfig {
+            owner,
+            loopswap_factory,
+            keeper_tip_rate,
+        } => update_config(deps, info, owner, loopswap_factory, keeper_tip_rate),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut<Empty>,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::CreateSchedule {
+            ask_asset_info,
+            amount_per_swap,
+            interval,
+            belief_price,
+            max_spread,
+            start_time,
+        } => {
+            let owner = deps.api.addr_validate(&cw20_msg.sender)?;
+            create_schedule(
+                deps,
+                env,
+                owner,
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: info.sender.to_string(),
+                    },
+                    amount: cw20_msg.amount,
+                },
+                ask_asset_info,
+                amount_per_swap,
+                interval,
+                belief_price,
+                max_spread,
+                start_time,
+            )
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_schedule(
+    deps: DepsMut,
+    env: Env,
+    owner: Addr,
+    budget: Asset,
+    ask_asset_info: AssetInfo,
+    amount_per_swap: Uint128,
+    interval: u64,
+    belief_price: Decimal,
+    max_spread: Decimal,
+    start_time: Option<u64>,
+) -> Result<Response, ContractError> {
+    if budget.amount.is_zero() || amount_per_swap.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    if budget.info.equal(&ask_asset_info) {
+        return Err(ContractError::SameAsset {});
+    }
+    if interval == 0 || amount_per_swap > budget.amount || belief_price.is_zero() {
+        return Err(ContractError::InvalidSchedule {});
+    }
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let pair_info = query_pair_info(
+        &deps.querier,
+        config.loopswap_factory,
+        &[budget.info.clone(), ask_asset_info.clone()],
+    )?;
+
+    let id = SCHEDULE_COUNT.load(deps.storage)? + 1;
+    SCHEDULE_COUNT.save(deps.storage, &id)?;
+
+    let next_execution_time = start_time.unwrap_or_default().max(env.block.time.seconds());
+    SCHEDULES.save(
+        deps.storage,
+        id,
+        &Schedule {
+            owner: owner.clone(),
+            offer_asset_info: budget.info.clone(),
+            ask_asset_info,
+            pair_contract: deps.api.addr_validate(&pair_info.contract_addr)?,
+            remaining: budget.amount,
+            amount_per_swap,
+            interval,
+            belief_price,
+            max_spread,
+            next_execution_time,
+            executions: 0,
+        },
+    )?;
+    OWNER_SCHEDULES.save(deps.storage, (&owner, id), &true)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "create_schedule"),
+        ("schedule_id", &id.to_string()),
+        ("owner", owner.as_str()),
+        ("budget", &budget.to_string()),
+        ("amount_per_swap", &amount_per_swap.to_string()),
+        ("next_execution_time", &next_execution_time.to_string()),
+    ]))
+}
+
+/// Swaps one installment of a due schedule, the output goes straight to the schedule owner
+/// and the keeper is paid its tip out of the installment
+pub fn execute_schedule(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut schedule = SCHEDULES.load(deps.storage, id)?;
+
+    let now = env.block.time.seconds();
+    if now < schedule.next_execution_time {
+        return Err(ContractError::ScheduleNotDue {
+            next_execution_time: schedule.next_execution_time,
+        });
+    }
+
+    let installment = schedule.amount_per_swap.min(schedule.remaining);
+    let tip_amount = installment * config.keeper_tip_rate;
+    let offer_asset = Asset {
+        info: schedule.offer_asset_info.clone(),
+        amount: installment.checked_sub(tip_amount)?,
+    };
+
+    schedule.remaining = schedule.remaining.checked_sub(installment)?;
+    schedule.executions += 1;
+    // missed intervals are skipped rather than swapped all at once
+    schedule.next_execution_time =
+        now + schedule.interval - (now - schedule.next_execution_time) % schedule.interval;
+    if schedule.remaining.is_zero() {
+        SCHEDULES.remove(deps.storage, id);
+        OWNER_SCHEDULES.remove(deps.storage, (&schedule.owner, id));
+    } else {
+        SCHEDULES.save(deps.storage, id, &schedule)?;
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![swap_msg(&schedule, offer_asset.clone())?];
+    if !tip_amount.is_zero() {
+        messages.push(
+            Asset {
+                info: schedule.offer_asset_info.clone(),
+                amount: tip_amount,
+            }
+            .into_msg(info.sender.clone())?,
+        );
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "execute_schedule"),
+        ("schedule_id", &id.to_string()),
+        ("keeper", info.sender.as_str()),
+        ("offer_amount", &offer_asset.amount.to_string()),
+        ("tip_amount", &tip_amount.to_string()),
+        ("remaining", &schedule.remaining.to_string()),
+    ]))
+}
+
+fn swap_msg(schedule: &Schedule, offer_asset: Asset) -> StdResult<CosmosMsg> {
+    match &offer_asset.info {
+        AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: schedule.pair_contract.to_string(),
+            funds: vec![Coin {
+                denom: denom.to_string(),
+                amount: offer_asset.amount,
+            }],
+            msg: to_binary(&PairExecuteMsg::Swap {
+                offer_asset,
+                belief_price: Some(schedule.belief_price),
+                max_spread: Some(schedule.max_spread),
+                to: Some(schedule.owner.to_string()),
+                deadline: None,
+                referral: None,
+            })?,
+        })),
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: schedule.pair_contract.to_string(),
+                amount: offer_asset.amount,
+                msg: to_binary(&PairCw20HookMsg::Swap {
+                    belief_price: Some(schedule.belief_price),
+                    max_spread: Some(schedule.max_spread),
+                    to: Some(schedule.owner.to_string()),
+                    deadline: None,
+                    referral: None,
+                })?,
+            })?,
+        })),
+    }
+}
+
+pub fn cancel_schedule(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let schedule = SCHEDULES.load(deps.storage, id)?;
+    if schedule.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    SCHEDULES.remove(deps.storage, id);
+    OWNER_SCHEDULES.remove(deps.storage, (&schedule.owner, id));
+
+    let refund = Asset {
+        info: schedule.offer_asset_info,
+        amount: schedule.remaining,
+    };
+    Ok(Response::new()
+        .add_message(refund.clone().into_msg(schedule.owner)?)
+        .add_attributes(vec![
+            ("action", "cancel_schedule"),
+            ("schedule_id", &id.to_string()),
+            ("refund", &refund.to_string()),
+        ]))
+}
+
+pub fn update_schedule(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let mut schedule = SCHEDULES.load(deps.storage, id)?;
+    if schedule.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(belief_price) = belief_price {
+        if belief_price.is_zero() {
+            return Err(ContractError::InvalidSchedule {});
+        }
+        schedule.belief_price = belief_price;
+    }
+    if let Some(max_spread) = max_spread {
+        schedule.max_spread = max_spread;
+    }
+    SCHEDULES.save(deps.storage, id, &schedule)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_schedule"),
+        ("schedule_id", &id.to_string()),
+        ("belief_price", &schedule.belief_price.to_string()),
+        ("max_spread", &schedule.max_spread.to_string()),
+    ]))
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    loopswap_factory: Option<String>,
+    keeper_tip_rate: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+    if let Some(loopswap_factory) = loopswap_factory {
+        config.loopswap_factory = deps.api.addr_validate(&loopswap_factory)?;
+    }
+    if let Some(keeper_tip_rate) = keeper_tip_rate {
+        if keeper_tip_rate > Decimal::percent(MAX_KEEPER_TIP_RATE) {
+            return Err(ContractError::InvalidKeeperTipRate {});
+        }
+        config.keeper_tip_rate = keeper_tip_rate;
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps<Empty>, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Config {} => Ok(to_binary(&query_config(deps)?)?),
+        QueryMsg::Schedule { id } => Ok(to_binary(&query_schedule(deps, id)?)?),
+        QueryMsg::Schedules {
+            owner,
+            start_after,
+            limit,
+        } => Ok(to_binary(&query_schedules(
+            deps,
+            owner,
+            start_after,
+            limit,
+        )?)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: config.owner.to_string(),
+        loopswap_factory: config.loopswap_factory.to_string(),
+        keeper_tip_rate: config.keeper_tip_rate,
+    })
+}
+
+pub fn query_schedule(deps: Deps, id: u64) -> StdResult<ScheduleResponse> {
+    let schedule = SCHEDULES.load(deps.storage, id)?;
+    Ok(to_schedule_response(id, schedule))
+}
+
+pub fn query_schedules(
+    deps: Deps,
+    owner: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<SchedulesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let ids: Vec<u64> = match owner {
+        Some(owner) => {
+            let owner = deps.api.addr_validate(&owner)?;
+            OWNER_SCHEDULES
+                .prefix(&owner)
+                .keys(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .collect::<StdResult<Vec<u64>>>()?
+        }
+        None => SCHEDULES
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<u64>>>()?,
+    };
+
+    let schedules = ids
+        .into_iter()
+        .map(|id| Ok(to_schedule_response(id, SCHEDULES.load(deps.storage, id)?)))
+        .collect::<StdResult<Vec<ScheduleResponse>>>()?;
+
+    Ok(SchedulesResponse { schedules })
+}
+
+fn to_schedule_response(id: u64, schedule: Schedule) -> ScheduleResponse {
+    ScheduleResponse {
+        id,
+        owner: schedule.owner.to_string(),
+        offer_asset_info: schedule.offer_asset_info,
+        ask_asset_info: schedule.ask_asset_info,
+        remaining: schedule.remaining,
+        amount_per_swap: schedule.amount_per_swap,
+        interval: schedule.interval,
+        belief_price: schedule.belief_price,
+        max_spread: schedule.max_spread,
+        next_execution_time: schedule.next_execution_time,
+        executions: schedule.executions,
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
@@ -0,0 +1,29 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Offer and ask asset are the same")]
+    SameAsset {},
+
+    #[error("Invalid schedule")]
+    InvalidSchedule {},
+
+    #[error("Keeper tip rate is too high")]
+    InvalidKeeperTipRate {},
+
+    #[error("Schedule is not due until {next_execution_time}")]
+    ScheduleNotDue { next_execution_time: u64 },
+}
@@ -0,0 +1,35 @@
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw_storage_plus::{Item, Map};
+use loopswap::asset::AssetInfo;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const SCHEDULE_COUNT: Item<u64> = Item::new("schedule_count");
+pub const SCHEDULES: Map<u64, Schedule> = Map::new("schedules");
+/// Schedule ids of an owner
+pub const OWNER_SCHEDULES: Map<(&Addr, u64), bool> = Map::new("owner_schedules");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Config {
+    pub owner: Addr,
+    pub loopswap_factory: Addr,
+    pub keeper_tip_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Schedule {
+    pub owner: Addr,
+    pub offer_asset_info: AssetInfo,
+    pub ask_asset_info: AssetInfo,
+    /// Pair found through the factory when the schedule was created
+    pub pair_contract: Addr,
+    pub remaining: Uint128,
+    pub amount_per_swap: Uint128,
+    pub interval: u64,
+    /// Price limit of every swap, in offer units per ask unit
+    pub belief_price: Decimal,
+    pub max_spread: Decimal,
+    pub next_execution_time: u64,
+    pub executions: u64,
+}
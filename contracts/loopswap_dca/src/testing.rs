@@ -0,0 +1,335 @@
+use crate::contract::{execute, instantiate, query_schedule, query_schedules};
+use crate::error::ContractError;
+use loopswap::mock_querier::{mock_dependencies, WasmMockQuerier};
+
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{
+    attr, to_binary, BankMsg, Coin, CosmosMsg, Decimal, Empty, OwnedDeps, StdError, SubMsg,
+    Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use loopswap::asset::{Asset, AssetInfo, PairInfo};
+use loopswap::dca::{Cw20HookMsg, ExecuteMsg, InstantiateMsg};
+use loopswap::pair::{Cw20HookMsg as PairCw20HookMsg, ExecuteMsg as PairExecuteMsg};
+
+const DAY: u64 = 86400;
+
+fn uusd() -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    }
+}
+
+fn loop_token() -> AssetInfo {
+    AssetInfo::Token {
+        contract_addr: "loop0000".to_string(),
+    }
+}
+
+fn mock_dca() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier, Empty> {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_terraswap_pairs(&[(
+        &"uusdloop0000".to_string(),
+        &PairInfo {
+            asset_infos: [uusd(), loop_token()],
+            contract_addr: "pair0000".to_string(),
+            liquidity_token: "liquidity0000".to_string(),
+            asset_decimals: [6u8, 6u8],
+        },
+    )]);
+
+    let msg = InstantiateMsg {
+        loopswap_factory: "factory0000".to_string(),
+        keeper_tip_rate: Decimal::permille(1),
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+
+    deps
+}
+
+#[test]
+fn proper_initialization() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        loopswap_factory: "factory0000".to_string(),
+        keeper_tip_rate: Decimal::percent(2),
+    };
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidKeeperTipRate {});
+}
+
+#[test]
+fn native_schedule_executes_and_cancels() {
+    let mut deps = mock_dca();
+    let env = mock_env();
+    let now = env.block.time.seconds();
+
+    let msg = ExecuteMsg::CreateSchedule {
+        budget: Asset {
+            info: uusd(),
+            amount: Uint128::from(250_000_000u128),
+        },
+        ask_asset_info: loop_token(),
+        amount_per_swap: Uint128::from(100_000_000u128),
+        interval: DAY,
+        belief_price: Decimal::from_ratio(2u128, 1u128),
+        max_spread: Decimal::percent(1),
+        start_time: None,
+    };
+
+    // the budget must be escrowed
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Native token balance mismatch between the argument and the transferred"
+        ))
+    );
+
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(250_000_000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(res.attributes[1], attr("schedule_id", "1"));
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper0000", &[]),
+        ExecuteMsg::ExecuteSchedule { id: 1 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "pair0000".to_string(),
+                funds: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(99_900_000u128),
+                }],
+                msg: to_binary(&PairExecuteMsg::Swap {
+                    offer_asset: Asset {
+                        info: uusd(),
+                        amount: Uint128::from(99_900_000u128),
+                    },
+                    belief_price: Some(Decimal::from_ratio(2u128, 1u128)),
+                    max_spread: Some(Decimal::percent(1)),
+                    to: Some("addr0000".to_string()),
+                    deadline: None,
+                    referral: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "keeper0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(100_000u128),
+                }],
+            })),
+        ]
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper0000", &[]),
+        ExecuteMsg::ExecuteSchedule { id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ScheduleNotDue {
+            next_execution_time: now + DAY
+        }
+    );
+
+    // a missed day is skipped, not swapped twice
+    let mut later = env.clone();
+    later.block.time = later.block.time.plus_seconds(DAY * 5 / 2);
+    execute(
+        deps.as_mut(),
+        later,
+        mock_info("keeper0000", &[]),
+        ExecuteMsg::ExecuteSchedule { id: 1 },
+    )
+    .unwrap();
+    let schedule = query_schedule(deps.as_ref(), 1).unwrap();
+    assert_eq!(schedule.remaining, Uint128::from(50_000_000u128));
+    assert_eq!(schedule.executions, 2);
+    assert_eq!(schedule.next_execution_time, now + 3 * DAY);
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper0000", &[]),
+        ExecuteMsg::CancelSchedule { id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CancelSchedule { id: 1 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(50_000_000u128),
+            }],
+        }))]
+    );
+    assert!(query_schedule(deps.as_ref(), 1).is_err());
+}
+
+#[test]
+fn token_schedule_swaps_through_send() {
+    let mut deps = mock_dca();
+    let env = mock_env();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1_500u128),
+        msg: to_binary(&Cw20HookMsg::CreateSchedule {
+            ask_asset_info: uusd(),
+            amount_per_swap: Uint128::from(1_000u128),
+            interval: DAY,
+            belief_price: Decimal::percent(50),
+            max_spread: Decimal::percent(2),
+            start_time: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), env.clone(), mock_info("loop0000", &[]), msg).unwrap();
+
+    let schedules = query_schedules(deps.as_ref(), Some("addr0000".to_string()), None, None)
+        .unwrap()
+        .schedules;
+    assert_eq!(schedules.len(), 1);
+    assert_eq!(schedules[0].offer_asset_info, loop_token());
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper0000", &[]),
+        ExecuteMsg::ExecuteSchedule { id: 1 },
+    )
+    .unwrap();
+
+    // the last installment is what is left of the budget
+    let mut later = env;
+    later.block.time = later.block.time.plus_seconds(DAY);
+    let res = execute(
+        deps.as_mut(),
+        later,
+        mock_info("keeper0000", &[]),
+        ExecuteMsg::ExecuteSchedule { id: 1 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "loop0000".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "pair0000".to_string(),
+                amount: Uint128::from(500u128),
+                msg: to_binary(&PairCw20HookMsg::Swap {
+                    belief_price: Some(Decimal::percent(50)),
+                    max_spread: Some(Decimal::percent(2)),
+                    to: Some("addr0000".to_string()),
+                    deadline: None,
+                    referral: None,
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+        }))
+    );
+    // no tip is left on such a small installment
+    assert_eq!(res.messages.len(), 1);
+
+    // the finished schedule is removed
+    let schedules = query_schedules(deps.as_ref(), Some("addr0000".to_string()), None, None)
+        .unwrap()
+        .schedules;
+    assert!(schedules.is_empty());
+}
+
+#[test]
+fn owner_updates_price_limit() {
+    let mut deps = mock_dca();
+    let env = mock_env();
+
+    let create_msg = |belief_price: Decimal| ExecuteMsg::CreateSchedule {
+        budget: Asset {
+            info: uusd(),
+            amount: Uint128::from(1_000u128),
+        },
+        ask_asset_info: loop_token(),
+        amount_per_swap: Uint128::from(100u128),
+        interval: DAY,
+        belief_price,
+        max_spread: Decimal::percent(1),
+        start_time: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1_000u128),
+        }],
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        create_msg(Decimal::zero()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidSchedule {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        create_msg(Decimal::from_ratio(2u128, 1u128)),
+    )
+    .unwrap();
+
+    let update_msg = ExecuteMsg::UpdateSchedule {
+        id: 1,
+        belief_price: Some(Decimal::from_ratio(3u128, 1u128)),
+        max_spread: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper0000", &[]),
+        update_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(deps.as_mut(), env, mock_info("addr0000", &[]), update_msg).unwrap();
+
+    let schedule = query_schedule(deps.as_ref(), 1).unwrap();
+    assert_eq!(schedule.belief_price, Decimal::from_ratio(3u128, 1u128));
+    assert_eq!(schedule.max_spread, Decimal::percent(1));
+}
@@ -1,21 +1,31 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Reply, ReplyOn,
-    Response, StdError, StdResult, SubMsg, WasmMsg, Uint128,
+    to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Reply,
+    ReplyOn, Response, StdError, StdResult, SubMsg, SubMsgResult, WasmMsg, Uint128,
 };
-use loopswap::querier::query_pair_info_from_pair;
+use loopswap::querier::{query_contract_version, query_pair_info_from_pair};
 
 use crate::response::MsgInstantiateContractResponse;
-use crate::state::{pair_key, read_pairs, Config, TmpPairInfo, CONFIG, PAIRS, TMP_PAIR_INFO};
+use crate::state::{
+    pair_key, read_lbps, read_pairs, Config, LbpInfoRaw, PairMigration, TmpLbpInfo, TmpPairInfo,
+    CONFIG, LBPS, MIGRATING_PAIRS, PAIRS, PAIR_MIGRATION, TMP_LBP_INFO, TMP_PAIR_INFO,
+};
 
 use loopswap::asset::{AssetInfo, PairInfo, PairInfoRaw};
 use loopswap::factory::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PairsResponse, QueryMsg,
+    ConfigResponse, ExecuteMsg, InstantiateMsg, LbpsResponse, MigrateMsg, PairMigrationResponse,
+    PairsResponse, QueryMsg,
 };
+use loopswap::lbp::{InstantiateMsg as LbpInstantiateMsg, LbpInfo};
 use loopswap::pair::{InstantiateMsg as PairInstantiateMsg, ExecuteMsg as PairExecuteMsg};
 use protobuf::Message;
 
+const INSTANTIATE_PAIR_REPLY_ID: u64 = 1;
+const INSTANTIATE_LBP_REPLY_ID: u64 = 2;
+/// Each pair of a MigratePairs page gets its own reply id from this base on
+const MIGRATE_PAIR_REPLY_ID_BASE: u64 = 1000;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut<Empty>,
@@ -29,6 +39,7 @@ pub fn instantiate(
         pair_code_id: msg.pair_code_id,
         admin: msg.admin,
         extra_commission_contract_addr: msg.extra_commission_contract_addr.unwrap_or_default(),
+        lbp_code_id: msg.lbp_code_id,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -49,15 +60,62 @@ pub fn execute(
             token_code_id,
             pair_code_id,
             admin,
-        } => execute_update_config(deps, env, info, owner, token_code_id, pair_code_id, admin),
+            lbp_code_id,
+        } => execute_update_config(
+            deps,
+            env,
+            info,
+            owner,
+            token_code_id,
+            pair_code_id,
+            admin,
+            lbp_code_id,
+        ),
         ExecuteMsg::CreatePair {
             asset_infos,
             is_stable_pair,
         } => execute_create_pair(deps, env, info, asset_infos, is_stable_pair),
+        ExecuteMsg::CreateLbp {
+            asset_infos,
+            owner,
+            start_time,
+            end_time,
+            start_weights,
+            end_weights,
+            commission_rate,
+        } => execute_create_lbp(
+            deps,
+            env,
+            info,
+            asset_infos,
+            owner,
+            start_time,
+            end_time,
+            start_weights,
+            end_weights,
+            commission_rate,
+        ),
+        ExecuteMsg::PauseAllPairs { start_after, limit } => {
+            execute_set_pairs_paused(deps, info, start_after, limit, true)
+        }
+        ExecuteMsg::UnpauseAllPairs { start_after, limit } => {
+            execute_set_pairs_paused(deps, info, start_after, limit, false)
+        }
+        ExecuteMsg::MigratePairs {
+            code_id,
+            msg,
+            version,
+            start_after,
+            limit,
+        } => execute_migrate_pairs(deps, info, code_id, msg, version, start_after, limit),
+        ExecuteMsg::UpdatePairAdmin { pair, admin } => {
+            execute_update_pair_admin(deps, info, pair, admin)
+        }
     }
 }
 
 // Only owner can execute it
+#[allow(clippy::too_many_arguments)]
 pub fn execute_update_config(
     deps: DepsMut<Empty>,
     _env: Env,
@@ -66,6 +124,7 @@ pub fn execute_update_config(
     token_code_id: Option<u64>,
     pair_code_id: Option<u64>,
     admin: Option<String>,
+    lbp_code_id: Option<u64>,
 ) -> StdResult<Response> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
@@ -94,6 +153,10 @@ pub fn execute_update_config(
         config.admin = admin;
     }
 
+    if let Some(lbp_code_id) = lbp_code_id {
+        config.lbp_code_id = Some(lbp_code_id);
+    }
+
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new().add_attribute("action", "update_config"))
@@ -119,10 +182,11 @@ pub fn execute_create_pair(
             Err(_) => return Err(StdError::generic_err("asset1 is invalid")),
         };
 
-    let asset_2_decimal = match asset_infos[1].query_decimals(env.contract.address, &deps.querier) {
-        Ok(decimal) => decimal,
-        Err(_) => return Err(StdError::generic_err("asset2 is invalid")),
-    };
+    let asset_2_decimal =
+        match asset_infos[1].query_decimals(env.contract.address.clone(), &deps.querier) {
+            Ok(decimal) => decimal,
+            Err(_) => return Err(StdError::generic_err("asset2 is invalid")),
+        };
 
     let raw_infos = [
         asset_infos[0].to_raw(deps.api)?,
@@ -158,12 +222,12 @@ pub fn execute_create_pair(
             ("pair", &format!("{}-{}", asset_infos[0], asset_infos[1])),
         ])
         .add_submessage(SubMsg {
-            id: 1,
+            id: INSTANTIATE_PAIR_REPLY_ID,
             gas_limit: None,
             msg: CosmosMsg::Wasm(WasmMsg::Instantiate {
                 code_id: config.pair_code_id,
                 funds: vec![],
-                admin: Some(config.admin.to_string()),
+                admin: Some(env.contract.address.to_string()),
                 label: "pair".to_string(),
                 msg: to_binary(&PairInstantiateMsg {
                     asset_infos,
@@ -178,6 +242,266 @@ pub fn execute_create_pair(
         }))
 }
 
+// Only owner can execute it, the pool is owned by `owner` or the sender
+#[allow(clippy::too_many_arguments)]
+pub fn execute_create_lbp(
+    deps: DepsMut<Empty>,
+    env: Env,
+    info: MessageInfo,
+    asset_infos: [AssetInfo; 2],
+    owner: Option<String>,
+    start_time: u64,
+    end_time: u64,
+    start_weights: [Decimal; 2],
+    end_weights: [Decimal; 2],
+    commission_rate: Decimal,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let owner = match owner {
+        Some(owner) => deps.api.addr_validate(&owner)?,
+        None => info.sender,
+    };
+
+    let lbp_code_id = match config.lbp_code_id {
+        Some(lbp_code_id) => lbp_code_id,
+        None => return Err(StdError::generic_err("lbp code id is not set")),
+    };
+
+    if asset_infos[0] == asset_infos[1] {
+        return Err(StdError::generic_err("same asset"));
+    }
+
+    let asset_1_decimal =
+        match asset_infos[0].query_decimals(env.contract.address.clone(), &deps.querier) {
+            Ok(decimal) => decimal,
+            Err(_) => return Err(StdError::generic_err("asset1 is invalid")),
+        };
+
+    let asset_2_decimal = match asset_infos[1].query_decimals(env.contract.address, &deps.querier) {
+        Ok(decimal) => decimal,
+        Err(_) => return Err(StdError::generic_err("asset2 is invalid")),
+    };
+
+    let raw_infos = [
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ];
+
+    let asset_decimals = [asset_1_decimal, asset_2_decimal];
+
+    let lbp_key = pair_key(&raw_infos);
+    if let Ok(Some(_)) = LBPS.may_load(deps.storage, &lbp_key) {
+        return Err(StdError::generic_err("Lbp already exists"));
+    }
+
+    TMP_LBP_INFO.save(
+        deps.storage,
+        &TmpLbpInfo {
+            lbp_key,
+            asset_infos: raw_infos,
+            asset_decimals,
+            start_time,
+            end_time,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "create_lbp"),
+            ("lbp", &format!("{}-{}", asset_infos[0], asset_infos[1])),
+            ("owner", owner.as_str()),
+        ])
+        .add_submessage(SubMsg {
+            id: INSTANTIATE_LBP_REPLY_ID,
+            gas_limit: None,
+            msg: CosmosMsg::Wasm(WasmMsg::Instantiate {
+                code_id: lbp_code_id,
+                funds: vec![],
+                admin: Some(config.admin),
+                label: "lbp".to_string(),
+                msg: to_binary(&LbpInstantiateMsg {
+                    asset_infos,
+                    asset_decimals,
+                    owner: owner.to_string(),
+                    start_time,
+                    end_time,
+                    start_weights,
+                    end_weights,
+                    commission_rate,
+                })?,
+            }),
+            reply_on: ReplyOn::Success,
+        }))
+}
+
+// Only owner can execute it, pairs are walked in pages of `limit`
+pub fn execute_set_pairs_paused(
+    deps: DepsMut<Empty>,
+    info: MessageInfo,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+    paused: bool,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let pairs = query_pairs(deps.as_ref(), start_after, limit)?.pairs;
+    let msg = if paused {
+        PairExecuteMsg::Pause {}
+    } else {
+        PairExecuteMsg::Unpause {}
+    };
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for pair in pairs.iter() {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: pair.contract_addr.clone(),
+            msg: to_binary(&msg)?,
+            funds: vec![],
+        }));
+    }
+
+    let last_pair = pairs
+        .last()
+        .map(|pair| format!("{}-{}", pair.asset_infos[0], pair.asset_infos[1]))
+        .unwrap_or_default();
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", if paused { "pause_all_pairs" } else { "unpause_all_pairs" }),
+        ("count", &pairs.len().to_string()),
+        ("last_pair", &last_pair),
+    ]))
+}
+
+// Only owner can execute it, the factory must be the wasm admin of the pairs
+pub fn execute_migrate_pairs(
+    deps: DepsMut<Empty>,
+    info: MessageInfo,
+    code_id: u64,
+    msg: Binary,
+    version: Option<String>,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    // the first page starts a new run, next pages continue the current one
+    let mut migration = match PAIR_MIGRATION.may_load(deps.storage)? {
+        Some(migration)
+            if start_after.is_some()
+                && migration.code_id == code_id
+                && migration.version == version =>
+        {
+            migration
+        }
+        _ => PairMigration {
+            code_id,
+            version: version.clone(),
+            migrated: 0,
+            skipped: 0,
+            failed: vec![],
+            last_pair: None,
+        },
+    };
+
+    let pairs = query_pairs(deps.as_ref(), start_after, limit)?.pairs;
+    let mut submessages: Vec<SubMsg> = vec![];
+    for pair in pairs.iter() {
+        if let Some(version) = &version {
+            // pairs that never recorded a version are always migrated
+            let pair_version = query_contract_version(
+                &deps.querier,
+                Addr::unchecked(pair.contract_addr.as_str()),
+            )
+            .unwrap_or(None);
+            if pair_version.map(|v| &v.version == version).unwrap_or(false) {
+                migration.skipped += 1;
+                continue;
+            }
+        }
+
+        let id = MIGRATE_PAIR_REPLY_ID_BASE + submessages.len() as u64;
+        MIGRATING_PAIRS.save(deps.storage, id, &pair.contract_addr)?;
+        submessages.push(SubMsg {
+            id,
+            gas_limit: None,
+            msg: CosmosMsg::Wasm(WasmMsg::Migrate {
+                contract_addr: pair.contract_addr.clone(),
+                new_code_id: code_id,
+                msg: msg.clone(),
+            }),
+            reply_on: ReplyOn::Always,
+        });
+    }
+
+    if let Some(pair) = pairs.last() {
+        migration.last_pair = Some([
+            pair.asset_infos[0].to_raw(deps.api)?,
+            pair.asset_infos[1].to_raw(deps.api)?,
+        ]);
+    }
+    PAIR_MIGRATION.save(deps.storage, &migration)?;
+
+    let last_pair = pairs
+        .last()
+        .map(|pair| format!("{}-{}", pair.asset_infos[0], pair.asset_infos[1]))
+        .unwrap_or_default();
+
+    Ok(Response::new()
+        .add_submessages(submessages.clone())
+        .add_attributes(vec![
+            ("action", "migrate_pairs"),
+            ("code_id", &code_id.to_string()),
+            ("count", &submessages.len().to_string()),
+            ("skipped", &(pairs.len() - submessages.len()).to_string()),
+            ("last_pair", &last_pair),
+        ]))
+}
+
+// Only owner can execute it, the factory must be the wasm admin of the pair
+pub fn execute_update_pair_admin(
+    deps: DepsMut<Empty>,
+    info: MessageInfo,
+    pair: String,
+    admin: String,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let pair = deps.api.addr_validate(&pair)?;
+    let admin = deps.api.addr_validate(&admin)?;
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Wasm(WasmMsg::UpdateAdmin {
+            contract_addr: pair.to_string(),
+            admin: admin.to_string(),
+        }))
+        .add_attributes(vec![
+            ("action", "update_pair_admin"),
+            ("pair", pair.as_str()),
+            ("admin", admin.as_str()),
+        ]))
+}
+
 pub fn update_extra_commission_info(
     deps: DepsMut,
     _env: Env,
@@ -205,7 +529,16 @@ pub fn update_extra_commission_info(
 
 /// This just stores the result for future query
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut<Empty>, _env: Env, msg: Reply) -> StdResult<Response> {
+pub fn reply(deps: DepsMut<Empty>, env: Env, msg: Reply) -> StdResult<Response> {
+    match msg.id {
+        INSTANTIATE_PAIR_REPLY_ID => reply_create_pair(deps, env, msg),
+        INSTANTIATE_LBP_REPLY_ID => reply_create_lbp(deps, env, msg),
+        id if id >= MIGRATE_PAIR_REPLY_ID_BASE => reply_migrate_pair(deps, msg),
+        _ => Err(StdError::generic_err("unknown reply id")),
+    }
+}
+
+fn reply_create_pair(deps: DepsMut<Empty>, _env: Env, msg: Reply) -> StdResult<Response> {
     let tmp_pair_info = TMP_PAIR_INFO.load(deps.storage)?;
 
     let res: MsgInstantiateContractResponse =
@@ -233,6 +566,54 @@ pub fn reply(deps: DepsMut<Empty>, _env: Env, msg: Reply) -> StdResult<Response>
     ]))
 }
 
+fn reply_create_lbp(deps: DepsMut<Empty>, _env: Env, msg: Reply) -> StdResult<Response> {
+    let tmp_lbp_info = TMP_LBP_INFO.load(deps.storage)?;
+
+    let res: MsgInstantiateContractResponse =
+        Message::parse_from_bytes(msg.result.unwrap().data.unwrap().as_slice()).map_err(|_| {
+            StdError::parse_err("MsgInstantiateContractResponse", "failed to parse data")
+        })?;
+
+    let lbp_contract = res.get_address();
+    LBPS.save(
+        deps.storage,
+        &tmp_lbp_info.lbp_key,
+        &LbpInfoRaw {
+            contract_addr: deps.api.addr_canonicalize(lbp_contract)?,
+            asset_infos: tmp_lbp_info.asset_infos,
+            asset_decimals: tmp_lbp_info.asset_decimals,
+            start_time: tmp_lbp_info.start_time,
+            end_time: tmp_lbp_info.end_time,
+        },
+    )?;
+
+    Ok(Response::new().add_attribute("lbp_contract_addr", lbp_contract))
+}
+
+// A failed migration is recorded instead of reverting the whole page
+fn reply_migrate_pair(deps: DepsMut<Empty>, msg: Reply) -> StdResult<Response> {
+    let pair_contract = MIGRATING_PAIRS.load(deps.storage, msg.id)?;
+    MIGRATING_PAIRS.remove(deps.storage, msg.id);
+
+    let mut migration = PAIR_MIGRATION.load(deps.storage)?;
+    let res = match msg.result {
+        SubMsgResult::Ok(_) => {
+            migration.migrated += 1;
+            Response::new().add_attribute("migrated_pair", pair_contract.as_str())
+        }
+        SubMsgResult::Err(err) => {
+            migration.failed.push(pair_contract.clone());
+            Response::new().add_attributes(vec![
+                ("failed_pair", pair_contract.as_str()),
+                ("reason", err.as_str()),
+            ])
+        }
+    };
+    PAIR_MIGRATION.save(deps.storage, &migration)?;
+
+    Ok(res)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps<Empty>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -241,6 +622,11 @@ pub fn query(deps: Deps<Empty>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Pairs { start_after, limit } => {
             to_binary(&query_pairs(deps, start_after, limit)?)
         }
+        QueryMsg::Lbp { asset_infos } => to_binary(&query_lbp(deps, asset_infos)?),
+        QueryMsg::Lbps { start_after, limit } => {
+            to_binary(&query_lbps(deps, start_after, limit)?)
+        }
+        QueryMsg::PairMigration {} => to_binary(&query_pair_migration(deps)?),
     }
 }
 
@@ -251,6 +637,7 @@ pub fn query_config(deps: Deps<Empty>) -> StdResult<ConfigResponse> {
         token_code_id: state.token_code_id,
         pair_code_id: state.pair_code_id,
         admin: state.admin,
+        lbp_code_id: state.lbp_code_id,
     };
 
     Ok(resp)
@@ -285,6 +672,53 @@ pub fn query_pairs(
     Ok(resp)
 }
 
+pub fn query_lbp(deps: Deps<Empty>, asset_infos: [AssetInfo; 2]) -> StdResult<LbpInfo> {
+    let lbp_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let lbp_info: LbpInfoRaw = LBPS.load(deps.storage, &lbp_key)?;
+    lbp_info.to_normal(deps.api)
+}
+
+pub fn query_lbps(
+    deps: Deps<Empty>,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+) -> StdResult<LbpsResponse> {
+    let start_after = if let Some(start_after) = start_after {
+        Some([
+            start_after[0].to_raw(deps.api)?,
+            start_after[1].to_raw(deps.api)?,
+        ])
+    } else {
+        None
+    };
+
+    let lbps: Vec<LbpInfo> = read_lbps(deps.storage, deps.api, start_after, limit)?;
+    Ok(LbpsResponse { lbps })
+}
+
+pub fn query_pair_migration(deps: Deps<Empty>) -> StdResult<PairMigrationResponse> {
+    let migration: PairMigration = PAIR_MIGRATION.load(deps.storage)?;
+    let last_pair = match migration.last_pair {
+        Some(last_pair) => Some([
+            last_pair[0].to_normal(deps.api)?,
+            last_pair[1].to_normal(deps.api)?,
+        ]),
+        None => None,
+    };
+
+    Ok(PairMigrationResponse {
+        code_id: migration.code_id,
+        version: migration.version,
+        migrated: migration.migrated,
+        skipped: migration.skipped,
+        failed: migration.failed,
+        last_pair,
+    })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
     Ok(Response::default())
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use cosmwasm_std::{Api, CanonicalAddr, Order, StdResult, Storage};
 use cw_storage_plus::{Bound, Item, Map};
 use loopswap::asset::{AssetInfoRaw, PairInfo, PairInfoRaw};
+use loopswap::lbp::LbpInfo;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct Config {
@@ -12,6 +13,7 @@ pub struct Config {
     pub token_code_id: u64,
     pub admin: String,
     pub extra_commission_contract_addr: String,
+    pub lbp_code_id: Option<u64>,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
@@ -27,6 +29,56 @@ pub struct TmpPairInfo {
 pub const TMP_PAIR_INFO: Item<TmpPairInfo> = Item::new("tmp_pair_info");
 pub const PAIRS: Map<&[u8], PairInfoRaw> = Map::new("pair_info");
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct TmpLbpInfo {
+    pub lbp_key: Vec<u8>,
+    pub asset_infos: [AssetInfoRaw; 2],
+    pub asset_decimals: [u8; 2],
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct LbpInfoRaw {
+    pub asset_infos: [AssetInfoRaw; 2],
+    pub contract_addr: CanonicalAddr,
+    pub asset_decimals: [u8; 2],
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+impl LbpInfoRaw {
+    pub fn to_normal(&self, api: &dyn Api) -> StdResult<LbpInfo> {
+        Ok(LbpInfo {
+            asset_infos: [
+                self.asset_infos[0].to_normal(api)?,
+                self.asset_infos[1].to_normal(api)?,
+            ],
+            contract_addr: api.addr_humanize(&self.contract_addr)?.to_string(),
+            asset_decimals: self.asset_decimals,
+            start_time: self.start_time,
+            end_time: self.end_time,
+        })
+    }
+}
+
+pub const TMP_LBP_INFO: Item<TmpLbpInfo> = Item::new("tmp_lbp_info");
+pub const LBPS: Map<&[u8], LbpInfoRaw> = Map::new("lbp_info");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PairMigration {
+    pub code_id: u64,
+    pub version: Option<String>,
+    pub migrated: u64,
+    pub skipped: u64,
+    pub failed: Vec<String>,
+    pub last_pair: Option<[AssetInfoRaw; 2]>,
+}
+
+pub const PAIR_MIGRATION: Item<PairMigration> = Item::new("pair_migration");
+/// Pair contract of each in-flight migrate submessage, keyed by reply id
+pub const MIGRATING_PAIRS: Map<u64, String> = Map::new("migrating_pairs");
+
 pub fn pair_key(asset_infos: &[AssetInfoRaw; 2]) -> Vec<u8> {
     let mut asset_infos = asset_infos.to_vec();
     asset_infos.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
@@ -70,3 +122,21 @@ fn calc_range_start(start_after: Option<[AssetInfoRaw; 2]>) -> Option<Vec<u8>> {
         v
     })
 }
+
+pub fn read_lbps(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    start_after: Option<[AssetInfoRaw; 2]>,
+    limit: Option<u32>,
+) -> StdResult<Vec<LbpInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after).map(Bound::ExclusiveRaw);
+
+    LBPS.range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, v) = item?;
+            v.to_normal(api)
+        })
+        .collect::<StdResult<Vec<LbpInfo>>>()
+}
@@ -1,18 +1,22 @@
 use crate::contract::{execute, instantiate, query, reply};
 use loopswap::mock_querier::{mock_dependencies, WasmMockQuerier};
 
-use crate::state::{pair_key, TmpPairInfo, TMP_PAIR_INFO};
+use crate::state::{pair_key, TmpPairInfo, PAIRS, TMP_PAIR_INFO};
 
 use cosmwasm_std::testing::{
     mock_dependencies_with_balance, mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR,
 };
 use cosmwasm_std::{
-    attr, coin, from_binary, to_binary, Empty, OwnedDeps, Reply, ReplyOn, StdError, SubMsg,
-    SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
+    attr, coin, from_binary, to_binary, Api, Binary, CosmosMsg, Decimal, Empty, OwnedDeps, Reply,
+    ReplyOn, StdError, SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
 };
-use loopswap::asset::{AssetInfo, PairInfo};
-use loopswap::factory::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use loopswap::pair::InstantiateMsg as PairInstantiateMsg;
+use cw2::ContractVersion;
+use loopswap::asset::{AssetInfo, PairInfo, PairInfoRaw};
+use loopswap::factory::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, PairMigrationResponse, QueryMsg,
+};
+use loopswap::lbp::InstantiateMsg as LbpInstantiateMsg;
+use loopswap::pair::{InstantiateMsg as PairInstantiateMsg, MigrateMsg as PairMigrateMsg};
 
 #[test]
 fn proper_initialization() {
@@ -21,6 +25,9 @@ fn proper_initialization() {
     let msg = InstantiateMsg {
         pair_code_id: 321u64,
         token_code_id: 123u64,
+        extra_commission_contract_addr: None,
+        admin: "admin0000".to_string(),
+        lbp_code_id: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -42,6 +49,9 @@ fn update_config() {
     let msg = InstantiateMsg {
         pair_code_id: 321u64,
         token_code_id: 123u64,
+        extra_commission_contract_addr: None,
+        admin: "admin0000".to_string(),
+        lbp_code_id: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -55,6 +65,8 @@ fn update_config() {
         owner: Some("addr0001".to_string()),
         pair_code_id: None,
         token_code_id: None,
+        admin: None,
+        lbp_code_id: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -74,6 +86,8 @@ fn update_config() {
         owner: None,
         pair_code_id: Some(100u64),
         token_code_id: Some(200u64),
+        admin: None,
+        lbp_code_id: None,
     };
 
     let res = execute(deps.as_mut(), env, info, msg).unwrap();
@@ -93,6 +107,8 @@ fn update_config() {
         owner: None,
         pair_code_id: None,
         token_code_id: None,
+        admin: None,
+        lbp_code_id: None,
     };
 
     let res = execute(deps.as_mut(), env, info, msg);
@@ -108,6 +124,9 @@ fn init(
     let msg = InstantiateMsg {
         pair_code_id: 321u64,
         token_code_id: 123u64,
+        extra_commission_contract_addr: None,
+        admin: "admin0000".to_string(),
+        lbp_code_id: None,
     };
 
     let env = mock_env();
@@ -139,6 +158,7 @@ fn create_pair() {
 
     let msg = ExecuteMsg::CreatePair {
         asset_infos: asset_infos.clone(),
+        is_stable_pair: false,
     };
 
     let env = mock_env();
@@ -161,7 +181,10 @@ fn create_pair() {
                 msg: to_binary(&PairInstantiateMsg {
                     asset_infos: asset_infos.clone(),
                     token_code_id: 123u64,
-                    asset_decimals: [6u8, 8u8]
+                    asset_decimals: [6u8, 8u8],
+                    is_stable_pair: false,
+                    extra_commission_contract_addr: "addr0000".to_string(),
+                    admin: "admin0000".to_string(),
                 })
                 .unwrap(),
                 code_id: 321u64,
@@ -183,11 +206,87 @@ fn create_pair() {
         TmpPairInfo {
             asset_infos: raw_infos.clone(),
             pair_key: pair_key(&raw_infos),
-            asset_decimals: [6u8, 8u8]
+            asset_decimals: [6u8, 8u8],
+            is_stable_pair: false,
         }
     );
 }
 
+#[test]
+fn create_lbp_only_by_owner() {
+    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
+    deps = init(deps);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateConfig {
+            owner: None,
+            token_code_id: None,
+            pair_code_id: None,
+            admin: None,
+            lbp_code_id: Some(456u64),
+        },
+    )
+    .unwrap();
+
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+    ];
+    let msg = ExecuteMsg::CreateLbp {
+        asset_infos: asset_infos.clone(),
+        owner: Some("project0000".to_string()),
+        start_time: 1_000_000,
+        end_time: 1_086_400,
+        start_weights: [Decimal::percent(96), Decimal::percent(4)],
+        end_weights: [Decimal::percent(50), Decimal::percent(50)],
+        commission_rate: Decimal::permille(3),
+    };
+
+    // a project's lbp slot can't be taken by anyone else
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("squatter0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg {
+            id: 2,
+            gas_limit: None,
+            reply_on: ReplyOn::Success,
+            msg: WasmMsg::Instantiate {
+                msg: to_binary(&LbpInstantiateMsg {
+                    asset_infos,
+                    asset_decimals: [8u8, 6u8],
+                    owner: "project0000".to_string(),
+                    start_time: 1_000_000,
+                    end_time: 1_086_400,
+                    start_weights: [Decimal::percent(96), Decimal::percent(4)],
+                    end_weights: [Decimal::percent(50), Decimal::percent(50)],
+                    commission_rate: Decimal::permille(3),
+                })
+                .unwrap(),
+                code_id: 456u64,
+                funds: vec![],
+                label: "lbp".to_string(),
+                admin: Some("admin0000".to_string()),
+            }
+            .into()
+        }]
+    );
+}
+
 #[test]
 fn create_pair_native_token_and_ibc_token() {
     let mut deps = mock_dependencies(&[
@@ -207,6 +306,7 @@ fn create_pair_native_token_and_ibc_token() {
 
     let msg = ExecuteMsg::CreatePair {
         asset_infos: asset_infos.clone(),
+        is_stable_pair: false,
     };
 
     let env = mock_env();
@@ -226,7 +326,10 @@ fn create_pair_native_token_and_ibc_token() {
                 msg: to_binary(&PairInstantiateMsg {
                     asset_infos: asset_infos.clone(),
                     token_code_id: 123u64,
-                    asset_decimals: [6u8, 6u8]
+                    asset_decimals: [6u8, 6u8],
+                    is_stable_pair: false,
+                    extra_commission_contract_addr: "addr0000".to_string(),
+                    admin: "admin0000".to_string(),
                 })
                 .unwrap(),
                 code_id: 321u64,
@@ -248,7 +351,8 @@ fn create_pair_native_token_and_ibc_token() {
         TmpPairInfo {
             asset_infos: raw_infos.clone(),
             pair_key: pair_key(&raw_infos),
-            asset_decimals: [6u8, 6u8]
+            asset_decimals: [6u8, 6u8],
+            is_stable_pair: false,
         }
     );
 }
@@ -267,7 +371,10 @@ fn fail_to_create_same_pair() {
         },
     ];
 
-    let msg = ExecuteMsg::CreatePair { asset_infos };
+    let msg = ExecuteMsg::CreatePair {
+        asset_infos,
+        is_stable_pair: false,
+    };
 
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
@@ -288,7 +395,10 @@ fn fail_to_create_pair_with_unactive_denoms() {
         },
     ];
 
-    let msg = ExecuteMsg::CreatePair { asset_infos };
+    let msg = ExecuteMsg::CreatePair {
+        asset_infos,
+        is_stable_pair: false,
+    };
 
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
@@ -309,7 +419,10 @@ fn fail_to_create_pair_with_invalid_denom() {
         },
     ];
 
-    let msg = ExecuteMsg::CreatePair { asset_infos };
+    let msg = ExecuteMsg::CreatePair {
+        asset_infos,
+        is_stable_pair: false,
+    };
 
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
@@ -323,6 +436,9 @@ fn fail_to_create_pair_with_unknown_token() {
     let msg = InstantiateMsg {
         pair_code_id: 321u64,
         token_code_id: 123u64,
+        extra_commission_contract_addr: None,
+        admin: "admin0000".to_string(),
+        lbp_code_id: None,
     };
 
     let env = mock_env();
@@ -340,7 +456,10 @@ fn fail_to_create_pair_with_unknown_token() {
         },
     ];
 
-    let msg = ExecuteMsg::CreatePair { asset_infos };
+    let msg = ExecuteMsg::CreatePair {
+        asset_infos,
+        is_stable_pair: false,
+    };
 
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
@@ -354,6 +473,9 @@ fn fail_to_create_pair_with_unknown_ibc_token() {
     let msg = InstantiateMsg {
         pair_code_id: 321u64,
         token_code_id: 123u64,
+        extra_commission_contract_addr: None,
+        admin: "admin0000".to_string(),
+        lbp_code_id: None,
     };
 
     let env = mock_env();
@@ -371,7 +493,10 @@ fn fail_to_create_pair_with_unknown_ibc_token() {
         },
     ];
 
-    let msg = ExecuteMsg::CreatePair { asset_infos };
+    let msg = ExecuteMsg::CreatePair {
+        asset_infos,
+        is_stable_pair: false,
+    };
 
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
@@ -412,6 +537,7 @@ fn reply_test() {
                 asset_infos: raw_infos,
                 pair_key,
                 asset_decimals: [8u8, 8u8],
+                is_stable_pair: false,
             },
         )
         .unwrap();
@@ -464,3 +590,210 @@ fn reply_test() {
         }
     );
 }
+
+fn store_pair(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier, Empty>,
+    asset_infos: [AssetInfo; 2],
+    contract_addr: &str,
+) {
+    let raw_infos = [
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ];
+    let pair_info = PairInfoRaw {
+        contract_addr: deps.api.addr_canonicalize(contract_addr).unwrap(),
+        liquidity_token: deps.api.addr_canonicalize("liquidity0000").unwrap(),
+        asset_infos: raw_infos.clone(),
+        asset_decimals: [6u8, 6u8],
+    };
+    PAIRS
+        .save(&mut deps.storage, &pair_key(&raw_infos), &pair_info)
+        .unwrap();
+}
+
+#[test]
+fn migrate_pairs() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    store_pair(
+        &mut deps,
+        [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        "pair0000",
+    );
+    store_pair(
+        &mut deps,
+        [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+        ],
+        "pair0001",
+    );
+    store_pair(
+        &mut deps,
+        [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0002".to_string(),
+            },
+        ],
+        "pair0002",
+    );
+
+    // pair0001 already runs the new version
+    deps.querier.with_contract_versions(&[(
+        &"pair0001".to_string(),
+        &ContractVersion {
+            contract: "crates.io:loopswap-pair".to_string(),
+            version: "1.1.0".to_string(),
+        },
+    )]);
+
+    let migrate_msg = to_binary(&PairMigrateMsg {
+        new_admin: "admin0000".to_string(),
+        new_commission_rate: "0.003".to_string(),
+    })
+    .unwrap();
+    let msg = ExecuteMsg::MigratePairs {
+        code_id: 555u64,
+        msg: migrate_msg.clone(),
+        version: Some("1.1.0".to_string()),
+        start_after: None,
+        limit: Some(2),
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    let pair_order: Vec<String> = res
+        .messages
+        .iter()
+        .map(|sub_msg| match &sub_msg.msg {
+            CosmosMsg::Wasm(WasmMsg::Migrate {
+                contract_addr,
+                new_code_id,
+                msg,
+            }) => {
+                assert_eq!(*new_code_id, 555u64);
+                assert_eq!(*msg, migrate_msg);
+                assert_eq!(sub_msg.reply_on, ReplyOn::Always);
+                contract_addr.clone()
+            }
+            _ => panic!("DO NOT ENTER HERE"),
+        })
+        .collect();
+    assert_eq!(pair_order.len(), 1);
+    assert_eq!(
+        res.attributes[..4],
+        vec![
+            attr("action", "migrate_pairs"),
+            attr("code_id", "555"),
+            attr("count", "1"),
+            attr("skipped", "1"),
+        ]
+    );
+
+    // the migration of the first page fails
+    let reply_msg = Reply {
+        id: res.messages[0].id,
+        result: SubMsgResult::Err("migrate failed".to_string()),
+    };
+    let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("failed_pair", pair_order[0].as_str()),
+            attr("reason", "migrate failed"),
+        ]
+    );
+
+    // next page continues the same run
+    let migration: PairMigrationResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::PairMigration {}).unwrap())
+            .unwrap();
+    let msg = ExecuteMsg::MigratePairs {
+        code_id: 555u64,
+        msg: migrate_msg,
+        version: Some("1.1.0".to_string()),
+        start_after: migration.last_pair,
+        limit: Some(2),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(res.messages.len(), 1);
+
+    let reply_msg = Reply {
+        id: res.messages[0].id,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    let migration: PairMigrationResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::PairMigration {}).unwrap())
+            .unwrap();
+    assert_eq!(migration.code_id, 555u64);
+    assert_eq!(migration.migrated, 1);
+    assert_eq!(migration.skipped, 1);
+    assert_eq!(migration.failed, vec![pair_order[0].clone()]);
+
+    // nothing left to migrate
+    let msg = ExecuteMsg::MigratePairs {
+        code_id: 555u64,
+        msg: Binary::default(),
+        version: Some("1.1.0".to_string()),
+        start_after: migration.last_pair,
+        limit: Some(2),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(res.messages.len(), 0);
+}
+
+#[test]
+fn update_pair_admin() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    let msg = ExecuteMsg::UpdatePairAdmin {
+        pair: "pair0000".to_string(),
+        admin: "admin0000".to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::UpdateAdmin {
+            contract_addr: "pair0000".to_string(),
+            admin: "admin0000".to_string(),
+        })]
+    );
+}
@@ -0,0 +1,99 @@
+use cosmwasm_std::{Addr, Order, QuerierWrapper, StdResult, Storage, Uint128};
+
+use loopswap::querier::{query_total_voting_power, query_voting_power};
+
+use crate::lock_tier::{get_total_weighted_stake, get_user_weighted_stake};
+use crate::state::{
+    BOOST_STAKING_CONTRACT, TOTAL_STAKED, TOTAL_WORKING_AMOUNT, USER_WORKING_AMOUNT,
+};
+
+// share of the stake that always earns rewards, the rest is earned through voting power
+const BOOST_BASE_PERCENT: u128 = 40;
+const BOOST_POWER_PERCENT: u128 = 60;
+
+/// Working amount of a staker, on stakes weighted by their lock tier:
+/// `min(weighted, 0.4 * weighted + 0.6 * total_weighted * user_power / total_power)`.
+/// Without a boost staking contract every staker works with the full weighted stake.
+pub fn compute_working_amount(
+    store: &dyn Storage,
+    querier: &QuerierWrapper,
+    user: &Addr,
+    user_weighted: Uint128,
+    total_weighted: Uint128,
+) -> StdResult<Uint128> {
+    let staking_contract = match BOOST_STAKING_CONTRACT.may_load(store)? {
+        Some(staking_contract) => staking_contract,
+        None => return Ok(user_weighted),
+    };
+    if user_weighted.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    let mut working = user_weighted.multiply_ratio(BOOST_BASE_PERCENT, 100u128);
+    let total_power = query_total_voting_power(querier, staking_contract.clone())?;
+    if !total_power.is_zero() {
+        let user_power = query_voting_power(querier, staking_contract, user.clone())?;
+        working += total_weighted
+            .multiply_ratio(BOOST_POWER_PERCENT, 100u128)
+            .multiply_ratio(user_power, total_power);
+    }
+    Ok(working.min(user_weighted))
+}
+
+/// Stakes made before boosting keep working with their full amount until updated
+pub fn get_user_working_amount(
+    store: &dyn Storage,
+    user: &Addr,
+    pool: &Addr,
+    user_staked: Uint128,
+) -> StdResult<Uint128> {
+    Ok(USER_WORKING_AMOUNT
+        .may_load(store, (user, pool))?
+        .unwrap_or(user_staked))
+}
+
+pub fn get_total_working_amount(store: &dyn Storage, pool_address: String) -> StdResult<Uint128> {
+    Ok(TOTAL_WORKING_AMOUNT
+        .may_load(store, pool_address)?
+        .unwrap_or_else(Uint128::zero))
+}
+
+/// Recomputes the working amount of the user from the current stake, lock tiers and
+/// voting power. Rewards earned with `old_working` must be settled before calling this.
+pub fn update_working_amount(
+    store: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    user: &Addr,
+    pool: &Addr,
+    old_working: Uint128,
+    user_staked: Uint128,
+) -> StdResult<Uint128> {
+    let user_weighted = get_user_weighted_stake(store, user, pool, user_staked)?;
+    let total_weighted = get_total_weighted_stake(store, pool.to_string())?;
+    let working = compute_working_amount(store, querier, user, user_weighted, total_weighted)?;
+
+    let total_working = get_total_working_amount(store, pool.to_string())? + working - old_working;
+    TOTAL_WORKING_AMOUNT.save(store, pool.to_string(), &total_working)?;
+    // a zero working amount is kept, only a position without stake falls back to it
+    if user_staked.is_zero() {
+        USER_WORKING_AMOUNT.remove(store, (user, pool));
+    } else {
+        USER_WORKING_AMOUNT.save(store, (user, pool), &working)?;
+    }
+    Ok(working)
+}
+
+/// Existing pools start with every stake working at its full amount
+pub fn init_total_working_amounts(store: &mut dyn Storage) -> StdResult<u64> {
+    let pools = TOTAL_STAKED
+        .range(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, Uint128)>>>()?;
+    let mut initialized = 0u64;
+    for (pool, total_staked) in pools {
+        if !TOTAL_WORKING_AMOUNT.has(store, pool.to_string()) {
+            TOTAL_WORKING_AMOUNT.save(store, pool, &total_staked)?;
+            initialized += 1;
+        }
+    }
+    Ok(initialized)
+}
@@ -1,35 +1,51 @@
 //use crate::response::MsgInstantiateContractResponse;
+use crate::boost::{
+    compute_working_amount, get_total_working_amount, get_user_working_amount,
+    init_total_working_amounts, update_working_amount,
+};
 use crate::state::{
-    Config, RewardInfo, CONFIG, CURRENT_POOL_ADDRESS, 
-    LIQUIDITY_TOKEN_MAP, POOL_COMPOUNDED_INDEX_MAP, POOL_LAST_DISTRIBUTION_TIME_IN_SECONDS,
-    POOL_REWARD_INDEX_MAP, POOL_TOTAL_COMPOUNDED_AMOUNT, STAKEABLE_INFOS,
+    Config, KeyMigration, RewardInfo, BOOST_STAKING_CONTRACT, CONFIG, CURRENT_POOL_ADDRESS,
+    KEY_MIGRATION, LIQUIDITY_TOKEN_MAP, LOCK_TIERS, POOL_COMPOUNDED_INDEX_MAP,
+    POOL_LAST_DISTRIBUTION_TIME_IN_SECONDS, POOL_REWARD_INDEX_MAP, POOL_STAKERS,
+    POOL_TOTAL_COMPOUNDED_AMOUNT, STAKEABLE_INFOS,
     TOTAL_ACCUMULATED_DISTRIBUTED_AMOUNT_IN_POOL_MAP, TOTAL_REWARDS_IN_POOL, TOTAL_STAKED,
     UNCLAIMED_DISTRIBUTED_TOKEN_AMOUNT_MAP, USER_AUTO_COMPOUND_SUBSCRIPTION_MAP,
     USER_COMPOUNDED_REWARD_INFO_MAP, USER_REWARD_INFO_MAP, USER_REWARD_STARTING_TIME_MAP,
-    USER_STAKED_AMOUNT,
+    USER_STAKED_AMOUNT, USER_TIER_STAKES,
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Reply,
-    ReplyOn, Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
+    from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Order, QuerierWrapper, Reply, ReplyOn, Response, StdError, StdResult, Storage, SubMsg, Uint128,
+    WasmMsg,
 };
 
+use cw2::{set_contract_version, CONTRACT};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
 
 use cw_storage_plus::Bound;
 use loopswap::asset::{StakeablePairedDistributionTokenInfo, StakeableToken};
 use loopswap::factory::MigrateMsg;
 use loopswap::farming::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, QueryRewardResponse,
-    QueryUserRewardInPoolResponse,
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, LockTier, QueryMsg, QueryRewardResponse,
+    QueryUserPositionResponse, QueryUserRewardInPoolResponse, UserLockTierResponse,
 };
 use loopswap::querier::query_token_balance;
 use loopswap::token::InstantiateMsg as TokenInstantiateMsg;
 // use protobuf::Message;
+use crate::error::ContractError;
+use crate::lock_tier::{
+    add_tier_stake, get_locked_stake, get_total_weighted_stake, get_user_weighted_stake,
+    release_expired_tier_stakes, remove_tier_stakes,
+};
+use crate::migration::{is_key_migration_done, migrate_composite_keys};
 use crate::parse_reply::parse_reply_instantiate_data;
 const REWARD_CALCULATION_DECIMAL_PRECISION: u128 = 1000000000000u128;
 const INSTANTIATE_REPLY_ID: u64 = 1;
+const CONTRACT_NAME: &str = "crates.io:loopswap-farming";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const MAX_KEY_MIGRATION_LIMIT: u32 = 500;
 
 //Initialize the contract.
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -38,7 +54,7 @@ pub fn instantiate(
     _env: Env,
     _info: MessageInfo,
     _msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config = Config {
         owner: _info.sender.to_string(),
         freeze: false,                                // freeze flag will be used to
@@ -52,12 +68,24 @@ pub fn instantiate(
         token_code_id: _msg.token_code_id,
     };
     CONFIG.save(deps.storage, &config)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     Ok(Response::new())
 }
 
 //Execute the handle messages.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    if !matches!(msg, ExecuteMsg::MigrateKeys { .. })
+        && KEY_MIGRATION.may_load(deps.storage)?.is_some()
+    {
+        return Err(ContractError::KeyMigrationPending {});
+    }
+
     match msg {
         ExecuteMsg::UpdateConfig { owner } => execute_update_config(deps, env, info, owner),
         ExecuteMsg::AddSecondOwner {
@@ -101,6 +129,9 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
         ExecuteMsg::ClaimReward { pool_address } => {
             execute_claim_reward(deps, env, info, pool_address)
         }
+        ExecuteMsg::ClaimAll { start_after, limit } => {
+            execute_claim_all(deps, env, info, start_after, limit)
+        }
 
         ExecuteMsg::UpdateLockTimeFrameForCompundReward {
             lock_time_frame_for_compound_reward,
@@ -116,6 +147,19 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
         ExecuteMsg::UpdateReserveAddress { reserve_addr } => {
             execute_update_reserve_addr(deps, info, reserve_addr)
         }
+        ExecuteMsg::UpdateBoostStakingContract { staking_contract } => {
+            execute_update_boost_staking_contract(deps, info, staking_contract)
+        }
+        ExecuteMsg::Kick { user, pool_address } => execute_kick(deps, env, user, pool_address),
+        ExecuteMsg::UpdateLockTier {
+            tier,
+            lock_period,
+            multiplier,
+        } => execute_update_lock_tier(deps, info, tier, lock_period, multiplier),
+        ExecuteMsg::RemoveLockTier { tier } => execute_remove_lock_tier(deps, info, tier),
+        ExecuteMsg::MigrateKeys { pool_dists, limit } => {
+            execute_migrate_keys(deps, info, pool_dists, limit)
+        }
     }
 }
 
@@ -125,7 +169,7 @@ pub fn execute_update_reserve_addr(
     deps: DepsMut,
     info: MessageInfo,
     reserve_addr: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
@@ -133,7 +177,7 @@ pub fn execute_update_reserve_addr(
         && (config.second_owner.is_empty()
             || (!config.second_owner.is_empty() && info.sender != config.second_owner))
     {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     config.reserve_addr = reserve_addr;
@@ -141,6 +185,92 @@ pub fn execute_update_reserve_addr(
     Ok(Response::new().add_attribute("action", "update reserve addr"))
 }
 
+/// Sets the staking contract whose voting power boosts rewards, None turns boosting off.
+/// Working amounts follow on the next stake, unstake, claim or kick of each user.
+pub fn execute_update_boost_staking_contract(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_contract: Option<String>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if info.sender != config.owner
+        && (config.second_owner.is_empty()
+            || (!config.second_owner.is_empty() && info.sender != config.second_owner))
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match staking_contract {
+        Some(staking_contract) => {
+            let staking_contract = deps.api.addr_validate(&staking_contract)?;
+            BOOST_STAKING_CONTRACT.save(deps.storage, &staking_contract)?;
+        }
+        None => BOOST_STAKING_CONTRACT.remove(deps.storage),
+    }
+    Ok(Response::new().add_attribute("action", "update_boost_staking_contract"))
+}
+
+/// Adds or replaces a lock tier stakers can pick when staking
+pub fn execute_update_lock_tier(
+    deps: DepsMut,
+    info: MessageInfo,
+    tier: u64,
+    lock_period: u64,
+    multiplier: Decimal,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if info.sender != config.owner
+        && (config.second_owner.is_empty()
+            || (!config.second_owner.is_empty() && info.sender != config.second_owner))
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+    if multiplier < Decimal::one() {
+        return Err(ContractError::InvalidLockTierMultiplier {});
+    }
+
+    LOCK_TIERS.save(
+        deps.storage,
+        tier,
+        &LockTier {
+            tier,
+            lock_period,
+            multiplier,
+        },
+    )?;
+    Ok(Response::new()
+        .add_attribute("action", "update_lock_tier")
+        .add_attribute("tier", tier.to_string()))
+}
+
+pub fn execute_remove_lock_tier(
+    deps: DepsMut,
+    info: MessageInfo,
+    tier: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if info.sender != config.owner
+        && (config.second_owner.is_empty()
+            || (!config.second_owner.is_empty() && info.sender != config.second_owner))
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !LOCK_TIERS.has(deps.storage, tier) {
+        return Err(ContractError::UnknownLockTier { tier });
+    }
+
+    LOCK_TIERS.remove(deps.storage, tier);
+    Ok(Response::new()
+        .add_attribute("action", "remove_lock_tier")
+        .add_attribute("tier", tier.to_string()))
+}
+
 /// User calls this to opt for auto compounding
 
 pub fn execute_opt_for_auto_compound(
@@ -148,16 +278,15 @@ pub fn execute_opt_for_auto_compound(
     env: Env,
     info: MessageInfo,
     pool_address: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     if !STAKEABLE_INFOS.has(deps.storage, pool_address.to_string()) {
-        return Err(StdError::generic_err("No pool found"));
+        return Err(ContractError::PoolNotFound {});
     }
 
-    let user_address = info.sender.to_string();
-    let mut user_pool_key = user_address.to_string();
-    user_pool_key.push_str(&pool_address);
+    let user = info.sender;
+    let pool = Addr::unchecked(&pool_address);
     if USER_AUTO_COMPOUND_SUBSCRIPTION_MAP
-        .may_load(deps.storage, user_pool_key.to_string())?
+        .may_load(deps.storage, (&user, &pool))?
         .unwrap_or(false)
     {
         return Ok(Response::new()
@@ -165,14 +294,11 @@ pub fn execute_opt_for_auto_compound(
             .add_attribute("response", "Already Opt for auto compounding"));
     }
 
-    let user_staked =
-        get_user_staked_amount_in_pool_from_map_storage(deps.storage, user_pool_key.to_string());
+    let user_staked = get_user_staked_amount_in_pool_from_map_storage(deps.storage, &user, &pool);
 
     //Check to allow auto compounding only when users have staked assets
     if user_staked <= Uint128::zero() {
-        return Err(StdError::generic_err(
-            "No asset staked in the pool, you need to stake asset in the pool first",
-        ));
+        return Err(ContractError::NoStakedAsset {});
     }
     //Adding amount in the total pool compounded amount
     let mut total_compounded = POOL_TOTAL_COMPOUNDED_AMOUNT
@@ -186,15 +312,12 @@ pub fn execute_opt_for_auto_compound(
 
     //Calculating compounded reward index of the pool and user compounded reward index for all distribution tokens of the pool
     for dist_tkn in stakeable_info.distribution.iter() {
-        let mut pool_dist_key = pool_address.to_string();
-        pool_dist_key.push_str(&dist_tkn.token.to_string());
-        let mut user_pool_dist_key = user_address.to_string();
-        user_pool_dist_key.push_str(&pool_dist_key);
+        let dist_token = Addr::unchecked(&dist_tkn.token);
         let current_compounded_reward_index = POOL_COMPOUNDED_INDEX_MAP
-            .may_load(deps.storage, pool_dist_key.to_string())?
+            .may_load(deps.storage, (&pool, &dist_token))?
             .unwrap_or_else(Uint128::zero);
         let mut user_compounded_reward_index = USER_COMPOUNDED_REWARD_INFO_MAP
-            .may_load(deps.storage, user_pool_dist_key.to_string())?
+            .may_load(deps.storage, (&user, &pool, &dist_token))?
             .unwrap_or_else(|| RewardInfo {
                 reward_index: Uint128::zero(),
                 pending_reward: Uint128::zero(),
@@ -204,19 +327,15 @@ pub fn execute_opt_for_auto_compound(
 
         USER_COMPOUNDED_REWARD_INFO_MAP.save(
             deps.storage,
-            user_pool_dist_key.to_string(),
+            (&user, &pool, &dist_token),
             &user_compounded_reward_index,
         )?;
     }
     //Subscribing user for auto compounding
-    USER_AUTO_COMPOUND_SUBSCRIPTION_MAP.save(deps.storage, user_pool_key.to_string(), &true)?;
+    USER_AUTO_COMPOUND_SUBSCRIPTION_MAP.save(deps.storage, (&user, &pool), &true)?;
 
     //Resetting the reward time
-    USER_REWARD_STARTING_TIME_MAP.save(
-        deps.storage,
-        user_pool_key.to_string(),
-        &env.block.time.seconds(),
-    )?;
+    USER_REWARD_STARTING_TIME_MAP.save(deps.storage, (&user, &pool), &env.block.time.seconds())?;
     Ok(Response::new()
         .add_attribute("action", "opt for auto compound")
         .add_attribute("response", "Successful"))
@@ -228,7 +347,7 @@ pub fn execute_update_lock_time_frame_for_compound_reward(
     _env: Env,
     info: MessageInfo,
     lock_time_frame_for_compound_reward: u64,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
@@ -236,7 +355,7 @@ pub fn execute_update_lock_time_frame_for_compound_reward(
         && (config.second_owner.is_empty()
             || (!config.second_owner.is_empty() && info.sender != config.second_owner))
     {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
     config.lock_time_frame_for_compound_reward = lock_time_frame_for_compound_reward;
     CONFIG.save(deps.storage, &config)?;
@@ -249,10 +368,10 @@ pub fn receive_cw20(
     env: Env,
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let pool_contract_addr = info.sender;
     match from_binary(&cw20_msg.msg) {
-        Ok(Cw20HookMsg::Stake {}) => {
+        Ok(Cw20HookMsg::Stake { lock_tier }) => {
             // compare with sent item.
             if STAKEABLE_INFOS
                 .may_load(deps.storage, pool_contract_addr.to_string())?
@@ -264,9 +383,10 @@ pub fn receive_cw20(
                     Addr::unchecked(cw20_msg.sender),
                     pool_contract_addr.to_string(),
                     cw20_msg.amount,
+                    lock_tier,
                 )
             } else {
-                Err(StdError::generic_err("Incorrect Asset Provided"))
+                Err(ContractError::IncorrectAsset {})
             }
         }
 
@@ -287,7 +407,7 @@ pub fn receive_cw20(
                     true,
                 )
             } else {
-                Err(StdError::generic_err("Incorrect Asset Provided"))
+                Err(ContractError::IncorrectAsset {})
             }
         }
         Ok(Cw20HookMsg::UnstakeWithoutClaim {}) => {
@@ -307,10 +427,10 @@ pub fn receive_cw20(
                     false,
                 )
             } else {
-                Err(StdError::generic_err("Incorrect Asset Provided"))
+                Err(ContractError::IncorrectAsset {})
             }
         }
-        Err(_err) => Err(StdError::generic_err("Unsuccessful")),
+        Err(_err) => Err(ContractError::InvalidCw20HookMsg {}),
     }
 }
 
@@ -320,22 +440,23 @@ pub fn execute_add_stakeable_token(
     env: Env,
     info: MessageInfo,
     pool_address: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
     // Permission check
     if info.sender != config.owner
         && (config.second_owner.is_empty()
             || (!config.second_owner.is_empty() && info.sender != config.second_owner))
     {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     if STAKEABLE_INFOS
         .may_load(deps.storage, pool_address.to_string())?
         .is_some()
     {
-        return Err(StdError::generic_err("Token already exists in list"));
+        return Err(ContractError::TokenAlreadyExists {});
     }
+    deps.api.addr_validate(&pool_address)?;
     let stakeable_token = StakeableToken {
         liquidity_token: "".to_string(),
         token: pool_address.to_string(),
@@ -386,12 +507,12 @@ pub fn execute_update_config(
     _env: Env,
     info: MessageInfo,
     owner_provided: Option<String>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // Permission check
     if info.sender != config.owner {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
     if let Some(owner) = owner_provided {
         // Validate address format
@@ -410,10 +531,10 @@ pub fn execute_add_second_owner(
     deps: DepsMut,
     info: MessageInfo,
     second_owner_address: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
     if info.sender != config.owner {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
     deps.api.addr_validate(&second_owner_address)?;
     config.second_owner = second_owner_address;
@@ -427,7 +548,7 @@ pub fn execute_update_freeze_flag(
     _env: Env,
     info: MessageInfo,
     freeze_flag: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
@@ -435,7 +556,7 @@ pub fn execute_update_freeze_flag(
         && (config.second_owner.is_empty()
             || (!config.second_owner.is_empty() && info.sender != config.second_owner))
     {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     if freeze_flag.eq_ignore_ascii_case("Y") {
@@ -454,7 +575,7 @@ pub fn execute_update_lock_time_frame(
     _env: Env,
     info: MessageInfo,
     lock_time_frame: u64,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
@@ -462,7 +583,7 @@ pub fn execute_update_lock_time_frame(
         && (config.second_owner.is_empty()
             || (!config.second_owner.is_empty() && info.sender != config.second_owner))
     {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
     config.lock_time_frame = lock_time_frame;
     CONFIG.save(deps.storage, &config)?;
@@ -475,7 +596,7 @@ pub fn execute_update_wait_time_for_distributions(
     _env: Env,
     info: MessageInfo,
     wait_time_for_distribution_in_seconds: u64,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
@@ -483,12 +604,10 @@ pub fn execute_update_wait_time_for_distributions(
         && (config.second_owner.is_empty()
             || (!config.second_owner.is_empty() && info.sender != config.second_owner))
     {
-        return Err(StdError::generic_err("unauthoriized"));
+        return Err(ContractError::Unauthorized {});
     }
     if wait_time_for_distribution_in_seconds < 1u64 {
-        return Err(StdError::generic_err(
-            "wait_time_for_distribution_in_seconds cannot be set to 0",
-        ));
+        return Err(ContractError::InvalidDistributionWaitTime {});
     }
     config.wait_time_for_distribution_in_seconds = wait_time_for_distribution_in_seconds;
     CONFIG.save(deps.storage, &config)?;
@@ -502,15 +621,21 @@ pub fn execute_stake(
     info: Addr,
     pool_address: String,
     amount: Uint128,
-) -> StdResult<Response> {
+    lock_tier: Option<u64>,
+) -> Result<Response, ContractError> {
     let mut messages: Vec<CosmosMsg> = vec![];
     let stakeable_token = if let Some(stakeable_token) =
         STAKEABLE_INFOS.may_load(deps.storage, pool_address.to_string())?
     {
         stakeable_token
     } else {
-        return Err(StdError::generic_err("Provided asset info not correct"));
+        return Err(ContractError::PoolNotFound {});
     };
+    if let Some(tier) = lock_tier {
+        if !LOCK_TIERS.has(deps.storage, tier) {
+            return Err(ContractError::UnknownLockTier { tier });
+        }
+    }
 
     let asset_infos = stakeable_token.token.clone();
     //  update staked amount in TOTAL_STAKED map
@@ -518,64 +643,58 @@ pub fn execute_stake(
         get_total_staked_amount_in_pool_from_map_storage(deps.storage, pool_address.to_string());
 
     TOTAL_STAKED.save(deps.storage, pool_address, &(total_staked + amount))?;
-    let mut user_pool_key = info.to_string();
-    user_pool_key.push_str(&asset_infos);
+    let pool = Addr::unchecked(&asset_infos);
 
     let mut user_staked =
-        get_user_staked_amount_in_pool_from_map_storage(deps.storage, user_pool_key.to_string());
+        get_user_staked_amount_in_pool_from_map_storage(deps.storage, &info, &pool);
+    let user_working = get_user_working_amount(deps.storage, &info, &pool, user_staked)?;
 
     let share = amount;
 
     let user_opt_for_auto_compound = USER_AUTO_COMPOUND_SUBSCRIPTION_MAP
-        .may_load(deps.storage, user_pool_key.to_string())?
+        .may_load(deps.storage, (&info, &pool))?
         .unwrap_or(false);
 
     if total_staked == Uint128::zero() {
         // Initial share = collateral amount
         for dist_itr in stakeable_token.distribution.iter() {
-            let mut pool_dist_key: String = asset_infos.to_string();
-            pool_dist_key.push_str(&dist_itr.token.to_string());
-            let mut user_pool_dist_key = info.to_string();
+            let dist_token = Addr::unchecked(&dist_itr.token);
 
             //geting pool reward index
             let current_reward_index =
-                get_reward_index_map_from_map_storage(deps.storage, pool_dist_key.to_string());
+                get_reward_index_map_from_map_storage(deps.storage, &pool, &dist_token);
 
             //assigning reward index to the user
             let mut reward_index_to_be_assigned = Uint128::zero();
             if current_reward_index > Uint128::zero() {
                 reward_index_to_be_assigned = current_reward_index; //if it is not the 1st time to stake in the pool
             }
-            user_pool_dist_key.push_str(&pool_dist_key.to_string());
 
             //getting user reward index
             let user_reward_info = get_user_reward_info_from_map_storage(
                 deps.storage,
-                user_pool_dist_key.to_string(),
+                (&info, &pool, &dist_token),
                 reward_index_to_be_assigned,
             );
 
             USER_REWARD_INFO_MAP.save(
                 deps.storage,
-                user_pool_dist_key.to_string(),
+                (&info, &pool, &dist_token),
                 &user_reward_info,
             )?;
         }
     } else {
         for dist_itr in stakeable_token.distribution.iter() {
-            let mut pool_dist_key: String = asset_infos.to_string();
-            pool_dist_key.push_str(&dist_itr.token.to_string());
-            let mut user_pool_dist_key = info.to_string();
-            user_pool_dist_key.push_str(&pool_dist_key.to_string());
+            let dist_token = Addr::unchecked(&dist_itr.token);
 
             //getting pool reward index
             let current_reward_index =
-                get_reward_index_map_from_map_storage(deps.storage, pool_dist_key.to_string());
+                get_reward_index_map_from_map_storage(deps.storage, &pool, &dist_token);
 
             //getting user reward index
             let mut user_reward_info = get_user_reward_info_from_map_storage(
                 deps.storage,
-                user_pool_dist_key.to_string(),
+                (&info, &pool, &dist_token),
                 current_reward_index,
             );
 
@@ -584,23 +703,23 @@ pub fn execute_stake(
                 current_reward_index - user_reward_info.reward_index;
 
             user_reward_info.pending_reward +=
-                diff_priv_and_curr_reward_index.multiply_ratio(user_staked, Uint128::from(1u128));
+                diff_priv_and_curr_reward_index.multiply_ratio(user_working, Uint128::from(1u128));
             user_reward_info.reward_index = current_reward_index;
 
             USER_REWARD_INFO_MAP.save(
                 deps.storage,
-                user_pool_dist_key.to_string(),
+                (&info, &pool, &dist_token),
                 &user_reward_info,
             )?;
 
             // calculating reward index for auto compounding
             if user_opt_for_auto_compound {
                 let current_compounded_reward_index = POOL_COMPOUNDED_INDEX_MAP
-                    .may_load(deps.storage, pool_dist_key.to_string())?
+                    .may_load(deps.storage, (&pool, &dist_token))?
                     .unwrap_or_else(Uint128::zero);
 
                 let mut user_compounded_reward_index = USER_COMPOUNDED_REWARD_INFO_MAP
-                    .may_load(deps.storage, user_pool_dist_key.to_string())?
+                    .may_load(deps.storage, (&info, &pool, &dist_token))?
                     .unwrap_or_else(|| RewardInfo {
                         reward_index: Uint128::zero(),
                         pending_reward: Uint128::zero(),
@@ -617,7 +736,7 @@ pub fn execute_stake(
 
                 USER_COMPOUNDED_REWARD_INFO_MAP.save(
                     deps.storage,
-                    user_pool_dist_key.to_string(),
+                    (&info, &pool, &dist_token),
                     &user_compounded_reward_index,
                 )?;
             }
@@ -647,12 +766,32 @@ pub fn execute_stake(
     } else {
         USER_REWARD_STARTING_TIME_MAP.save(
             deps.storage,
-            user_pool_key.to_string(),
+            (&info, &pool),
             &env.block.time.seconds(),
         )?;
     }
 
-    USER_STAKED_AMOUNT.save(deps.storage, user_pool_key, &user_staked)?;
+    USER_STAKED_AMOUNT.save(deps.storage, (&info, &pool), &user_staked)?;
+    POOL_STAKERS.save(deps.storage, (&pool, &info), &true)?;
+    release_expired_tier_stakes(deps.storage, env.block.time.seconds(), &info, &pool)?;
+    if let Some(lock_tier) = lock_tier {
+        add_tier_stake(
+            deps.storage,
+            env.block.time.seconds(),
+            &info,
+            &pool,
+            lock_tier,
+            share,
+        )?;
+    }
+    update_working_amount(
+        deps.storage,
+        &deps.querier,
+        &info,
+        &pool,
+        user_working,
+        user_staked,
+    )?;
 
     Ok(Response::new()
         .add_attributes(vec![
@@ -672,14 +811,12 @@ pub fn execute_unstake_and_claim(
     amount: Uint128,
     liquidity_token_addr: String,
     is_reward_claimed: bool,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
     //freezing the unstaking mechanism
     if config.freeze {
-        return Err(StdError::generic_err(
-            "Sorry for inconvenience, system is under maintenance. Kindly check again later",
-        ));
+        return Err(ContractError::Frozen {});
     }
 
     let mut stakeable_token = if let Some(stakeable_token) =
@@ -687,29 +824,28 @@ pub fn execute_unstake_and_claim(
     {
         stakeable_token
     } else {
-        return Err(StdError::generic_err("Incorrect pool address Provided"));
+        return Err(ContractError::PoolNotFound {});
     };
 
     let mut message: String = String::from("");
     let mut messages: Vec<CosmosMsg> = Vec::new();
 
-    let mut user_pool_key: String = sender.to_string();
-    user_pool_key.push_str(&stakeable_token.token.to_string());
+    let user = Addr::unchecked(&sender);
+    let pool = Addr::unchecked(&stakeable_token.token);
 
-    let user_staked =
-        get_user_staked_amount_in_pool_from_map_storage(deps.storage, user_pool_key.to_string());
+    let user_staked = get_user_staked_amount_in_pool_from_map_storage(deps.storage, &user, &pool);
 
     if liquidity_token_addr != stakeable_token.liquidity_token {
-        return Err(StdError::generic_err(
-            "Invalid token provided, Kindly provide only FLP tokens",
-        ));
+        return Err(ContractError::InvalidFlpToken {});
     }
 
-    if amount != user_staked {
-        return Err(StdError::generic_err(
-            "FLP token not provided, Kindly provide all flp tokens",
-        ));
+    if amount > user_staked {
+        return Err(ContractError::IncompleteFlpAmount {});
+    }
+    if amount < user_staked {
+        return unstake_unlocked(deps, env, stakeable_token, user, pool, amount, user_staked);
     }
+    remove_tier_stakes(deps.storage, env.block.time.seconds(), &user, &pool)?;
 
     messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
         //burning FLP token
@@ -738,17 +874,17 @@ pub fn execute_unstake_and_claim(
         stakeable_token.token.to_string(),
         &total_staked,
     )?;
-    USER_STAKED_AMOUNT.save(deps.storage, user_pool_key.clone(), &Uint128::zero())?;
+    USER_STAKED_AMOUNT.remove(deps.storage, (&user, &pool));
+    POOL_STAKERS.remove(deps.storage, (&pool, &user));
 
     let mut user_opt_for_auto_compound = false;
     if let Some(true) = USER_AUTO_COMPOUND_SUBSCRIPTION_MAP
-        .may_load(deps.storage, user_pool_key.to_string())
+        .may_load(deps.storage, (&user, &pool))
         .unwrap()
     {
         user_opt_for_auto_compound = true;
     }
-    let user_staked_time =
-        USER_REWARD_STARTING_TIME_MAP.load(deps.storage, user_pool_key.to_string())?;
+    let user_staked_time = USER_REWARD_STARTING_TIME_MAP.load(deps.storage, (&user, &pool))?;
     let mut total_compounded = POOL_TOTAL_COMPOUNDED_AMOUNT
         .may_load(deps.storage, pool_address.to_string())?
         .unwrap_or_else(Uint128::zero);
@@ -762,27 +898,23 @@ pub fn execute_unstake_and_claim(
             &total_compounded,
         )?;
         USER_AUTO_COMPOUND_SUBSCRIPTION_MAP
-            .save(deps.storage, user_pool_key.to_string(), &false)
+            .save(deps.storage, (&user, &pool), &false)
             .unwrap();
     }
 
     //getting reward amounts from the linked distributed tokens of the stakeable token
     for dist_tkn in stakeable_token.distribution.iter_mut() {
-        let mut pool_dist_key: String = stakeable_token.token.to_string();
-        pool_dist_key.push_str(&dist_tkn.token.to_string());
-        let mut user_pool_dist_key = sender.to_string();
-        user_pool_dist_key.push_str(&pool_dist_key.to_string());
+        let dist_token = Addr::unchecked(&dist_tkn.token);
 
         //calculate user's reward
         let reward_to_be_dist = calculate_reward(
             deps.storage,
-            user_pool_dist_key.to_string(),
-            //&mut stakeable_token,
-            pool_dist_key.to_string(),
+            &user,
+            &pool,
             &user_opt_for_auto_compound,
             &user_staked,
             dist_tkn,
-        );
+        )?;
 
         if is_reward_claimed && // check if reward is claimed or not 
                         (( user_opt_for_auto_compound && env.block.time.seconds() - user_staked_time >= config.lock_time_frame_for_compound_reward) //if the user has subscribed for auto compound then check the time with lock time frame for compound reward
@@ -806,14 +938,14 @@ pub fn execute_unstake_and_claim(
             if total_compounded != Uint128::zero() {
                 // if total compounded is not zero than adding reward index in compounded reward index
                 let mut compounded_index = POOL_COMPOUNDED_INDEX_MAP
-                    .may_load(deps.storage, pool_dist_key.to_string())?
+                    .may_load(deps.storage, (&pool, &dist_token))?
                     .unwrap_or_else(Uint128::zero);
                 compounded_index += reward_to_be_dist
                     .multiply_ratio(REWARD_CALCULATION_DECIMAL_PRECISION, total_compounded);
 
                 POOL_COMPOUNDED_INDEX_MAP.save(
                     deps.storage,
-                    pool_dist_key.to_string(),
+                    (&pool, &dist_token),
                     &compounded_index,
                 )?;
             } else if dist_tkn.reserve_amount != Uint128::zero() {
@@ -832,8 +964,17 @@ pub fn execute_unstake_and_claim(
             }
             // if user is not availing the reward
         }
-        USER_REWARD_INFO_MAP.remove(deps.storage, user_pool_dist_key.to_string());
+        USER_REWARD_INFO_MAP.remove(deps.storage, (&user, &pool, &dist_token));
     }
+    let user_working = get_user_working_amount(deps.storage, &user, &pool, user_staked)?;
+    update_working_amount(
+        deps.storage,
+        &deps.querier,
+        &user,
+        &pool,
+        user_working,
+        Uint128::zero(),
+    )?;
     STAKEABLE_INFOS.save(deps.storage, pool_address, &stakeable_token)?;
     message.push_str("Unstake");
     if is_reward_claimed {
@@ -845,35 +986,249 @@ pub fn execute_unstake_and_claim(
         .add_attribute("action", message))
 }
 
+/// Unstakes part of the stake, at most what is not locked in a tier. Rewards earned so far
+/// stay pending, to be claimed with ClaimReward or the rest of the stake.
+fn unstake_unlocked(
+    deps: DepsMut,
+    env: Env,
+    stakeable_token: StakeableToken,
+    user: Addr,
+    pool: Addr,
+    amount: Uint128,
+    user_staked: Uint128,
+) -> Result<Response, ContractError> {
+    let now = env.block.time.seconds();
+    release_expired_tier_stakes(deps.storage, now, &user, &pool)?;
+    let (locked, unlock_time) = get_locked_stake(deps.storage, now, &user, &pool)?;
+    if amount > user_staked - locked {
+        return Err(ContractError::StakeLocked { unlock_time });
+    }
+
+    let user_working = get_user_working_amount(deps.storage, &user, &pool, user_staked)?;
+    let user_opt_for_auto_compound = USER_AUTO_COMPOUND_SUBSCRIPTION_MAP
+        .may_load(deps.storage, (&user, &pool))?
+        .unwrap_or(false);
+
+    // settling rewards earned with the current stake before it changes
+    for dist_tkn in stakeable_token.distribution.iter() {
+        let dist_token = Addr::unchecked(&dist_tkn.token);
+        let current_reward_index =
+            get_reward_index_map_from_map_storage(deps.storage, &pool, &dist_token);
+        let mut user_reward_info = get_user_reward_info_from_map_storage(
+            deps.storage,
+            (&user, &pool, &dist_token),
+            current_reward_index,
+        );
+        user_reward_info.pending_reward += (current_reward_index - user_reward_info.reward_index)
+            .multiply_ratio(user_working, Uint128::from(1u128));
+        user_reward_info.reward_index = current_reward_index;
+        USER_REWARD_INFO_MAP.save(deps.storage, (&user, &pool, &dist_token), &user_reward_info)?;
+
+        if user_opt_for_auto_compound {
+            let current_compounded_reward_index = POOL_COMPOUNDED_INDEX_MAP
+                .may_load(deps.storage, (&pool, &dist_token))?
+                .unwrap_or_else(Uint128::zero);
+            let mut user_compounded_reward_index = USER_COMPOUNDED_REWARD_INFO_MAP
+                .may_load(deps.storage, (&user, &pool, &dist_token))?
+                .unwrap_or_else(|| RewardInfo {
+                    reward_index: Uint128::zero(),
+                    pending_reward: Uint128::zero(),
+                });
+            user_compounded_reward_index.pending_reward += (current_compounded_reward_index
+                - user_compounded_reward_index.reward_index)
+                .multiply_ratio(user_staked, Uint128::from(1u128));
+            user_compounded_reward_index.reward_index = current_compounded_reward_index;
+            USER_COMPOUNDED_REWARD_INFO_MAP.save(
+                deps.storage,
+                (&user, &pool, &dist_token),
+                &user_compounded_reward_index,
+            )?;
+        }
+    }
+
+    let user_staked = user_staked - amount;
+    USER_STAKED_AMOUNT.save(deps.storage, (&user, &pool), &user_staked)?;
+    let total_staked =
+        get_total_staked_amount_in_pool_from_map_storage(deps.storage, pool.to_string()) - amount;
+    TOTAL_STAKED.save(deps.storage, pool.to_string(), &total_staked)?;
+    if user_opt_for_auto_compound {
+        let total_compounded = POOL_TOTAL_COMPOUNDED_AMOUNT.load(deps.storage, pool.to_string())?;
+        POOL_TOTAL_COMPOUNDED_AMOUNT.save(
+            deps.storage,
+            pool.to_string(),
+            &(total_compounded - amount),
+        )?;
+    }
+    update_working_amount(
+        deps.storage,
+        &deps.querier,
+        &user,
+        &pool,
+        user_working,
+        user_staked,
+    )?;
+
+    Ok(Response::new()
+        .add_messages(vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: stakeable_token.liquidity_token,
+                msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: stakeable_token.token,
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: user.to_string(),
+                    amount,
+                })?,
+                funds: vec![],
+            }),
+        ])
+        .add_attributes(vec![("action", "Unstake"), ("amount", &amount.to_string())]))
+}
+
 //To claim rewards only
 pub fn execute_claim_reward(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     pool_address: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.freeze {
+        return Err(ContractError::Frozen {});
+    }
+    let user = info.sender;
+    let rewards = claim_reward_in_pool(
+        deps.storage,
+        &deps.querier,
+        &env,
+        &config,
+        &user,
+        pool_address,
+    )?;
+
     let mut messages: Vec<CosmosMsg> = Vec::new();
+    for (token, amount) in rewards {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            //sending reward to user
+            contract_addr: token,
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: user.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        }));
+    }
+    Ok(Response::new().add_messages(messages))
+}
+
+//To claim rewards of every pool the user has staked in, one transfer per distribution token
+pub fn execute_claim_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     if config.freeze {
-        return Err(StdError::generic_err(
-            "Sorry for inconvenience, system is under maintenance. Kindly check again later",
-        ));
+        return Err(ContractError::Frozen {});
     }
-    let user = info.sender.into_string();
-    let mut user_pool_key = user.to_string();
-    user_pool_key.push_str(&pool_address);
-    if let Some(mut stakeable_token) =
-        STAKEABLE_INFOS.may_load(deps.storage, pool_address.to_string())?
-    {
-        let user_staked_time =
-            USER_REWARD_STARTING_TIME_MAP.load(deps.storage, user_pool_key.to_string())?;
-        let user_staked = get_user_staked_amount_in_pool_from_map_storage(
+    let user = info.sender;
+    let start_after = start_after.map(Addr::unchecked);
+    let limit = limit.unwrap_or(config.default_limit).min(config.max_limit) as usize;
+
+    let pools = USER_STAKED_AMOUNT
+        .prefix(&user)
+        .keys(
             deps.storage,
-            user_pool_key.to_string(),
-        );
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<Addr>>>()?;
+
+    let mut attributes = vec![("action".to_string(), "claim_all".to_string())];
+    let mut totals: Vec<(String, Uint128)> = vec![];
+    for pool in pools {
+        let rewards = match claim_reward_in_pool(
+            deps.storage,
+            &deps.querier,
+            &env,
+            &config,
+            &user,
+            pool.to_string(),
+        ) {
+            Ok(rewards) => rewards,
+            // still locked or no longer stakeable, leave it for a later claim
+            Err(ContractError::ClaimTimeNotReached {}) | Err(ContractError::PoolNotFound {}) => {
+                attributes.push(("skipped".to_string(), pool.to_string()));
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+
+        // attribute values can't be empty, report "0" when the pool had nothing to pay
+        let breakdown = if rewards.is_empty() {
+            "0".to_string()
+        } else {
+            rewards
+                .iter()
+                .map(|(token, amount)| format!("{}{}", amount, token))
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+        attributes.push(("pool".to_string(), pool.to_string()));
+        attributes.push(("rewards".to_string(), breakdown));
+
+        for (token, amount) in rewards {
+            match totals
+                .iter_mut()
+                .find(|(total_token, _)| *total_token == token)
+            {
+                Some((_, total)) => *total += amount,
+                None => totals.push((token, amount)),
+            }
+        }
+    }
+
+    let mut messages: Vec<CosmosMsg> = Vec::new();
+    for (token, amount) in totals {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            //sending reward to user
+            contract_addr: token,
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: user.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        }));
+    }
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
+}
+
+// Settles the user's rewards in the pool and returns the amount due per distribution token.
+// Fails with ClaimTimeNotReached while the lock time frame has not passed.
+fn claim_reward_in_pool(
+    store: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+    config: &Config,
+    user: &Addr,
+    pool_address: String,
+) -> Result<Vec<(String, Uint128)>, ContractError> {
+    let mut rewards: Vec<(String, Uint128)> = vec![];
+    let pool = Addr::unchecked(&pool_address);
+    if let Some(mut stakeable_token) = STAKEABLE_INFOS.may_load(store, pool_address.to_string())? {
+        let user_staked_time = USER_REWARD_STARTING_TIME_MAP.load(store, (user, &pool))?;
+        let user_staked = get_user_staked_amount_in_pool_from_map_storage(store, user, &pool);
         let mut user_opt_for_auto_compound = false;
         if let Some(true) = USER_AUTO_COMPOUND_SUBSCRIPTION_MAP
-            .may_load(deps.storage, user_pool_key.to_string())
+            .may_load(store, (user, &pool))
             .unwrap()
         {
             user_opt_for_auto_compound = true;
@@ -887,79 +1242,129 @@ pub fn execute_claim_reward(
         {
             //getting reward amounts from the linked distributed tokens of the stakeable token
             for dist_tkn in stakeable_token.distribution.iter_mut() {
-                let mut pool_dist_key: String = pool_address.to_string();
-                pool_dist_key.push_str(&dist_tkn.token.to_string());
-                let mut user_pool_dist_key = user.to_string();
-                user_pool_dist_key.push_str(&pool_dist_key.to_string());
                 let reward_to_be_dist = calculate_reward(
-                    deps.storage,
-                    user_pool_dist_key.to_string(),
-                    pool_dist_key,
+                    store,
+                    user,
+                    &pool,
                     &user_opt_for_auto_compound,
                     &user_staked,
                     dist_tkn,
-                );
+                )?;
 
                 if reward_to_be_dist != Uint128::zero() {
-                    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                        //sending reward to user
-                        contract_addr: dist_tkn.token.to_string(),
-                        msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                            recipient: user.to_string(),
-                            amount: reward_to_be_dist,
-                        })?,
-                        funds: vec![],
-                    }));
+                    rewards.push((dist_tkn.token.to_string(), reward_to_be_dist));
                 }
             }
+            // rewards are settled, the boost can follow the current voting power and locks
+            let user_working = get_user_working_amount(store, user, &pool, user_staked)?;
+            release_expired_tier_stakes(store, env.block.time.seconds(), user, &pool)?;
+            update_working_amount(store, querier, user, &pool, user_working, user_staked)?;
         } else {
-            return Err(StdError::generic_err(
-                "User claim reward time not reached yet",
-            ));
+            return Err(ContractError::ClaimTimeNotReached {});
         }
-        STAKEABLE_INFOS.save(deps.storage, pool_address.to_string(), &stakeable_token)?;
+        STAKEABLE_INFOS.save(store, pool_address, &stakeable_token)?;
     } else {
-        return Err(StdError::generic_err("Pool address not valid"));
+        return Err(ContractError::PoolNotFound {});
     }
     USER_AUTO_COMPOUND_SUBSCRIPTION_MAP
-        .save(deps.storage, user_pool_key.to_string(), &false)
+        .save(store, (user, &pool), &false)
         .unwrap();
-    Ok(Response::new().add_messages(messages))
+    Ok(rewards)
+}
+
+/// Anyone can kick a user whose voting power decayed or whose tier lock ended since the last
+/// update, so their working amount drops to what the current voting power and locks earn.
+pub fn execute_kick(
+    deps: DepsMut,
+    env: Env,
+    user: String,
+    pool_address: String,
+) -> Result<Response, ContractError> {
+    let user = deps.api.addr_validate(&user)?;
+    let pool = Addr::unchecked(&pool_address);
+    let stakeable_token = STAKEABLE_INFOS
+        .may_load(deps.storage, pool_address.to_string())?
+        .ok_or(ContractError::PoolNotFound {})?;
+
+    let user_staked = get_user_staked_amount_in_pool_from_map_storage(deps.storage, &user, &pool);
+    if user_staked.is_zero() {
+        return Err(ContractError::NoStakedAsset {});
+    }
+    let user_working = get_user_working_amount(deps.storage, &user, &pool, user_staked)?;
+    let released =
+        release_expired_tier_stakes(deps.storage, env.block.time.seconds(), &user, &pool)?;
+    let working = compute_working_amount(
+        deps.storage,
+        &deps.querier,
+        &user,
+        get_user_weighted_stake(deps.storage, &user, &pool, user_staked)?,
+        get_total_weighted_stake(deps.storage, pool_address.to_string())?,
+    )?;
+    if working >= user_working && !released {
+        return Err(ContractError::KickNotAllowed {});
+    }
+
+    // settling rewards earned with the old working amount before it changes
+    for dist_tkn in stakeable_token.distribution.iter() {
+        let dist_token = Addr::unchecked(&dist_tkn.token);
+        let current_reward_index =
+            get_reward_index_map_from_map_storage(deps.storage, &pool, &dist_token);
+        let mut user_reward_info = get_user_reward_info_from_map_storage(
+            deps.storage,
+            (&user, &pool, &dist_token),
+            current_reward_index,
+        );
+        user_reward_info.pending_reward += (current_reward_index - user_reward_info.reward_index)
+            .multiply_ratio(user_working, Uint128::from(1u128));
+        user_reward_info.reward_index = current_reward_index;
+        USER_REWARD_INFO_MAP.save(deps.storage, (&user, &pool, &dist_token), &user_reward_info)?;
+    }
+    let working = update_working_amount(
+        deps.storage,
+        &deps.querier,
+        &user,
+        &pool,
+        user_working,
+        user_staked,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "kick")
+        .add_attribute("user", user)
+        .add_attribute("pool", pool_address)
+        .add_attribute("working_amount", working.to_string()))
 }
 
 pub fn calculate_reward(
     store: &mut dyn Storage,
-    user_pool_dist_key: String,
-    //stakeable_token: &mut StakeableToken,
-    pool_dist_key: String,
+    user: &Addr,
+    pool: &Addr,
     user_opt_for_auto_compound: &bool,
     user_staked: &Uint128,
     dist_tkn: &mut StakeablePairedDistributionTokenInfo,
-) -> Uint128 {
+) -> StdResult<Uint128> {
+    let dist_token = Addr::unchecked(&dist_tkn.token);
     //getting total amount of rewards in the pool
     let mut total_rewards_in_pool =
-        get_total_reward_in_pool_from_map_storage(store, pool_dist_key.to_string());
+        get_total_reward_in_pool_from_map_storage(store, pool, &dist_token);
 
     //getting unclaimed distributed amount of contract
     let mut total_rewards_of_ditributed_tokens_in_contract =
         get_unclaimed_distirbuted_token_amount_from_map_storage(store, dist_tkn.token.to_string());
 
     //getting user reward index
-    let mut user_reward_info = get_user_reward_info_from_map_storage(
-        store,
-        user_pool_dist_key.to_string(),
-        Uint128::zero(),
-    );
+    let mut user_reward_info =
+        get_user_reward_info_from_map_storage(store, (user, pool, &dist_token), Uint128::zero());
 
     //calculate reward amount
-    let current_reward_index =
-        get_reward_index_map_from_map_storage(store, pool_dist_key.to_string());
+    let current_reward_index = get_reward_index_map_from_map_storage(store, pool, &dist_token);
 
     // getting user reward difference from it's last stake to current pool index
     let diff_priv_and_curr_reward_index = current_reward_index - user_reward_info.reward_index;
 
+    let user_working = get_user_working_amount(store, user, pool, *user_staked)?;
     let mut reward_to_be_dist =
-        diff_priv_and_curr_reward_index.multiply_ratio(*user_staked, Uint128::new(1u128));
+        diff_priv_and_curr_reward_index.multiply_ratio(user_working, Uint128::new(1u128));
 
     reward_to_be_dist += user_reward_info.pending_reward;
 
@@ -974,7 +1379,7 @@ pub fn calculate_reward(
     if *user_opt_for_auto_compound {
         // calculating reward for auto compounding amount
         let mut user_compounded_reward_index = USER_COMPOUNDED_REWARD_INFO_MAP
-            .may_load(store, user_pool_dist_key.to_string())
+            .may_load(store, (user, pool, &dist_token))
             .unwrap()
             .unwrap_or_else(|| RewardInfo {
                 reward_index: Uint128::zero(),
@@ -982,7 +1387,7 @@ pub fn calculate_reward(
             });
 
         let pool_compound_reward_index = POOL_COMPOUNDED_INDEX_MAP
-            .may_load(store, pool_dist_key.to_string())
+            .may_load(store, (pool, &dist_token))
             .unwrap()
             .unwrap_or_else(Uint128::zero);
 
@@ -1004,7 +1409,7 @@ pub fn calculate_reward(
         USER_COMPOUNDED_REWARD_INFO_MAP
             .save(
                 store,
-                user_pool_dist_key.to_string(),
+                (user, pool, &dist_token),
                 &user_compounded_reward_index,
             )
             .unwrap();
@@ -1022,7 +1427,7 @@ pub fn calculate_reward(
     //adjusting values of calculating reward in pool and contract
 
     TOTAL_REWARDS_IN_POOL
-        .save(store, pool_dist_key, &total_rewards_in_pool.clone())
+        .save(store, (pool, &dist_token), &total_rewards_in_pool.clone())
         .unwrap();
 
     UNCLAIMED_DISTRIBUTED_TOKEN_AMOUNT_MAP
@@ -1034,9 +1439,9 @@ pub fn calculate_reward(
         .unwrap();
 
     USER_REWARD_INFO_MAP
-        .save(store, user_pool_dist_key, &user_reward_info)
+        .save(store, (user, pool, &dist_token), &user_reward_info)
         .unwrap();
-    reward_to_be_dist
+    Ok(reward_to_be_dist)
 }
 
 /*
@@ -1049,17 +1454,17 @@ pub fn execute_distribute_by_limit(
     _info: MessageInfo,
     start_after: Option<String>,
     limit: Option<u32>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
     let mut bound = None;
-    if let Some(start_after) = start_after  {
+    if let Some(start_after) = start_after {
         if STAKEABLE_INFOS
             .may_load(deps.storage, start_after.to_string())?
             .is_some()
         {
             bound = Some(Bound::exclusive(start_after));
         } else {
-            return Err(StdError::generic_err("not a valid address passed"));
+            return Err(ContractError::InvalidStartAfter {});
         }
     }
 
@@ -1093,13 +1498,11 @@ pub fn execute_distribute_by_limit(
                     if distributed_token.amount == Uint128::zero() {
                         continue;
                     }
-                    let mut pool_dist_key: String = staleable_token_vec_obj.1.token.to_string();
-                    pool_dist_key.push_str(&distributed_token.token.to_string());
+                    let pool = Addr::unchecked(&staleable_token_vec_obj.1.token);
+                    let dist_token = Addr::unchecked(&distributed_token.token);
 
-                    let mut total_rewards_in_pool = get_total_reward_in_pool_from_map_storage(
-                        deps.storage,
-                        pool_dist_key.clone(),
-                    );
+                    let mut total_rewards_in_pool =
+                        get_total_reward_in_pool_from_map_storage(deps.storage, &pool, &dist_token);
 
                     let mut total_rewards_of_ditributed_tokens_in_contract =
                         get_unclaimed_distirbuted_token_amount_from_map_storage(
@@ -1108,14 +1511,14 @@ pub fn execute_distribute_by_limit(
                         );
 
                     let stakeable_token = staleable_token_vec_obj.1.clone();
-                    let total_staked = get_total_staked_amount_in_pool_from_map_storage(
-                        deps.storage,
-                        stakeable_token.token.to_string(),
-                    );
+                    // rewards are shared by the boosted stakes
+                    let total_working =
+                        get_total_working_amount(deps.storage, stakeable_token.token.to_string())?;
                     let mut total_accumulative_distributed_amount =
                         get_total_accumulated_distributed_amount_in_pool_from_map_storage(
                             deps.storage,
-                            pool_dist_key.to_string(),
+                            &pool,
+                            &dist_token,
                         );
                     let reward_time_precission =
                         (env.block.time.seconds() - last_distributed) as u128;
@@ -1138,12 +1541,10 @@ pub fn execute_distribute_by_limit(
                             - total_rewards_of_ditributed_tokens_in_contract
                             - distributed_token.reserve_amount)
                     {
-                        let mut reward_index = get_reward_index_map_from_map_storage(
-                            deps.storage,
-                            pool_dist_key.to_string(),
-                        );
+                        let mut reward_index =
+                            get_reward_index_map_from_map_storage(deps.storage, &pool, &dist_token);
 
-                        if total_staked.is_zero() {
+                        if total_working.is_zero() {
                             reward_index = Uint128::zero();
                         } else {
                             total_rewards_in_pool += total_reward_to_be_dist_ratio;
@@ -1152,22 +1553,22 @@ pub fn execute_distribute_by_limit(
                                 total_reward_to_be_dist_ratio;
 
                             reward_index += Uint128::new(REWARD_CALCULATION_DECIMAL_PRECISION)
-                                .multiply_ratio(total_reward_to_be_dist_ratio, total_staked);
+                                .multiply_ratio(total_reward_to_be_dist_ratio, total_working);
                         }
 
                         POOL_REWARD_INDEX_MAP.save(
                             deps.storage,
-                            pool_dist_key.to_string(),
+                            (&pool, &dist_token),
                             &reward_index,
                         )?;
                         TOTAL_REWARDS_IN_POOL.save(
                             deps.storage,
-                            pool_dist_key.to_string(),
+                            (&pool, &dist_token),
                             &total_rewards_in_pool,
                         )?;
                         TOTAL_ACCUMULATED_DISTRIBUTED_AMOUNT_IN_POOL_MAP.save(
                             deps.storage,
-                            pool_dist_key.to_string(),
+                            (&pool, &dist_token),
                             &total_accumulative_distributed_amount,
                         )?;
                         UNCLAIMED_DISTRIBUTED_TOKEN_AMOUNT_MAP.save(
@@ -1176,7 +1577,7 @@ pub fn execute_distribute_by_limit(
                             &total_rewards_of_ditributed_tokens_in_contract,
                         )?;
                     } else {
-                        return Err(StdError::generic_err("insufficient funds  "));
+                        return Err(ContractError::InsufficientFunds {});
                     }
                     POOL_LAST_DISTRIBUTION_TIME_IN_SECONDS.save(
                         deps.storage,
@@ -1193,9 +1594,7 @@ pub fn execute_distribute_by_limit(
             }
         }
     } else {
-        return Err(StdError::generic_err(
-            "please wait patiently for the specified time",
-        ));
+        return Err(ContractError::DistributionNotReady {});
     }
     //config.last_distributed = last_distributed;
     CONFIG.save(deps.storage, &config)?;
@@ -1212,13 +1611,13 @@ pub fn execute_update_reward(
     info: MessageInfo,
     pool: String,
     rewards: Vec<(String, Uint128)>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
     if info.sender != config.owner
         && (config.second_owner.is_empty()
             || (!config.second_owner.is_empty() && info.sender != config.second_owner))
     {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     //get user stakeable token from STAKEABLE_TOKENS map
@@ -1227,7 +1626,7 @@ pub fn execute_update_reward(
         if let Some(stakeable_token) = STAKEABLE_INFOS.may_load(deps.storage, pool.to_string())? {
             stakeable_token
         } else {
-            return Err(StdError::generic_err("correct info not provided"));
+            return Err(ContractError::PoolNotFound {});
         };
 
     for reward_tuple in rewards.iter() {
@@ -1268,13 +1667,13 @@ pub fn execute_update_stakeable_token_address(
     info: MessageInfo,
     old_address: String,
     new_address: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
     if info.sender != config.owner
         && (config.second_owner.is_empty()
             || (!config.second_owner.is_empty() && info.sender != config.second_owner))
     {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     let last_distributed = if let Some(last_distribute) =
@@ -1298,7 +1697,7 @@ pub fn execute_update_stakeable_token_address(
             &last_distributed,
         )?;
     } else {
-        return Err(StdError::generic_err("no pool exists with given address"));
+        return Err(ContractError::PoolNotFound {});
     }
 
     Ok(Response::new().add_attribute("action", "Pool address updated"))
@@ -1310,13 +1709,13 @@ pub fn execute_delete_stakeable_token(
     _env: Env,
     info: MessageInfo,
     address: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
     if info.sender != config.owner
         && (config.second_owner.is_empty()
             || (!config.second_owner.is_empty() && info.sender != config.second_owner))
     {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     if STAKEABLE_INFOS
@@ -1325,7 +1724,7 @@ pub fn execute_delete_stakeable_token(
     {
         STAKEABLE_INFOS.remove(deps.storage, address);
     } else {
-        return Err(StdError::generic_err("pool does not exist in the contract"));
+        return Err(ContractError::PoolNotFound {});
     }
 
     Ok(Response::new().add_attribute("action", "Pool address deleted"))
@@ -1338,13 +1737,13 @@ pub fn execute_delete_distributeable_token(
     info: MessageInfo,
     pool_address: String,
     token_address: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
     if info.sender != config.owner
         && (config.second_owner.is_empty()
             || (!config.second_owner.is_empty() && info.sender != config.second_owner))
     {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     let mut index = 0;
@@ -1367,9 +1766,7 @@ pub fn execute_delete_distributeable_token(
                 STAKEABLE_INFOS.save(deps.storage, pool_address, &stakeable_token)?;
             }
             false => {
-                return Err(StdError::generic_err(
-                    "distributeable_token does not exist in the pool",
-                ));
+                return Err(ContractError::DistributeableTokenNotFound {});
             }
         }
     }
@@ -1442,6 +1839,31 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::QueryFlpTokenFromPoolAddress { pool_address } => {
             to_binary(&query_flp_token_address(deps, pool_address)?)
         }
+        QueryMsg::QueryPoolsOfUser {
+            wallet,
+            start_after,
+            limit,
+        } => to_binary(&query_pools_of_user(deps, wallet, start_after, limit)?),
+        QueryMsg::QueryStakersInPool {
+            pool,
+            start_after,
+            limit,
+        } => to_binary(&query_stakers_in_pool(deps, pool, start_after, limit)?),
+        QueryMsg::QueryUserPositions {
+            wallet,
+            start_after,
+            limit,
+        } => to_binary(&query_user_positions(deps, wallet, start_after, limit)?),
+        QueryMsg::QueryWorkingAmount { wallet, pool } => {
+            to_binary(&query_working_amount(deps, wallet, pool)?)
+        }
+        QueryMsg::QueryTotalWorkingAmount { pool } => {
+            to_binary(&get_total_working_amount(deps.storage, pool)?)
+        }
+        QueryMsg::QueryLockTiers {} => to_binary(&query_lock_tiers(deps)?),
+        QueryMsg::QueryUserLockTiers { wallet, pool } => {
+            to_binary(&query_user_lock_tiers(deps, wallet, pool)?)
+        }
     }
 }
 
@@ -1457,10 +1879,10 @@ pub fn query_get_user_auto_compound_subscription(
     user_address: String,
     pool_address: String,
 ) -> StdResult<bool> {
-    let mut user_pool_address = user_address;
-    user_pool_address.push_str(&pool_address);
+    let user = deps.api.addr_validate(&user_address)?;
+    let pool = deps.api.addr_validate(&pool_address)?;
     let user_opt_for_auto_compound = if let Some(user_opt_for_auto_compound) =
-        USER_AUTO_COMPOUND_SUBSCRIPTION_MAP.may_load(deps.storage, user_pool_address)?
+        USER_AUTO_COMPOUND_SUBSCRIPTION_MAP.may_load(deps.storage, (&user, &pool))?
     {
         user_opt_for_auto_compound
     } else {
@@ -1477,9 +1899,9 @@ pub fn query_reward_in_pool(
     distribution_token: String,
 ) -> StdResult<Uint128> {
     let mut resp = Uint128::zero();
-    let mut key: String = pool;
-    key.push_str(&distribution_token);
-    let result = TOTAL_REWARDS_IN_POOL.may_load(deps.storage, key.clone())?;
+    let pool = deps.api.addr_validate(&pool)?;
+    let distribution_token = deps.api.addr_validate(&distribution_token)?;
+    let result = TOTAL_REWARDS_IN_POOL.may_load(deps.storage, (&pool, &distribution_token))?;
 
     if let Some(result) = result {
         resp = result;
@@ -1496,9 +1918,9 @@ pub fn query_staked_by_user(
     staked_token: String,
 ) -> StdResult<Uint128> {
     let mut resp = Uint128::zero();
-    let mut key = wallet;
-    key.push_str(&staked_token);
-    let result = USER_STAKED_AMOUNT.may_load(deps.storage, key.clone())?;
+    let wallet = deps.api.addr_validate(&wallet)?;
+    let staked_token = deps.api.addr_validate(&staked_token)?;
+    let result = USER_STAKED_AMOUNT.may_load(deps.storage, (&wallet, &staked_token))?;
     if let Some(result) = result {
         resp = result;
     }
@@ -1617,97 +2039,168 @@ pub fn query_user_reward_in_pool(
     if let Some(stakeable_token) =
         STAKEABLE_INFOS.may_load(deps.storage, pool_address.to_string())?
     {
-        let mut resp2 = QueryUserRewardInPoolResponse {
-            pool: pool_address.to_string(),
-            rewards_info: vec![],
-        };
-        let mut user_pool_key: String = wallet.to_string();
-        user_pool_key.push_str(&pool_address);
+        let user = deps.api.addr_validate(&wallet)?;
+        let pool = Addr::unchecked(&pool_address);
         let mut user_opt_for_auto_compound = false;
         if let Some(true) =
-            USER_AUTO_COMPOUND_SUBSCRIPTION_MAP.may_load(deps.storage, user_pool_key.to_string())?
+            USER_AUTO_COMPOUND_SUBSCRIPTION_MAP.may_load(deps.storage, (&user, &pool))?
         {
             user_opt_for_auto_compound = true;
         }
-        let user_staked = if let Some(user_staked) =
-            USER_STAKED_AMOUNT.may_load(deps.storage, user_pool_key.clone())?
+        let user_staked =
+            if let Some(user_staked) = USER_STAKED_AMOUNT.may_load(deps.storage, (&user, &pool))? {
+                user_staked
+            } else {
+                Uint128::zero()
+            };
+
+        resp.push(QueryUserRewardInPoolResponse {
+            pool: pool_address.to_string(),
+            rewards_info: query_pending_rewards(
+                deps.storage,
+                &user,
+                &stakeable_token,
+                user_staked,
+                user_opt_for_auto_compound,
+            )?,
+        });
+    }
+    Ok(resp)
+}
+
+// Rewards the user could claim from the pool right now, per distribution token
+pub fn query_pending_rewards(
+    store: &dyn Storage,
+    user: &Addr,
+    stakeable_token: &StakeableToken,
+    user_staked: Uint128,
+    user_opt_for_auto_compound: bool,
+) -> StdResult<Vec<(String, Uint128)>> {
+    let pool = Addr::unchecked(&stakeable_token.token);
+    let mut rewards_info = vec![];
+    for distt in stakeable_token.distribution.iter() {
+        let dist_token = Addr::unchecked(&distt.token);
+
+        //getting user reward index
+        let user_reward_info = if let Some(user_reward_info) =
+            USER_REWARD_INFO_MAP.may_load(store, (user, &pool, &dist_token))?
         {
-            user_staked
+            user_reward_info
         } else {
-            Uint128::zero()
+            RewardInfo {
+                reward_index: Uint128::zero(),
+                pending_reward: Uint128::zero(),
+            }
         };
 
-        for distt in stakeable_token.distribution.iter() {
-            let mut user_pool_dist_key = wallet.to_string();
-            let mut pool_dist_key: String = pool_address.to_string();
-            pool_dist_key.push_str(&distt.token.to_string());
-            user_pool_dist_key.push_str(&pool_dist_key.to_string());
+        //calculate reward amount
+        let current_reward_index = if let Some(current_reward_index) =
+            POOL_REWARD_INDEX_MAP.may_load(store, (&pool, &dist_token))?
+        {
+            current_reward_index
+        } else {
+            Uint128::zero()
+        };
 
-            //getting user reward index
-            let user_reward_info = if let Some(user_reward_info) =
-                USER_REWARD_INFO_MAP.may_load(deps.storage, user_pool_dist_key.to_string())?
-            {
-                user_reward_info
-            } else {
-                RewardInfo {
+        // getting user reward difference from it's last stake to current pool index
+        let diff_priv_and_curr_reward_index = current_reward_index - user_reward_info.reward_index;
+
+        //calculating reward to be distributed
+        let user_working = get_user_working_amount(store, user, &pool, user_staked)?;
+        let mut reward_to_be_dist =
+            diff_priv_and_curr_reward_index.multiply_ratio(user_working, Uint128::from(1u128));
+        reward_to_be_dist += user_reward_info.pending_reward;
+        if user_opt_for_auto_compound {
+            let user_compounded_reward_index = USER_COMPOUNDED_REWARD_INFO_MAP
+                .may_load(store, (user, &pool, &dist_token))?
+                .unwrap_or_else(|| RewardInfo {
                     reward_index: Uint128::zero(),
                     pending_reward: Uint128::zero(),
-                }
-            };
-
-            //calculate reward amount
-            let current_reward_index = if let Some(current_reward_index) =
-                POOL_REWARD_INDEX_MAP.may_load(deps.storage, pool_dist_key.to_string())?
-            {
-                current_reward_index
-            } else {
-                Uint128::zero()
-            };
-
-            // getting user reward difference from it's last stake to current pool index
-            let diff_priv_and_curr_reward_index =
-                current_reward_index - user_reward_info.reward_index;
-
-            //calculating reward to be distributed
-            let mut reward_to_be_dist =
-                diff_priv_and_curr_reward_index.multiply_ratio(user_staked, Uint128::from(1u128));
-            reward_to_be_dist += user_reward_info.pending_reward;
-            if user_opt_for_auto_compound {
-                let user_compounded_reward_index = USER_COMPOUNDED_REWARD_INFO_MAP
-                    .may_load(deps.storage, user_pool_dist_key.to_string())
-                    .unwrap()
-                    .unwrap_or_else(|| RewardInfo {
-                        reward_index: Uint128::zero(),
-                        pending_reward: Uint128::zero(),
-                    });
+                });
 
-                let pool_compound_reward_index = POOL_COMPOUNDED_INDEX_MAP
-                    .may_load(deps.storage, pool_dist_key.to_string())
-                    .unwrap()
-                    .unwrap_or_else(Uint128::zero);
+            let pool_compound_reward_index = POOL_COMPOUNDED_INDEX_MAP
+                .may_load(store, (&pool, &dist_token))?
+                .unwrap_or_else(Uint128::zero);
 
-                let diff_priv_and_curr_compound_reward_index =
-                    pool_compound_reward_index - user_compounded_reward_index.reward_index;
-                let mut compound_reward = diff_priv_and_curr_compound_reward_index
-                    .multiply_ratio(user_staked, Uint128::new(1u128));
-                compound_reward += user_compounded_reward_index.pending_reward;
-                reward_to_be_dist += compound_reward;
-            }
-            reward_to_be_dist = reward_to_be_dist.multiply_ratio(
-                Uint128::new(1),
-                Uint128::new(REWARD_CALCULATION_DECIMAL_PRECISION),
-            );
+            let diff_priv_and_curr_compound_reward_index =
+                pool_compound_reward_index - user_compounded_reward_index.reward_index;
+            let mut compound_reward = diff_priv_and_curr_compound_reward_index
+                .multiply_ratio(user_staked, Uint128::new(1u128));
+            compound_reward += user_compounded_reward_index.pending_reward;
+            reward_to_be_dist += compound_reward;
+        }
+        reward_to_be_dist = reward_to_be_dist.multiply_ratio(
+            Uint128::new(1),
+            Uint128::new(REWARD_CALCULATION_DECIMAL_PRECISION),
+        );
 
-            if reward_to_be_dist != Uint128::zero() {
-                resp2
-                    .rewards_info
-                    .push((distt.token.clone(), reward_to_be_dist))
-            }
+        if reward_to_be_dist != Uint128::zero() {
+            rewards_info.push((distt.token.clone(), reward_to_be_dist))
         }
+    }
+    Ok(rewards_info)
+}
+
+// paginated positions of the wallet across all pools it has staked in
+pub fn query_user_positions(
+    deps: Deps,
+    wallet: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<QueryUserPositionResponse>> {
+    let user = deps.api.addr_validate(&wallet)?;
+    let start_after = start_after.map(Addr::unchecked);
+    let config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(config.default_limit).min(config.max_limit) as usize;
 
-        resp.push(resp2);
+    let staked_pools = USER_STAKED_AMOUNT
+        .prefix(&user)
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut positions = vec![];
+    for (pool, staked) in staked_pools {
+        let stakeable_token = match STAKEABLE_INFOS.may_load(deps.storage, pool.to_string())? {
+            Some(stakeable_token) => stakeable_token,
+            // pool has been deleted or moved, nothing to report
+            None => continue,
+        };
+        let auto_compound = USER_AUTO_COMPOUND_SUBSCRIPTION_MAP
+            .may_load(deps.storage, (&user, &pool))?
+            .unwrap_or(false);
+        // same lock as claiming, auto compounders are held by their own time frame
+        let lock_time_frame = if auto_compound {
+            config.lock_time_frame_for_compound_reward
+        } else {
+            config.lock_time_frame
+        };
+        let unlock_time = USER_REWARD_STARTING_TIME_MAP
+            .may_load(deps.storage, (&user, &pool))?
+            .unwrap_or(0u64)
+            + lock_time_frame;
+
+        positions.push(QueryUserPositionResponse {
+            pool: pool.to_string(),
+            staked,
+            pending_rewards: query_pending_rewards(
+                deps.storage,
+                &user,
+                &stakeable_token,
+                staked,
+                auto_compound,
+            )?,
+            auto_compound,
+            unlock_time,
+        });
     }
-    Ok(resp)
+
+    Ok(positions)
 }
 
 pub fn get_unclaimed_distirbuted_token_amount_from_map_storage(
@@ -1729,11 +2222,11 @@ pub fn get_unclaimed_distirbuted_token_amount_from_map_storage(
 
 pub fn get_user_reward_info_from_map_storage(
     store: &dyn Storage,
-    user_pool_dist_address: String,
+    user_pool_dist: (&Addr, &Addr, &Addr),
     default_reward_index: Uint128,
 ) -> RewardInfo {
     let user_reward_info: RewardInfo = if let Some(user_reward_info) = USER_REWARD_INFO_MAP
-        .may_load(store, user_pool_dist_address)
+        .may_load(store, user_pool_dist)
         .unwrap()
     {
         user_reward_info
@@ -1748,10 +2241,11 @@ pub fn get_user_reward_info_from_map_storage(
 
 pub fn get_reward_index_map_from_map_storage(
     store: &dyn Storage,
-    pool_dist_address: String,
+    pool: &Addr,
+    dist_token: &Addr,
 ) -> Uint128 {
     let reward_index: Uint128 = if let Some(reward_index) = POOL_REWARD_INDEX_MAP
-        .may_load(store, pool_dist_address)
+        .may_load(store, (pool, dist_token))
         .unwrap()
     {
         reward_index
@@ -1763,11 +2257,12 @@ pub fn get_reward_index_map_from_map_storage(
 
 pub fn get_total_accumulated_distributed_amount_in_pool_from_map_storage(
     store: &dyn Storage,
-    pool_dist_address: String,
+    pool: &Addr,
+    dist_token: &Addr,
 ) -> Uint128 {
     let total_accumulate_amount_in_pool: Uint128 = if let Some(total_accumulate_amount_in_pool) =
         TOTAL_ACCUMULATED_DISTRIBUTED_AMOUNT_IN_POOL_MAP
-            .may_load(store, pool_dist_address)
+            .may_load(store, (pool, dist_token))
             .unwrap()
     {
         total_accumulate_amount_in_pool
@@ -1779,10 +2274,12 @@ pub fn get_total_accumulated_distributed_amount_in_pool_from_map_storage(
 
 pub fn get_total_reward_in_pool_from_map_storage(
     store: &dyn Storage,
-    pool_address: String,
+    pool: &Addr,
+    dist_token: &Addr,
 ) -> Uint128 {
-    let total_reward_in_pool: Uint128 = if let Some(total_reward_in_pool) =
-        TOTAL_REWARDS_IN_POOL.may_load(store, pool_address).unwrap()
+    let total_reward_in_pool: Uint128 = if let Some(total_reward_in_pool) = TOTAL_REWARDS_IN_POOL
+        .may_load(store, (pool, dist_token))
+        .unwrap()
     {
         total_reward_in_pool
     } else {
@@ -1807,12 +2304,12 @@ pub fn get_total_staked_amount_in_pool_from_map_storage(
 
 pub fn get_user_staked_amount_in_pool_from_map_storage(
     store: &dyn Storage,
-    user_pool_address: String,
+    user: &Addr,
+    pool: &Addr,
 ) -> Uint128 {
     let user_reward_issued_token_amount_in_pool: Uint128 =
-        if let Some(user_reward_issued_token_amount_in_pool) = USER_STAKED_AMOUNT
-            .may_load(store, user_pool_address)
-            .unwrap()
+        if let Some(user_reward_issued_token_amount_in_pool) =
+            USER_STAKED_AMOUNT.may_load(store, (user, pool)).unwrap()
         {
             user_reward_issued_token_amount_in_pool
         } else {
@@ -1823,10 +2320,10 @@ pub fn get_user_staked_amount_in_pool_from_map_storage(
 
 //query to get user staked time
 pub fn query_user_staked_time(deps: Deps, wallet: String, pool: String) -> StdResult<String> {
-    let mut user_pool_key = String::from(&wallet);
-    user_pool_key.push_str(&pool);
+    let wallet = deps.api.addr_validate(&wallet)?;
+    let pool = deps.api.addr_validate(&pool)?;
     if let Some(user_staked_time) =
-        USER_REWARD_STARTING_TIME_MAP.may_load(deps.storage, user_pool_key)?
+        USER_REWARD_STARTING_TIME_MAP.may_load(deps.storage, (&wallet, &pool))?
     {
         Ok(user_staked_time.to_string())
     } else {
@@ -1858,10 +2355,10 @@ pub fn query_total_ditributed_amount_in_pool(
     pool_addr: String,
     dist_token_addr: String,
 ) -> StdResult<Uint128> {
-    let mut pool_dist_addr = pool_addr;
-    pool_dist_addr.push_str(dist_token_addr.as_str());
+    let pool = deps.api.addr_validate(&pool_addr)?;
+    let dist_token = deps.api.addr_validate(&dist_token_addr)?;
     Ok(TOTAL_ACCUMULATED_DISTRIBUTED_AMOUNT_IN_POOL_MAP
-        .load(deps.storage, pool_dist_addr)
+        .load(deps.storage, (&pool, &dist_token))
         .unwrap_or_else(|_| Uint128::zero()))
 }
 
@@ -1905,28 +2402,176 @@ pub fn query_flp_token_address(deps: Deps, pool_address: String) -> StdResult<St
     Ok(stakeable_token.liquidity_token)
 }
 
+// paginated list of pools the wallet has staked in
+pub fn query_pools_of_user(
+    deps: Deps,
+    wallet: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, Uint128)>> {
+    let wallet = deps.api.addr_validate(&wallet)?;
+    let start_after = start_after.map(Addr::unchecked);
+    let config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(config.default_limit).min(config.max_limit) as usize;
+
+    USER_STAKED_AMOUNT
+        .prefix(&wallet)
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(pool, amount)| (pool.to_string(), amount)))
+        .collect()
+}
+
+// paginated list of wallets staked in the pool
+pub fn query_stakers_in_pool(
+    deps: Deps,
+    pool: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, Uint128)>> {
+    let pool = deps.api.addr_validate(&pool)?;
+    let start_after = start_after.map(Addr::unchecked);
+    let config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(config.default_limit).min(config.max_limit) as usize;
+
+    POOL_STAKERS
+        .prefix(&pool)
+        .keys(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|user| {
+            let user = user?;
+            let staked =
+                get_user_staked_amount_in_pool_from_map_storage(deps.storage, &user, &pool);
+            Ok((user.to_string(), staked))
+        })
+        .collect()
+}
+
+pub fn query_working_amount(deps: Deps, wallet: String, pool: String) -> StdResult<Uint128> {
+    let wallet = deps.api.addr_validate(&wallet)?;
+    let pool = deps.api.addr_validate(&pool)?;
+    let user_staked = get_user_staked_amount_in_pool_from_map_storage(deps.storage, &wallet, &pool);
+    get_user_working_amount(deps.storage, &wallet, &pool, user_staked)
+}
+
+pub fn query_lock_tiers(deps: Deps) -> StdResult<Vec<LockTier>> {
+    LOCK_TIERS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, lock_tier)| lock_tier))
+        .collect()
+}
+
+pub fn query_user_lock_tiers(
+    deps: Deps,
+    wallet: String,
+    pool: String,
+) -> StdResult<Vec<UserLockTierResponse>> {
+    let wallet = deps.api.addr_validate(&wallet)?;
+    let pool = deps.api.addr_validate(&pool)?;
+    USER_TIER_STAKES
+        .prefix((&wallet, &pool))
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (tier, stake) = item?;
+            Ok(UserLockTierResponse {
+                tier,
+                amount: stake.amount,
+                multiplier: stake.multiplier,
+                unlock_time: stake.unlock_time,
+            })
+        })
+        .collect()
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // versions deployed before cw2 was set still use the string keys
+    let key_migration = CONTRACT.may_load(deps.storage)?.is_none();
+    if key_migration {
+        KEY_MIGRATION.save(deps.storage, &KeyMigration::default())?;
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    init_total_working_amounts(deps.storage)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("key_migration", key_migration.to_string()))
+}
+
+/// Rewrites the next page of legacy storage keys, only owner or second_owner can perform the action
+pub fn execute_migrate_keys(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_dists: Option<Vec<(String, String)>>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if info.sender != config.owner
+        && (config.second_owner.is_empty()
+            || (!config.second_owner.is_empty() && info.sender != config.second_owner))
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut migration = KEY_MIGRATION
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoKeyMigration {})?;
+    // calling again without new pairs once done leaves the skipped entries behind
+    let finalize = pool_dists.is_none() && is_key_migration_done(&migration);
+    // new pairs restart the run, so that the entries skipped so far are matched again
+    if let Some(pool_dists) = pool_dists {
+        migration = KeyMigration {
+            pool_dists,
+            migrated: migration.migrated,
+            ..KeyMigration::default()
+        };
+    }
+
+    let limit = limit
+        .unwrap_or(config.default_limit)
+        .min(MAX_KEY_MIGRATION_LIMIT) as usize;
+    let done = migrate_composite_keys(deps.storage, deps.api, &mut migration, limit)?;
+    // skipped entries keep the run open until it is restarted with their pairs or finalized
+    if done && (migration.skipped == 0 || finalize) {
+        KEY_MIGRATION.remove(deps.storage);
+    } else {
+        KEY_MIGRATION.save(deps.storage, &migration)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate_keys")
+        .add_attribute("migrated_entries", migration.migrated.to_string())
+        .add_attribute("skipped_entries", migration.skipped.to_string())
+        .add_attribute("done", done.to_string()))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg.id {
         INSTANTIATE_REPLY_ID => handle_instantiate_reply(deps, msg),
-        id => Err(StdError::generic_err(format!("Unknown reply id: {}", id))),
+        id => Err(ContractError::UnknownReplyId { id }),
     }
 }
 
-fn handle_instantiate_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
+fn handle_instantiate_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
     // Handle the msg data and save the contract address
     // See: https://github.com/CosmWasm/cw-plus/blob/main/packages/utils/src/parse_reply.rs
     let res = parse_reply_instantiate_data(msg);
 
     if res.is_err() {
-        return Err(StdError::generic_err(
-            "no successful response get from parsing reply data",
-        ));
+        return Err(ContractError::InvalidReplyData {});
     }
 
     let pool_address = CURRENT_POOL_ADDRESS.load(deps.storage)?;
@@ -1945,14 +2590,11 @@ fn handle_instantiate_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
         &pool_address,
     )?;
 
-    Ok(Response::new().add_attribute(
-        "liquidity_token_addr",
-        stakeable_info.liquidity_token,
-    ))
+    Ok(Response::new().add_attribute("liquidity_token_addr", stakeable_info.liquidity_token))
 }
 
 // #[cfg_attr(not(feature = "library"), entry_point)]
-// pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+// pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
 //     let data = msg.result.unwrap().data.unwrap();
 //     let res: MsgInstantiateContractResponse =
 //         Message::parse_from_bytes(data.as_slice()).map_err(|_| {
@@ -0,0 +1,77 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("No pool found")]
+    PoolNotFound {},
+
+    #[error("No asset staked in the pool, you need to stake asset in the pool first")]
+    NoStakedAsset {},
+
+    #[error("Incorrect asset provided")]
+    IncorrectAsset {},
+
+    #[error("Invalid cw20 hook message")]
+    InvalidCw20HookMsg {},
+
+    #[error("Token already exists in list")]
+    TokenAlreadyExists {},
+
+    #[error("wait_time_for_distribution_in_seconds cannot be set to 0")]
+    InvalidDistributionWaitTime {},
+
+    #[error("Sorry for inconvenience, system is under maintenance. Kindly check again later")]
+    Frozen {},
+
+    #[error("Invalid token provided, Kindly provide only FLP tokens")]
+    InvalidFlpToken {},
+
+    #[error("FLP token not provided, Kindly provide all flp tokens")]
+    IncompleteFlpAmount {},
+
+    #[error("User claim reward time not reached yet")]
+    ClaimTimeNotReached {},
+
+    #[error("Not a valid address passed")]
+    InvalidStartAfter {},
+
+    #[error("Insufficient funds")]
+    InsufficientFunds {},
+
+    #[error("Please wait patiently for the specified time")]
+    DistributionNotReady {},
+
+    #[error("distributeable_token does not exist in the pool")]
+    DistributeableTokenNotFound {},
+
+    #[error("User boost has not decayed, nothing to kick")]
+    KickNotAllowed {},
+
+    #[error("Lock tier multiplier must be at least 1")]
+    InvalidLockTierMultiplier {},
+
+    #[error("Lock tier {tier} does not exist")]
+    UnknownLockTier { tier: u64 },
+
+    #[error("Staked tokens are locked until {unlock_time}")]
+    StakeLocked { unlock_time: u64 },
+
+    #[error("Storage keys are being migrated, run MigrateKeys until it is done")]
+    KeyMigrationPending {},
+
+    #[error("No storage key migration in progress")]
+    NoKeyMigration {},
+
+    #[error("Unknown reply id: {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("No successful response get from parsing reply data")]
+    InvalidReplyData {},
+}
@@ -1,5 +1,9 @@
+pub mod boost;
 pub mod contract;
 //pub mod math;
+mod error;
+pub mod lock_tier;
+mod migration;
 mod parse_reply;
 pub mod state;
 
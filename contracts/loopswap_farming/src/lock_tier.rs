@@ -0,0 +1,156 @@
+use cosmwasm_std::{Addr, Order, StdResult, Storage, Uint128};
+
+use crate::error::ContractError;
+use crate::state::{TierStake, LOCK_TIERS, TOTAL_STAKED, TOTAL_TIER_BONUS, USER_TIER_STAKES};
+
+// weight a tier stake adds on top of its amount
+fn tier_bonus(stake: &TierStake) -> Uint128 {
+    stake.amount * stake.multiplier - stake.amount
+}
+
+/// Staked amount with every tier stake weighted by its multiplier
+pub fn get_user_weighted_stake(
+    store: &dyn Storage,
+    user: &Addr,
+    pool: &Addr,
+    user_staked: Uint128,
+) -> StdResult<Uint128> {
+    let mut weighted = user_staked;
+    for item in USER_TIER_STAKES
+        .prefix((user, pool))
+        .range(store, None, None, Order::Ascending)
+    {
+        let (_, stake) = item?;
+        weighted += tier_bonus(&stake);
+    }
+    Ok(weighted)
+}
+
+pub fn get_total_weighted_stake(store: &dyn Storage, pool_address: String) -> StdResult<Uint128> {
+    let total_staked = TOTAL_STAKED
+        .may_load(store, pool_address.to_string())?
+        .unwrap_or_else(Uint128::zero);
+    let total_bonus = TOTAL_TIER_BONUS
+        .may_load(store, pool_address)?
+        .unwrap_or_else(Uint128::zero);
+    Ok(total_staked + total_bonus)
+}
+
+/// Locks `amount` of a new stake in the tier. Adding to a tier the user already has
+/// restarts its lock with the current terms of the tier.
+pub fn add_tier_stake(
+    store: &mut dyn Storage,
+    now: u64,
+    user: &Addr,
+    pool: &Addr,
+    tier: u64,
+    amount: Uint128,
+) -> Result<TierStake, ContractError> {
+    let lock_tier = LOCK_TIERS
+        .may_load(store, tier)?
+        .ok_or(ContractError::UnknownLockTier { tier })?;
+
+    let mut stake = USER_TIER_STAKES
+        .may_load(store, (user, pool, tier))?
+        .unwrap_or(TierStake {
+            amount: Uint128::zero(),
+            multiplier: lock_tier.multiplier,
+            unlock_time: now,
+        });
+    let old_bonus = tier_bonus(&stake);
+    stake.amount += amount;
+    stake.multiplier = lock_tier.multiplier;
+    stake.unlock_time = now + lock_tier.lock_period;
+
+    let total_bonus = TOTAL_TIER_BONUS
+        .may_load(store, pool.to_string())?
+        .unwrap_or_else(Uint128::zero)
+        + tier_bonus(&stake)
+        - old_bonus;
+    TOTAL_TIER_BONUS.save(store, pool.to_string(), &total_bonus)?;
+    USER_TIER_STAKES.save(store, (user, pool, tier), &stake)?;
+    Ok(stake)
+}
+
+/// Drops the tier stakes of the user in the pool whose lock is over, their amount keeps being
+/// staked without a bonus. Returns whether any stake was dropped.
+pub fn release_expired_tier_stakes(
+    store: &mut dyn Storage,
+    now: u64,
+    user: &Addr,
+    pool: &Addr,
+) -> StdResult<bool> {
+    let expired = USER_TIER_STAKES
+        .prefix((user, pool))
+        .range(store, None, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, stake)) => stake.unlock_time <= now,
+            Err(_) => true,
+        })
+        .collect::<StdResult<Vec<(u64, TierStake)>>>()?;
+
+    let mut bonus = Uint128::zero();
+    for (tier, stake) in expired.iter() {
+        bonus += tier_bonus(stake);
+        USER_TIER_STAKES.remove(store, (user, pool, *tier));
+    }
+    if !bonus.is_zero() {
+        let total_bonus = TOTAL_TIER_BONUS.load(store, pool.to_string())? - bonus;
+        TOTAL_TIER_BONUS.save(store, pool.to_string(), &total_bonus)?;
+    }
+    Ok(!expired.is_empty())
+}
+
+/// Amount of the user's stake in the pool still locked in a tier, with the last unlock time
+pub fn get_locked_stake(
+    store: &dyn Storage,
+    now: u64,
+    user: &Addr,
+    pool: &Addr,
+) -> StdResult<(Uint128, u64)> {
+    let mut locked = Uint128::zero();
+    let mut unlock_time = now;
+    for item in USER_TIER_STAKES
+        .prefix((user, pool))
+        .range(store, None, None, Order::Ascending)
+    {
+        let (_, stake) = item?;
+        if stake.unlock_time > now {
+            locked += stake.amount;
+            unlock_time = unlock_time.max(stake.unlock_time);
+        }
+    }
+    Ok((locked, unlock_time))
+}
+
+/// Releases all tier stakes of the user in the pool, failing while any of them is locked
+pub fn remove_tier_stakes(
+    store: &mut dyn Storage,
+    now: u64,
+    user: &Addr,
+    pool: &Addr,
+) -> Result<(), ContractError> {
+    let stakes = USER_TIER_STAKES
+        .prefix((user, pool))
+        .range(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(u64, TierStake)>>>()?;
+    if let Some(unlock_time) = stakes
+        .iter()
+        .map(|(_, stake)| stake.unlock_time)
+        .filter(|unlock_time| *unlock_time > now)
+        .max()
+    {
+        return Err(ContractError::StakeLocked { unlock_time });
+    }
+
+    let mut bonus = Uint128::zero();
+    for (tier, stake) in stakes {
+        bonus += tier_bonus(&stake);
+        USER_TIER_STAKES.remove(store, (user, pool, tier));
+    }
+    if !bonus.is_zero() {
+        let total_bonus = TOTAL_TIER_BONUS.load(store, pool.to_string())? - bonus;
+        TOTAL_TIER_BONUS.save(store, pool.to_string(), &total_bonus)?;
+    }
+    Ok(())
+}
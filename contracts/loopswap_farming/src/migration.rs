@@ -0,0 +1,291 @@
+use cosmwasm_std::{Addr, Api, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Map, PrimaryKey};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::state::{
+    KeyMigration, RewardInfo, LEGACY_POOL_COMPOUNDED_INDEX_MAP, LEGACY_POOL_REWARD_INDEX_MAP,
+    LEGACY_TOTAL_ACCUMULATED_DISTRIBUTED_AMOUNT_IN_POOL_MAP, LEGACY_TOTAL_REWARDS_IN_POOL,
+    LEGACY_USER_AUTO_COMPOUND_SUBSCRIPTION_MAP, LEGACY_USER_COMPOUNDED_REWARD_INFO_MAP,
+    LEGACY_USER_REWARD_INFO_MAP, LEGACY_USER_REWARD_STARTING_TIME_MAP, LEGACY_USER_STAKED_AMOUNT,
+    POOL_COMPOUNDED_INDEX_MAP, POOL_REWARD_INDEX_MAP, POOL_STAKERS, STAKEABLE_INFOS,
+    TOTAL_ACCUMULATED_DISTRIBUTED_AMOUNT_IN_POOL_MAP, TOTAL_REWARDS_IN_POOL,
+    USER_AUTO_COMPOUND_SUBSCRIPTION_MAP, USER_COMPOUNDED_REWARD_INFO_MAP, USER_REWARD_INFO_MAP,
+    USER_REWARD_STARTING_TIME_MAP, USER_STAKED_AMOUNT,
+};
+
+const LAST_STEP: u8 = 5;
+
+/// Rewrites up to `limit` entries of the concatenated string keys of the old storage layout
+/// into composite keys, continuing where `migration` stopped. Returns whether it is done.
+///
+/// Pool and distribution token parts are known from `STAKEABLE_INFOS` and the extra pairs of
+/// `migration`, so a legacy key is split by matching its suffix against them, longest first.
+/// Entries that don't end with a known pool, or whose remaining prefix isn't a valid address,
+/// are left untouched and counted as skipped.
+pub fn migrate_composite_keys(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    migration: &mut KeyMigration,
+    limit: usize,
+) -> StdResult<bool> {
+    let mut pool_dists: Vec<(String, String)> = migration.pool_dists.clone();
+    for item in STAKEABLE_INFOS.range(storage, None, None, Order::Ascending) {
+        let (_, stakeable_token) = item?;
+        for dist in stakeable_token.distribution.iter() {
+            pool_dists.push((stakeable_token.token.to_string(), dist.token.to_string()));
+        }
+        pool_dists.push((stakeable_token.token, String::new()));
+    }
+    let mut pools: Vec<String> = pool_dists.iter().map(|(pool, _)| pool.clone()).collect();
+    pools.sort();
+    pools.dedup();
+    pools.sort_by_key(|pool| std::cmp::Reverse(pool.len()));
+    pool_dists.retain(|(_, dist)| !dist.is_empty());
+    pool_dists.sort_by_key(|(pool, dist)| std::cmp::Reverse(pool.len() + dist.len()));
+
+    let mut remaining = limit;
+    while remaining > 0 && migration.step <= LAST_STEP {
+        let visited = match migration.step {
+            // pool + distribution token, as many entries as there are distribution tokens
+            0 => {
+                for (pool, dist) in pool_dists.iter() {
+                    migration.migrated += move_pool_dist_entries(storage, pool, dist)?;
+                }
+                migration.step += 1;
+                pool_dists.len()
+            }
+            // user + pool
+            1 => migrate_page(
+                storage,
+                LEGACY_USER_STAKED_AMOUNT,
+                migration,
+                remaining,
+                |storage, legacy_key, amount| match split_user_pool(api, legacy_key, &pools) {
+                    Some((user, pool)) => {
+                        let pool = Addr::unchecked(pool);
+                        if !amount.is_zero() {
+                            USER_STAKED_AMOUNT.save(storage, (&user, &pool), &amount)?;
+                            POOL_STAKERS.save(storage, (&pool, &user), &true)?;
+                        }
+                        Ok(true)
+                    }
+                    None => Ok(false),
+                },
+            )?,
+            2 => migrate_page(
+                storage,
+                LEGACY_USER_REWARD_STARTING_TIME_MAP,
+                migration,
+                remaining,
+                |storage, legacy_key, time| match split_user_pool(api, legacy_key, &pools) {
+                    Some((user, pool)) => {
+                        let pool = Addr::unchecked(pool);
+                        USER_REWARD_STARTING_TIME_MAP.save(storage, (&user, &pool), &time)?;
+                        Ok(true)
+                    }
+                    None => Ok(false),
+                },
+            )?,
+            3 => migrate_page(
+                storage,
+                LEGACY_USER_AUTO_COMPOUND_SUBSCRIPTION_MAP,
+                migration,
+                remaining,
+                |storage, legacy_key, subscribed| match split_user_pool(api, legacy_key, &pools) {
+                    Some((user, pool)) => {
+                        let pool = Addr::unchecked(pool);
+                        USER_AUTO_COMPOUND_SUBSCRIPTION_MAP.save(
+                            storage,
+                            (&user, &pool),
+                            &subscribed,
+                        )?;
+                        Ok(true)
+                    }
+                    None => Ok(false),
+                },
+            )?,
+            // user + pool + distribution token
+            4 => migrate_page(
+                storage,
+                LEGACY_USER_REWARD_INFO_MAP,
+                migration,
+                remaining,
+                |storage, legacy_key, info| {
+                    move_user_reward_info(
+                        storage,
+                        api,
+                        USER_REWARD_INFO_MAP,
+                        legacy_key,
+                        info,
+                        &pool_dists,
+                    )
+                },
+            )?,
+            _ => migrate_page(
+                storage,
+                LEGACY_USER_COMPOUNDED_REWARD_INFO_MAP,
+                migration,
+                remaining,
+                |storage, legacy_key, info| {
+                    move_user_reward_info(
+                        storage,
+                        api,
+                        USER_COMPOUNDED_REWARD_INFO_MAP,
+                        legacy_key,
+                        info,
+                        &pool_dists,
+                    )
+                },
+            )?,
+        };
+        remaining = remaining.saturating_sub(visited);
+    }
+
+    Ok(is_key_migration_done(migration))
+}
+
+/// Whether every legacy map of `migration` has been visited
+pub fn is_key_migration_done(migration: &KeyMigration) -> bool {
+    migration.step > LAST_STEP
+}
+
+/// Visits up to `limit` entries of `legacy` after the cursor of `migration` and removes the
+/// ones `migrate_entry` moved. The cursor moves on to the next map once `legacy` is exhausted.
+/// Returns the number of visited entries.
+fn migrate_page<T, F>(
+    storage: &mut dyn Storage,
+    legacy: Map<String, T>,
+    migration: &mut KeyMigration,
+    limit: usize,
+    mut migrate_entry: F,
+) -> StdResult<usize>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnMut(&mut dyn Storage, &str, T) -> StdResult<bool>,
+{
+    let start = migration.start_after.clone().map(Bound::exclusive);
+    let entries = legacy
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<(String, T)>>>()?;
+    let visited = entries.len();
+
+    for (legacy_key, value) in entries {
+        if migrate_entry(storage, &legacy_key, value)? {
+            legacy.remove(storage, legacy_key.clone());
+            migration.migrated += 1;
+        } else {
+            migration.skipped += 1;
+        }
+        migration.start_after = Some(legacy_key);
+    }
+    if visited < limit {
+        migration.step += 1;
+        migration.start_after = None;
+    }
+
+    Ok(visited)
+}
+
+fn move_pool_dist_entries(storage: &mut dyn Storage, pool: &str, dist: &str) -> StdResult<u64> {
+    let legacy_key = format!("{}{}", pool, dist);
+    let pool = Addr::unchecked(pool);
+    let dist = Addr::unchecked(dist);
+    Ok(move_entry(
+        storage,
+        LEGACY_TOTAL_REWARDS_IN_POOL,
+        &legacy_key,
+        TOTAL_REWARDS_IN_POOL,
+        (&pool, &dist),
+    )? + move_entry(
+        storage,
+        LEGACY_TOTAL_ACCUMULATED_DISTRIBUTED_AMOUNT_IN_POOL_MAP,
+        &legacy_key,
+        TOTAL_ACCUMULATED_DISTRIBUTED_AMOUNT_IN_POOL_MAP,
+        (&pool, &dist),
+    )? + move_entry(
+        storage,
+        LEGACY_POOL_REWARD_INDEX_MAP,
+        &legacy_key,
+        POOL_REWARD_INDEX_MAP,
+        (&pool, &dist),
+    )? + move_entry(
+        storage,
+        LEGACY_POOL_COMPOUNDED_INDEX_MAP,
+        &legacy_key,
+        POOL_COMPOUNDED_INDEX_MAP,
+        (&pool, &dist),
+    )?)
+}
+
+fn move_entry<'a, K, T>(
+    storage: &mut dyn Storage,
+    legacy: Map<String, T>,
+    legacy_key: &str,
+    map: Map<'a, K, T>,
+    key: K,
+) -> StdResult<u64>
+where
+    K: PrimaryKey<'a>,
+    T: Serialize + DeserializeOwned,
+{
+    match legacy.may_load(storage, legacy_key.to_string())? {
+        Some(value) => {
+            map.save(storage, key, &value)?;
+            legacy.remove(storage, legacy_key.to_string());
+            Ok(1)
+        }
+        None => Ok(0),
+    }
+}
+
+fn move_user_reward_info(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    map: Map<(&Addr, &Addr, &Addr), RewardInfo>,
+    legacy_key: &str,
+    info: RewardInfo,
+    pool_dists: &[(String, String)],
+) -> StdResult<bool> {
+    match split_user_pool_dist(api, legacy_key, pool_dists) {
+        Some((user, (pool, dist))) => {
+            map.save(
+                storage,
+                (&user, &Addr::unchecked(pool), &Addr::unchecked(dist)),
+                &info,
+            )?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+fn split_user(api: &dyn Api, legacy_key: &str, suffix: &str) -> Option<Addr> {
+    let user = legacy_key.strip_suffix(suffix)?;
+    if user.is_empty() {
+        return None;
+    }
+    api.addr_validate(user).ok()
+}
+
+fn split_user_pool<'p>(
+    api: &dyn Api,
+    legacy_key: &str,
+    pools: &'p [String],
+) -> Option<(Addr, &'p str)> {
+    pools
+        .iter()
+        .find_map(|pool| split_user(api, legacy_key, pool).map(|user| (user, pool.as_str())))
+}
+
+fn split_user_pool_dist<'p>(
+    api: &dyn Api,
+    legacy_key: &str,
+    pool_dists: &'p [(String, String)],
+) -> Option<(Addr, (&'p str, &'p str))> {
+    pool_dists.iter().find_map(|(pool, dist)| {
+        split_user(api, legacy_key, &format!("{}{}", pool, dist))
+            .map(|user| (user, (pool.as_str(), dist.as_str())))
+    })
+}
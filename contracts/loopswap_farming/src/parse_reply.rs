@@ -13,6 +13,9 @@ pub struct MsgInstantiateContractResponse {
     pub data: Option<Binary>,
 }
 
+// Farming only handles instantiate replies, the execute reply parsing is
+// kept for its tests.
+#[cfg(test)]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MsgExecuteContractResponse {
     pub data: Option<Binary>,
@@ -115,15 +118,16 @@ pub fn parse_reply_instantiate_data(
     parse_instantiate_response_data(&data.0)
 }
 
-// pub fn parse_reply_execute_data(msg: Reply) -> Result<MsgExecuteContractResponse, ParseReplyError> {
-//     let data = msg
-//         .result
-//         .into_result()
-//         .map_err(ParseReplyError::SubMsgFailure)?
-//         .data
-//         .ok_or_else(|| ParseReplyError::ParseFailure("Missing reply data".to_owned()))?;
-//     parse_execute_response_data(&data.0)
-// }
+#[cfg(test)]
+pub fn parse_reply_execute_data(msg: Reply) -> Result<MsgExecuteContractResponse, ParseReplyError> {
+    let data = msg
+        .result
+        .into_result()
+        .map_err(ParseReplyError::SubMsgFailure)?
+        .data
+        .ok_or_else(|| ParseReplyError::ParseFailure("Missing reply data".to_owned()))?;
+    parse_execute_response_data(&data.0)
+}
 
 pub fn parse_instantiate_response_data(
     data: &[u8],
@@ -142,15 +146,16 @@ pub fn parse_instantiate_response_data(
     })
 }
 
-// pub fn parse_execute_response_data(
-//     data: &[u8],
-// ) -> Result<MsgExecuteContractResponse, ParseReplyError> {
-//     // Manual protobuf decoding
-//     let mut data = data.to_vec();
-//     let inner_data = parse_protobuf_bytes(&mut data, 1)?;
+#[cfg(test)]
+pub fn parse_execute_response_data(
+    data: &[u8],
+) -> Result<MsgExecuteContractResponse, ParseReplyError> {
+    // Manual protobuf decoding
+    let mut data = data.to_vec();
+    let inner_data = parse_protobuf_bytes(&mut data, 1)?;
 
-//     Ok(MsgExecuteContractResponse { data: inner_data })
-// }
+    Ok(MsgExecuteContractResponse { data: inner_data })
+}
 
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum ParseReplyError {
@@ -1,9 +1,10 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Addr, Decimal, Uint128};
 use cw_storage_plus::{Item, Map};
 use loopswap::asset::StakeableToken;
+use loopswap::farming::LockTier;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct Config {
@@ -19,6 +20,28 @@ pub struct Config {
     pub token_code_id: u64,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct TierStake {
+    pub amount: Uint128,
+    pub multiplier: Decimal,
+    pub unlock_time: u64,
+}
+
+/// Progress of the rewrite of the legacy string keys, see `migrate_composite_keys`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct KeyMigration {
+    /// (pool, distribution token) pairs matched on top of STAKEABLE_INFOS, e.g. distribution
+    /// tokens deleted from their pool
+    pub pool_dists: Vec<(String, String)>,
+    /// Legacy map being rewritten
+    pub step: u8,
+    /// Last key visited in that map
+    pub start_after: Option<String>,
+    pub migrated: u64,
+    /// Legacy entries that matched no pool, left in place
+    pub skipped: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct RewardInfo {
     pub reward_index: Uint128,
@@ -29,22 +52,63 @@ pub const CONFIG: Item<Config> = Item::new("config");
 pub const STAKEABLE_INFOS: Map<String, StakeableToken> = Map::new("StakeableInfos");
 pub const UNCLAIMED_DISTRIBUTED_TOKEN_AMOUNT_MAP: Map<String, Uint128> =
     Map::new("unclaimedDistributedTokenAmountMap");
-pub const USER_STAKED_AMOUNT: Map<String, Uint128> = Map::new("rewardTokenIssued");
 pub const TOTAL_STAKED: Map<String, Uint128> = Map::new("totalStaked");
-pub const TOTAL_REWARDS_IN_POOL: Map<String, Uint128> = Map::new("totalRewardsInPool");
-pub const TOTAL_ACCUMULATED_DISTRIBUTED_AMOUNT_IN_POOL_MAP: Map<String, Uint128> =
-    Map::new("totalAccumulatedDistributedTokenAmountMapInPools");
-pub const POOL_REWARD_INDEX_MAP: Map<String, Uint128> = Map::new("rewardIndexMap");
-pub const USER_REWARD_INFO_MAP: Map<String, RewardInfo> = Map::new("userRewardInfoxMap");
-pub const USER_REWARD_STARTING_TIME_MAP: Map<String, u64> = Map::new("userRewardStartingTimeMap");
 pub const POOL_LAST_DISTRIBUTION_TIME_IN_SECONDS: Map<String, u64> =
     Map::new("liquidityAndDevTokenMap");
-pub const USER_AUTO_COMPOUND_SUBSCRIPTION_MAP: Map<String, bool> =
-    Map::new("UserAutoCompoundSubscriptionMap");
 pub const POOL_TOTAL_COMPOUNDED_AMOUNT: Map<String, Uint128> = Map::new("totalCompoundedStaked");
-pub const POOL_COMPOUNDED_INDEX_MAP: Map<String, Uint128> = Map::new("CompoundedIndexMap");
-pub const USER_COMPOUNDED_REWARD_INFO_MAP: Map<String, RewardInfo> =
-    Map::new("userCompoundedInfoxMap");
 pub const CURRENT_POOL_ADDRESS: Item<String> = Item::new("CurrentPoolAddress");
 pub const LIQUIDITY_TOKEN_MAP: Map<String, String> = Map::new("LiquidityTokenMap");
-pub const LAST_CLAIMED_REWARD_TIME: Map<String, u64> = Map::new("LastClaimedRewardTime");
\ No newline at end of file
+pub const LAST_CLAIMED_REWARD_TIME: Map<String, u64> = Map::new("LastClaimedRewardTime");
+// staking contract whose voting power boosts rewards, boosting is off while unset
+pub const BOOST_STAKING_CONTRACT: Item<Addr> = Item::new("boostStakingContract");
+// sum of the working amounts of all stakers in the pool
+pub const TOTAL_WORKING_AMOUNT: Map<String, Uint128> = Map::new("totalWorkingAmount");
+pub const LOCK_TIERS: Map<u64, LockTier> = Map::new("lockTiers");
+// extra weight of the stakes locked in tiers of the pool, on top of TOTAL_STAKED
+pub const TOTAL_TIER_BONUS: Map<String, Uint128> = Map::new("totalTierBonus");
+
+// (user, pool)
+pub const USER_STAKED_AMOUNT: Map<(&Addr, &Addr), Uint128> = Map::new("userStakedAmount");
+pub const USER_REWARD_STARTING_TIME_MAP: Map<(&Addr, &Addr), u64> =
+    Map::new("userRewardStartingTime");
+pub const USER_AUTO_COMPOUND_SUBSCRIPTION_MAP: Map<(&Addr, &Addr), bool> =
+    Map::new("userAutoCompoundSubscription");
+// boosted stake the user earns rewards on, absent for stakes older than boosting
+pub const USER_WORKING_AMOUNT: Map<(&Addr, &Addr), Uint128> = Map::new("userWorkingAmount");
+// (pool, user), reverse index of USER_STAKED_AMOUNT
+pub const POOL_STAKERS: Map<(&Addr, &Addr), bool> = Map::new("poolStakers");
+
+// (pool, distribution token)
+pub const TOTAL_REWARDS_IN_POOL: Map<(&Addr, &Addr), Uint128> = Map::new("poolTotalRewards");
+pub const TOTAL_ACCUMULATED_DISTRIBUTED_AMOUNT_IN_POOL_MAP: Map<(&Addr, &Addr), Uint128> =
+    Map::new("poolAccumulatedDistributedAmount");
+pub const POOL_REWARD_INDEX_MAP: Map<(&Addr, &Addr), Uint128> = Map::new("poolRewardIndex");
+pub const POOL_COMPOUNDED_INDEX_MAP: Map<(&Addr, &Addr), Uint128> = Map::new("poolCompoundedIndex");
+
+// (user, pool, lock tier)
+pub const USER_TIER_STAKES: Map<(&Addr, &Addr, u64), TierStake> = Map::new("userTierStakes");
+
+// (user, pool, distribution token)
+pub const USER_REWARD_INFO_MAP: Map<(&Addr, &Addr, &Addr), RewardInfo> = Map::new("userRewardInfo");
+pub const USER_COMPOUNDED_REWARD_INFO_MAP: Map<(&Addr, &Addr, &Addr), RewardInfo> =
+    Map::new("userCompoundedRewardInfo");
+
+// present while the legacy keys are being rewritten or skipped entries are left, the contract
+// only accepts MigrateKeys
+pub const KEY_MIGRATION: Item<KeyMigration> = Item::new("keyMigration");
+
+// Storage layout before the composite keys, where user, pool and distribution token
+// addresses were concatenated into a single string. Only read by MigrateKeys.
+pub const LEGACY_USER_STAKED_AMOUNT: Map<String, Uint128> = Map::new("rewardTokenIssued");
+pub const LEGACY_USER_REWARD_STARTING_TIME_MAP: Map<String, u64> =
+    Map::new("userRewardStartingTimeMap");
+pub const LEGACY_USER_AUTO_COMPOUND_SUBSCRIPTION_MAP: Map<String, bool> =
+    Map::new("UserAutoCompoundSubscriptionMap");
+pub const LEGACY_TOTAL_REWARDS_IN_POOL: Map<String, Uint128> = Map::new("totalRewardsInPool");
+pub const LEGACY_TOTAL_ACCUMULATED_DISTRIBUTED_AMOUNT_IN_POOL_MAP: Map<String, Uint128> =
+    Map::new("totalAccumulatedDistributedTokenAmountMapInPools");
+pub const LEGACY_POOL_REWARD_INDEX_MAP: Map<String, Uint128> = Map::new("rewardIndexMap");
+pub const LEGACY_POOL_COMPOUNDED_INDEX_MAP: Map<String, Uint128> = Map::new("CompoundedIndexMap");
+pub const LEGACY_USER_REWARD_INFO_MAP: Map<String, RewardInfo> = Map::new("userRewardInfoxMap");
+pub const LEGACY_USER_COMPOUNDED_REWARD_INFO_MAP: Map<String, RewardInfo> =
+    Map::new("userCompoundedInfoxMap");
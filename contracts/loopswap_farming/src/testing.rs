@@ -1,15 +1,27 @@
-use crate::contract::{execute, instantiate, query, query_stakeable_info, reply};
+use crate::contract::{execute, instantiate, migrate, query, query_stakeable_info, reply};
+use crate::error::ContractError;
+use crate::state::{
+    RewardInfo, KEY_MIGRATION, LEGACY_POOL_REWARD_INDEX_MAP, LEGACY_USER_REWARD_INFO_MAP,
+    LEGACY_USER_REWARD_STARTING_TIME_MAP, LEGACY_USER_STAKED_AMOUNT, POOL_REWARD_INDEX_MAP,
+    POOL_STAKERS, TOTAL_STAKED, TOTAL_WORKING_AMOUNT, USER_REWARD_INFO_MAP,
+    USER_REWARD_STARTING_TIME_MAP, USER_STAKED_AMOUNT,
+};
 use loopswap::mock_querier::mock_dependencies;
 
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{from_binary, ContractResult, Env, Timestamp, Uint128};
+use cosmwasm_std::{attr, from_binary, Addr, Decimal, Env, OwnedDeps, Timestamp, Uint128};
 use cosmwasm_std::{
-    to_binary, Coin, CosmosMsg, Reply, StdError, SubMsg, SubMsgExecutionResponse, WasmMsg,
+    to_binary, Coin, CosmosMsg, Reply, SubMsg, SubMsgResponse, SubMsgResult, WasmMsg,
 };
+use cw2::CONTRACT;
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
 use loopswap::asset::{StakeablePairedDistributionTokenInfo, StakeableToken};
-use loopswap::farming::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg};
+use loopswap::factory::MigrateMsg;
+use loopswap::farming::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, QueryUserPositionResponse,
+    QueryUserRewardInPoolResponse, UserLockTierResponse,
+};
 
 fn mock_env_time(time: Timestamp) -> Env {
     let mut env = mock_env();
@@ -55,7 +67,7 @@ mod tests {
             update_config_msg.clone(),
         );
         match res {
-            Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+            Err(ContractError::Unauthorized {}) => {}
             _ => panic!("Invalid error"),
         }
 
@@ -140,6 +152,17 @@ mod tests {
 
         let _result = instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
 
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UpdateWaitTimeForDistribution {
+                wait_time_for_distribution_in_seconds: 0,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidDistributionWaitTime {});
+
         let update_config_msg = ExecuteMsg::UpdateWaitTimeForDistribution {
             wait_time_for_distribution_in_seconds: 8400,
         };
@@ -541,7 +564,7 @@ mod tests {
         let stake_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "loop_staker1".to_string(),
             amount: Uint128::from(100u128),
-            msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+            msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
         });
 
         execute(
@@ -555,7 +578,7 @@ mod tests {
         let stake_msg2 = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "loop_staker2".to_string(),
             amount: Uint128::from(100u128),
-            msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+            msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
         });
 
         execute(
@@ -596,46 +619,74 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
-    fn test_stake_with_unstakeable_token() {
-        let mut deps = mock_dependencies(&[Coin {
-            denom: "uusd".to_string(),
-            amount: Uint128::from(200u128),
-        }]);
-
-        deps.querier.with_token_balances(&[
-            (
-                &"liquidity0000".to_string(),
-                &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())],
-            ),
-            (&"asset0000".to_string(), &[]),
-        ]);
-
+    fn test_pools_of_user_and_stakers_in_pool() {
+        let mut deps = mock_dependencies(&[]);
+        let info = mock_info(&"loop_staker1".to_string(), &[]);
         let init_msg = InstantiateMsg {
             reserve_addr: "reserve addr".to_string(),
             token_code_id: 6,
         };
-        let info = mock_info(&"loop_staker1".to_string(), &[]);
-        let _result = instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+        instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
 
-        let add_stakeable_token_msg = ExecuteMsg::AddStakeableToken {
-            token: "asset0000".to_string(),
-        };
+        for pool in ["asset0000", "asset0001"] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                ExecuteMsg::AddStakeableToken {
+                    token: pool.to_string(),
+                },
+            )
+            .unwrap();
+        }
 
-        execute(
-            deps.as_mut(),
-            mock_env(),
-            info.clone(),
-            add_stakeable_token_msg,
+        let stakes = [
+            ("loop_staker1", "asset0000", 100u128),
+            ("loop_staker1", "asset0001", 50u128),
+            ("loop_staker2", "asset0000", 70u128),
+        ];
+        for (staker, pool, amount) in stakes {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(pool, &[]),
+                ExecuteMsg::Receive(Cw20ReceiveMsg {
+                    sender: staker.to_string(),
+                    amount: Uint128::from(amount),
+                    msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
+                }),
+            )
+            .unwrap();
+        }
+
+        let pools: Vec<(String, Uint128)> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::QueryPoolsOfUser {
+                    wallet: "loop_staker1".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
         )
         .unwrap();
+        assert_eq!(
+            pools,
+            vec![
+                ("asset0000".to_string(), Uint128::from(100u128)),
+                ("asset0001".to_string(), Uint128::from(50u128)),
+            ]
+        );
 
-        let stakeable_tokens: Vec<StakeableToken> = from_binary(
+        let stakers: Vec<(String, Uint128)> = from_binary(
             &query(
                 deps.as_ref(),
                 mock_env(),
-                QueryMsg::QueryListOfStakeableTokens {
-                    start_after: None,
+                QueryMsg::QueryStakersInPool {
+                    pool: "asset0000".to_string(),
+                    start_after: Some("loop_staker1".to_string()),
                     limit: None,
                 },
             )
@@ -643,177 +694,697 @@ mod tests {
         )
         .unwrap();
         assert_eq!(
-            stakeable_tokens,
-            vec![StakeableToken {
-                token: "asset0000".to_string(),
-                distribution: vec![],
-                liquidity_token: "".to_string(),
-            }]
+            stakers,
+            vec![("loop_staker2".to_string(), Uint128::from(70u128))]
         );
-        // testing of stakeable token completed
+    }
 
-        //now testing staking
-        let stake_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: "loop_staker1".to_string(),
-            amount: Uint128::from(100u128),
-            msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
-        });
+    #[test]
+    fn test_user_positions() {
+        let mut deps = mock_dependencies(&[]);
+        let info = mock_info(&"loop_staker1".to_string(), &[]);
+        let init_msg = InstantiateMsg {
+            reserve_addr: "reserve addr".to_string(),
+            token_code_id: 6,
+        };
+        instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
 
+        for pool in ["asset0000", "asset0001"] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                ExecuteMsg::AddStakeableToken {
+                    token: pool.to_string(),
+                },
+            )
+            .unwrap();
+        }
         execute(
             deps.as_mut(),
             mock_env(),
-            mock_info(&"asset0001".to_string(), &[]),
-            stake_msg,
+            info.clone(),
+            ExecuteMsg::UpdateLockTimeFrame {
+                lock_time_frame: 100,
+            },
         )
         .unwrap();
-        let stake_msg2 = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: "loop_staker2".to_string(),
-            amount: Uint128::from(100u128),
-            msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
-        });
         execute(
             deps.as_mut(),
             mock_env(),
-            mock_info(&"asset0001".to_string(), &[]),
-            stake_msg2,
+            info.clone(),
+            ExecuteMsg::UpdateReward {
+                pool: "asset0000".to_string(),
+                rewards: vec![("loop0000".to_string(), Uint128::from(200u128))],
+            },
         )
         .unwrap();
 
-        let user1_staked: Uint128 = from_binary(
+        for pool in ["asset0000", "asset0001"] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(pool, &[]),
+                ExecuteMsg::Receive(Cw20ReceiveMsg {
+                    sender: "loop_staker1".to_string(),
+                    amount: Uint128::from(100u128),
+                    msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
+                }),
+            )
+            .unwrap();
+        }
+
+        // pending rewards come from the pool index, as if rewards were distributed
+        POOL_REWARD_INDEX_MAP
+            .save(
+                deps.as_mut().storage,
+                (&Addr::unchecked("asset0000"), &Addr::unchecked("loop0000")),
+                &Uint128::from(2_000_000_000_000u128),
+            )
+            .unwrap();
+
+        let positions: Vec<QueryUserPositionResponse> = from_binary(
             &query(
                 deps.as_ref(),
                 mock_env(),
-                QueryMsg::QueryStakedByUser {
-                    wallet: info.sender.to_string(),
-                    staked_token: "asset0000".to_string(),
+                QueryMsg::QueryUserPositions {
+                    wallet: "loop_staker1".to_string(),
+                    start_after: None,
+                    limit: None,
                 },
             )
             .unwrap(),
         )
         .unwrap();
+        let unlock_time = mock_env().block.time.seconds() + 100;
+        assert_eq!(
+            positions,
+            vec![
+                QueryUserPositionResponse {
+                    pool: "asset0000".to_string(),
+                    staked: Uint128::from(100u128),
+                    pending_rewards: vec![("loop0000".to_string(), Uint128::from(200u128))],
+                    auto_compound: false,
+                    unlock_time,
+                },
+                QueryUserPositionResponse {
+                    pool: "asset0001".to_string(),
+                    staked: Uint128::from(100u128),
+                    pending_rewards: vec![],
+                    auto_compound: false,
+                    unlock_time,
+                },
+            ]
+        );
 
-        assert_eq!(user1_staked, Uint128::from(100u128));
-
-        let total_staked: Uint128 = from_binary(
+        let positions: Vec<QueryUserPositionResponse> = from_binary(
             &query(
                 deps.as_ref(),
                 mock_env(),
-                QueryMsg::QueryTotalStaked {
-                    staked_token: "asset0000".to_string(),
+                QueryMsg::QueryUserPositions {
+                    wallet: "loop_staker1".to_string(),
+                    start_after: Some("asset0000".to_string()),
+                    limit: Some(1),
                 },
             )
             .unwrap(),
         )
         .unwrap();
-        assert_eq!(total_staked, Uint128::from(200u128));
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].pool, "asset0001".to_string());
     }
 
-    //testing reward assignment and distribution of reward to the respective pool
     #[test]
-    fn test_update_reward_and_distribution() {
-        let mut deps = mock_dependencies(&[Coin {
-            denom: "uusd".to_string(),
-            amount: Uint128::from(200u128),
-        }]);
-
-        deps.querier.with_token_balances(&[
-            (
-                &"liquidity0000".to_string(),
-                &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())],
-            ),
-            (&"asset0000".to_string(), &[]),
-            (
-                &"reward0000".to_string(),
-                &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(10000u128))],
-            ),
-        ]);
-
-        let env = mock_env();
+    fn test_migrate_composite_keys() {
+        let mut deps = mock_dependencies(&[]);
+        let info = mock_info(&"loop_staker1".to_string(), &[]);
         let init_msg = InstantiateMsg {
             reserve_addr: "reserve addr".to_string(),
             token_code_id: 6,
         };
-        let info = mock_info(&"loop_staker1".to_string(), &[]);
-        let _result = instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+        instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::AddStakeableToken {
+                token: "asset0000".to_string(),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UpdateReward {
+                pool: "asset0000".to_string(),
+                rewards: vec![("reward0000".to_string(), Uint128::from(1000u128))],
+            },
+        )
+        .unwrap();
 
-        let add_stakeable_token_msg = ExecuteMsg::AddStakeableToken {
-            token: "asset0000".to_string(),
+        // entries written by the previous version of the contract
+        let reward_info = RewardInfo {
+            reward_index: Uint128::from(7u128),
+            pending_reward: Uint128::from(3u128),
         };
+        let storage = deps.as_mut().storage;
+        LEGACY_USER_STAKED_AMOUNT
+            .save(
+                storage,
+                "loop_staker1asset0000".to_string(),
+                &Uint128::from(100u128),
+            )
+            .unwrap();
+        LEGACY_USER_REWARD_STARTING_TIME_MAP
+            .save(storage, "loop_staker1asset0000".to_string(), &1000u64)
+            .unwrap();
+        TOTAL_STAKED
+            .save(storage, "asset0000".to_string(), &Uint128::from(100u128))
+            .unwrap();
+        LEGACY_POOL_REWARD_INDEX_MAP
+            .save(
+                storage,
+                "asset0000reward0000".to_string(),
+                &Uint128::from(7u128),
+            )
+            .unwrap();
+        LEGACY_USER_REWARD_INFO_MAP
+            .save(
+                storage,
+                "loop_staker1asset0000reward0000".to_string(),
+                &reward_info,
+            )
+            .unwrap();
+        // reward0001 was deleted from the pool, its entries are only known to the owner
+        LEGACY_POOL_REWARD_INDEX_MAP
+            .save(
+                storage,
+                "asset0000reward0001".to_string(),
+                &Uint128::from(9u128),
+            )
+            .unwrap();
+        LEGACY_USER_REWARD_INFO_MAP
+            .save(
+                storage,
+                "loop_staker1asset0000reward0001".to_string(),
+                &reward_info,
+            )
+            .unwrap();
+        // the previous version never set a cw2 version
+        CONTRACT.remove(storage);
 
-        let _res = execute(
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(
+            res.attributes[1],
+            ("key_migration".to_string(), "true".to_string())
+        );
+
+        // nothing else runs until the keys are migrated
+        let err = execute(
             deps.as_mut(),
             mock_env(),
             info.clone(),
-            add_stakeable_token_msg,
+            ExecuteMsg::ClaimReward {
+                pool_address: "asset0000".to_string(),
+            },
         )
-        .unwrap();
+        .unwrap_err();
+        assert_eq!(err, ContractError::KeyMigrationPending {});
 
-        let stakeable_tokens: Vec<StakeableToken> = from_binary(
-            &query(
-                deps.as_ref(),
-                mock_env(),
-                QueryMsg::QueryListOfStakeableTokens {
-                    start_after: None,
-                    limit: None,
-                },
-            )
-            .unwrap(),
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::MigrateKeys {
+                pool_dists: None,
+                limit: Some(2),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // the pool index of reward0000 and the staked amount
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::MigrateKeys {
+                pool_dists: None,
+                limit: Some(2),
+            },
         )
         .unwrap();
         assert_eq!(
-            stakeable_tokens,
-            vec![StakeableToken {
-                token: "asset0000".to_string(),
-                distribution: vec![],
-                liquidity_token: "".to_string(),
-            }]
+            res.attributes,
+            vec![
+                attr("action", "migrate_keys"),
+                attr("migrated_entries", "2"),
+                attr("skipped_entries", "0"),
+                attr("done", "false"),
+            ]
         );
-        // testing of stakeable token completed
 
-        //adding distribution token
+        // reward0001 was deleted from the pool, its user entry matches nothing
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::MigrateKeys {
+                pool_dists: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "migrate_keys"),
+                attr("migrated_entries", "4"),
+                attr("skipped_entries", "1"),
+                attr("done", "true"),
+            ]
+        );
 
-        //now testing staking
-        let stake_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: "loop_staker1".to_string(),
-            amount: Uint128::from(100u128),
-            msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
-        });
+        // the skipped entries keep the run open
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::ClaimReward {
+                pool_address: "asset0000".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::KeyMigrationPending {});
 
-        execute(
+        let res = execute(
             deps.as_mut(),
             mock_env(),
-            mock_info(&"asset0000".to_string(), &[]),
-            stake_msg,
+            info.clone(),
+            ExecuteMsg::MigrateKeys {
+                pool_dists: Some(vec![("asset0000".to_string(), "reward0001".to_string())]),
+                limit: None,
+            },
         )
         .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "migrate_keys"),
+                attr("migrated_entries", "6"),
+                attr("skipped_entries", "0"),
+                attr("done", "true"),
+            ]
+        );
 
-        let user1_staked: Uint128 = from_binary(
+        let user = Addr::unchecked("loop_staker1");
+        let pool = Addr::unchecked("asset0000");
+        let reward = Addr::unchecked("reward0000");
+        let storage = deps.as_ref().storage;
+        assert_eq!(
+            USER_STAKED_AMOUNT.load(storage, (&user, &pool)).unwrap(),
+            Uint128::from(100u128)
+        );
+        assert!(POOL_STAKERS.has(storage, (&pool, &user)));
+        assert_eq!(
+            USER_REWARD_STARTING_TIME_MAP
+                .load(storage, (&user, &pool))
+                .unwrap(),
+            1000u64
+        );
+        assert_eq!(
+            POOL_REWARD_INDEX_MAP
+                .load(storage, (&pool, &reward))
+                .unwrap(),
+            Uint128::from(7u128)
+        );
+        assert_eq!(
+            USER_REWARD_INFO_MAP
+                .load(storage, (&user, &pool, &reward))
+                .unwrap(),
+            reward_info
+        );
+        assert_eq!(
+            USER_REWARD_INFO_MAP
+                .load(storage, (&user, &pool, &Addr::unchecked("reward0001")))
+                .unwrap(),
+            reward_info
+        );
+        assert_eq!(
+            POOL_REWARD_INDEX_MAP
+                .load(storage, (&pool, &Addr::unchecked("reward0001")))
+                .unwrap(),
+            Uint128::from(9u128)
+        );
+        assert!(!LEGACY_USER_STAKED_AMOUNT.has(storage, "loop_staker1asset0000".to_string()));
+        assert!(!LEGACY_USER_REWARD_INFO_MAP
+            .has(storage, "loop_staker1asset0000reward0000".to_string()));
+
+        let user_staked: Uint128 = from_binary(
             &query(
                 deps.as_ref(),
                 mock_env(),
                 QueryMsg::QueryStakedByUser {
-                    wallet: info.sender.to_string(),
+                    wallet: "loop_staker1".to_string(),
                     staked_token: "asset0000".to_string(),
                 },
             )
             .unwrap(),
         )
         .unwrap();
+        assert_eq!(user_staked, Uint128::from(100u128));
 
-        assert_eq!(user1_staked, Uint128::from(100u128));
-
-        let total_staked: Uint128 = from_binary(
+        // stakes from before boosting work with their full amount
+        assert_eq!(
+            TOTAL_WORKING_AMOUNT
+                .load(deps.as_ref().storage, "asset0000".to_string())
+                .unwrap(),
+            Uint128::from(100u128)
+        );
+        let user_working: Uint128 = from_binary(
             &query(
                 deps.as_ref(),
                 mock_env(),
-                QueryMsg::QueryTotalStaked {
-                    staked_token: "asset0000".to_string(),
+                QueryMsg::QueryWorkingAmount {
+                    wallet: "loop_staker1".to_string(),
+                    pool: "asset0000".to_string(),
                 },
             )
             .unwrap(),
         )
         .unwrap();
-        assert_eq!(total_staked, Uint128::from(100u128));
+        assert_eq!(user_working, Uint128::from(100u128));
+
+        // later migrations are gated by the stored version
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::MigrateKeys {
+                pool_dists: None,
+                limit: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NoKeyMigration {});
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(
+            res.attributes[1],
+            ("key_migration".to_string(), "false".to_string())
+        );
+    }
+
+    #[test]
+    fn test_finalize_key_migration() {
+        let mut deps = mock_dependencies(&[]);
+        let info = mock_info(&"loop_staker1".to_string(), &[]);
+        let init_msg = InstantiateMsg {
+            reserve_addr: "reserve addr".to_string(),
+            token_code_id: 6,
+        };
+        instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::AddStakeableToken {
+                token: "asset0000".to_string(),
+            },
+        )
+        .unwrap();
+        let storage = deps.as_mut().storage;
+        LEGACY_USER_REWARD_INFO_MAP
+            .save(
+                storage,
+                "loop_staker1asset0000reward0001".to_string(),
+                &RewardInfo {
+                    reward_index: Uint128::from(7u128),
+                    pending_reward: Uint128::from(3u128),
+                },
+            )
+            .unwrap();
+        CONTRACT.remove(storage);
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        let migrate_keys = ExecuteMsg::MigrateKeys {
+            pool_dists: None,
+            limit: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            migrate_keys.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2], attr("skipped_entries", "1"));
+        assert_eq!(res.attributes[3], attr("done", "true"));
+        assert!(KEY_MIGRATION
+            .may_load(deps.as_ref().storage)
+            .unwrap()
+            .is_some());
+
+        // calling again without new pairs gives up on the skipped entries
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            migrate_keys.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[3], attr("done", "true"));
+        assert!(KEY_MIGRATION
+            .may_load(deps.as_ref().storage)
+            .unwrap()
+            .is_none());
+        assert!(LEGACY_USER_REWARD_INFO_MAP.has(
+            deps.as_ref().storage,
+            "loop_staker1asset0000reward0001".to_string()
+        ));
+        let err = execute(deps.as_mut(), mock_env(), info, migrate_keys).unwrap_err();
+        assert_eq!(err, ContractError::NoKeyMigration {});
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_stake_with_unstakeable_token() {
+        let mut deps = mock_dependencies(&[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(200u128),
+        }]);
+
+        deps.querier.with_token_balances(&[
+            (
+                &"liquidity0000".to_string(),
+                &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())],
+            ),
+            (&"asset0000".to_string(), &[]),
+        ]);
+
+        let init_msg = InstantiateMsg {
+            reserve_addr: "reserve addr".to_string(),
+            token_code_id: 6,
+        };
+        let info = mock_info(&"loop_staker1".to_string(), &[]);
+        let _result = instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+
+        let add_stakeable_token_msg = ExecuteMsg::AddStakeableToken {
+            token: "asset0000".to_string(),
+        };
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            add_stakeable_token_msg,
+        )
+        .unwrap();
+
+        let stakeable_tokens: Vec<StakeableToken> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::QueryListOfStakeableTokens {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            stakeable_tokens,
+            vec![StakeableToken {
+                token: "asset0000".to_string(),
+                distribution: vec![],
+                liquidity_token: "".to_string(),
+            }]
+        );
+        // testing of stakeable token completed
+
+        //now testing staking
+        let stake_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "loop_staker1".to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
+        });
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(&"asset0001".to_string(), &[]),
+            stake_msg,
+        )
+        .unwrap();
+        let stake_msg2 = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "loop_staker2".to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
+        });
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(&"asset0001".to_string(), &[]),
+            stake_msg2,
+        )
+        .unwrap();
+
+        let user1_staked: Uint128 = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::QueryStakedByUser {
+                    wallet: info.sender.to_string(),
+                    staked_token: "asset0000".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(user1_staked, Uint128::from(100u128));
+
+        let total_staked: Uint128 = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::QueryTotalStaked {
+                    staked_token: "asset0000".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(total_staked, Uint128::from(200u128));
+    }
+
+    //testing reward assignment and distribution of reward to the respective pool
+    #[test]
+    fn test_update_reward_and_distribution() {
+        let mut deps = mock_dependencies(&[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(200u128),
+        }]);
+
+        deps.querier.with_token_balances(&[
+            (
+                &"liquidity0000".to_string(),
+                &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())],
+            ),
+            (&"asset0000".to_string(), &[]),
+            (
+                &"reward0000".to_string(),
+                &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(10000u128))],
+            ),
+        ]);
+
+        let env = mock_env();
+        let init_msg = InstantiateMsg {
+            reserve_addr: "reserve addr".to_string(),
+            token_code_id: 6,
+        };
+        let info = mock_info(&"loop_staker1".to_string(), &[]);
+        let _result = instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+
+        let add_stakeable_token_msg = ExecuteMsg::AddStakeableToken {
+            token: "asset0000".to_string(),
+        };
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            add_stakeable_token_msg,
+        )
+        .unwrap();
+
+        let stakeable_tokens: Vec<StakeableToken> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::QueryListOfStakeableTokens {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            stakeable_tokens,
+            vec![StakeableToken {
+                token: "asset0000".to_string(),
+                distribution: vec![],
+                liquidity_token: "".to_string(),
+            }]
+        );
+        // testing of stakeable token completed
+
+        //adding distribution token
+
+        //now testing staking
+        let stake_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "loop_staker1".to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
+        });
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(&"asset0000".to_string(), &[]),
+            stake_msg,
+        )
+        .unwrap();
+
+        let user1_staked: Uint128 = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::QueryStakedByUser {
+                    wallet: info.sender.to_string(),
+                    staked_token: "asset0000".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(user1_staked, Uint128::from(100u128));
+
+        let total_staked: Uint128 = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::QueryTotalStaked {
+                    staked_token: "asset0000".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(total_staked, Uint128::from(100u128));
 
         //Assigning Distribution reward
         let tempvec = vec![("reward0000".to_string(), Uint128::from(1000u128))];
@@ -908,7 +1479,7 @@ mod tests {
         // testing of stakeable token completed
         let reply_msg = Reply {
             id: 1,
-            result: ContractResult::Ok(SubMsgExecutionResponse {
+            result: SubMsgResult::Ok(SubMsgResponse {
                 events: vec![],
                 data: Some(
                     vec![
@@ -927,7 +1498,7 @@ mod tests {
         let stake_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "loop_staker1".to_string(),
             amount: Uint128::from(100u128),
-            msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+            msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
         });
 
         execute(
@@ -1157,7 +1728,7 @@ mod tests {
 
         let reply_msg = Reply {
             id: 1,
-            result: ContractResult::Ok(SubMsgExecutionResponse {
+            result: SubMsgResult::Ok(SubMsgResponse {
                 events: vec![],
                 data: Some(
                     vec![
@@ -1179,7 +1750,7 @@ mod tests {
         let stake_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "loop_staker1".to_string(),
             amount: Uint128::from(100u128),
-            msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+            msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
         });
 
         execute(
@@ -1406,7 +1977,7 @@ mod tests {
 
         let reply_msg = Reply {
             id: 1,
-            result: ContractResult::Ok(SubMsgExecutionResponse {
+            result: SubMsgResult::Ok(SubMsgResponse {
                 events: vec![],
                 data: Some(
                     vec![
@@ -1428,7 +1999,7 @@ mod tests {
         let stake_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "loop_staker1".to_string(),
             amount: Uint128::from(100u128),
-            msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+            msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
         });
 
         execute(
@@ -1519,10 +2090,7 @@ mod tests {
             unstake_msg,
         );
         match res {
-            Err(StdError::GenericErr { msg, .. }) => assert_eq!(
-                msg,
-                "Sorry for inconvenience, system is under maintenance. Kindly check again later"
-            ),
+            Err(ContractError::Frozen {}) => {}
             _ => panic!("Invalid error"),
         }
 
@@ -1632,7 +2200,7 @@ mod tests {
         let stake_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "loop_staker1".to_string(),
             amount: Uint128::from(100u128),
-            msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+            msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
         });
 
         execute(
@@ -1646,7 +2214,7 @@ mod tests {
         let stake_msg1 = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "loop_staker2".to_string(),
             amount: Uint128::from(100u128),
-            msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+            msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
         });
 
         execute(
@@ -1899,7 +2467,7 @@ mod tests {
         let stake_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "loop_staker1".to_string(),
             amount: Uint128::from(100u128),
-            msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+            msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
         });
 
         execute(
@@ -1971,6 +2539,627 @@ mod tests {
         assert_eq!(reward_assigned, Uint128::from(1000u128));
     }
 
+    #[test]
+    fn test_claim_all() {
+        let mut deps = mock_dependencies(&[]);
+        deps.querier.with_token_balances(&[(
+            &"reward0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(10000u128))],
+        )]);
+        let env = mock_env();
+        let info = mock_info("loop_staker1", &[]);
+        let init_msg = InstantiateMsg {
+            reserve_addr: "reserve addr".to_string(),
+            token_code_id: 6,
+        };
+        instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UpdateLockTimeFrame {
+                lock_time_frame: 100,
+            },
+        )
+        .unwrap();
+
+        for pool in ["asset0000", "asset0001", "asset0002"] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                ExecuteMsg::AddStakeableToken {
+                    token: pool.to_string(),
+                },
+            )
+            .unwrap();
+        }
+        for pool in ["asset0000", "asset0001"] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                ExecuteMsg::UpdateReward {
+                    pool: pool.to_string(),
+                    rewards: vec![("reward0000".to_string(), Uint128::from(1000u128))],
+                },
+            )
+            .unwrap();
+        }
+
+        let new_env = mock_env_time(env.block.time.plus_seconds(86401));
+        // the stake in asset0002 is still within the lock time frame when claiming
+        let stakes = [
+            ("asset0000", mock_env()),
+            ("asset0001", mock_env()),
+            ("asset0002", new_env.clone()),
+        ];
+        for (pool, stake_env) in stakes {
+            execute(
+                deps.as_mut(),
+                stake_env,
+                mock_info(pool, &[]),
+                ExecuteMsg::Receive(Cw20ReceiveMsg {
+                    sender: "loop_staker1".to_string(),
+                    amount: Uint128::from(100u128),
+                    msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
+                }),
+            )
+            .unwrap();
+        }
+
+        execute(
+            deps.as_mut(),
+            new_env.clone(),
+            info.clone(),
+            ExecuteMsg::DistributeByLimit {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            new_env.clone(),
+            info.clone(),
+            ExecuteMsg::ClaimAll {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "reward0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: info.sender.to_string(),
+                    amount: Uint128::from(2000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            }))]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "claim_all"),
+                attr("pool", "asset0000"),
+                attr("rewards", "1000reward0000"),
+                attr("pool", "asset0001"),
+                attr("rewards", "1000reward0000"),
+                attr("skipped", "asset0002"),
+            ]
+        );
+
+        // rewards are settled, nothing left to transfer
+        let res = execute(
+            deps.as_mut(),
+            new_env,
+            info,
+            ExecuteMsg::ClaimAll {
+                start_after: None,
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "claim_all"),
+                attr("pool", "asset0000"),
+                attr("rewards", "0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_boosted_rewards_and_kick() {
+        let mut deps = mock_dependencies(&[]);
+        deps.querier.with_token_balances(&[
+            (
+                &"reward0000".to_string(),
+                &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(10000u128))],
+            ),
+            (
+                &"staking0000".to_string(),
+                &[(&"loop_staker1".to_string(), &Uint128::from(100u128))],
+            ),
+        ]);
+        deps.querier
+            .with_staking_total_balances(&[(1u64, Uint128::from(100u128))]);
+        let env = mock_env();
+        let info = mock_info("loop_staker1", &[]);
+        let init_msg = InstantiateMsg {
+            reserve_addr: "reserve addr".to_string(),
+            token_code_id: 6,
+        };
+        instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::AddStakeableToken {
+                token: "asset0000".to_string(),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UpdateReward {
+                pool: "asset0000".to_string(),
+                rewards: vec![("reward0000".to_string(), Uint128::from(1000u128))],
+            },
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker2", &[]),
+            ExecuteMsg::UpdateBoostStakingContract {
+                staking_contract: Some("staking0000".to_string()),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UpdateBoostStakingContract {
+                staking_contract: Some("staking0000".to_string()),
+            },
+        )
+        .unwrap();
+
+        for staker in ["loop_staker1", "loop_staker2"] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("asset0000", &[]),
+                ExecuteMsg::Receive(Cw20ReceiveMsg {
+                    sender: staker.to_string(),
+                    amount: Uint128::from(100u128),
+                    msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
+                }),
+            )
+            .unwrap();
+        }
+
+        let working_amount = |deps: &OwnedDeps<_, _, _>, wallet: &str| -> Uint128 {
+            from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::QueryWorkingAmount {
+                        wallet: wallet.to_string(),
+                        pool: "asset0000".to_string(),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+        // all the voting power is with staker1, staker2 only works with 40% of the stake
+        assert_eq!(
+            working_amount(&deps, "loop_staker1"),
+            Uint128::from(100u128)
+        );
+        assert_eq!(working_amount(&deps, "loop_staker2"), Uint128::from(40u128));
+
+        let new_env = mock_env_time(env.block.time.plus_seconds(86401));
+        execute(
+            deps.as_mut(),
+            new_env.clone(),
+            info.clone(),
+            ExecuteMsg::DistributeByLimit {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+
+        let pending_reward = |deps: &OwnedDeps<_, _, _>, wallet: &str| -> Vec<(String, Uint128)> {
+            let res: Vec<QueryUserRewardInPoolResponse> = from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::QueryUserRewardInPool {
+                        wallet: wallet.to_string(),
+                        pool: "asset0000".to_string(),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            res[0].rewards_info.clone()
+        };
+        assert_eq!(
+            pending_reward(&deps, "loop_staker1"),
+            vec![("reward0000".to_string(), Uint128::from(714u128))]
+        );
+        assert_eq!(
+            pending_reward(&deps, "loop_staker2"),
+            vec![("reward0000".to_string(), Uint128::from(285u128))]
+        );
+
+        // staker2 has no boost to lose
+        let err = execute(
+            deps.as_mut(),
+            new_env.clone(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Kick {
+                user: "loop_staker2".to_string(),
+                pool_address: "asset0000".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::KickNotAllowed {});
+
+        // the lock of staker1 decayed
+        deps.querier.with_token_balances(&[
+            (
+                &"reward0000".to_string(),
+                &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(10000u128))],
+            ),
+            (&"staking0000".to_string(), &[]),
+        ]);
+        let res = execute(
+            deps.as_mut(),
+            new_env,
+            mock_info("anyone", &[]),
+            ExecuteMsg::Kick {
+                user: "loop_staker1".to_string(),
+                pool_address: "asset0000".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[3], attr("working_amount", "40"));
+        assert_eq!(working_amount(&deps, "loop_staker1"), Uint128::from(40u128));
+        let total_working: Uint128 = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::QueryTotalWorkingAmount {
+                    pool: "asset0000".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(total_working, Uint128::from(80u128));
+        // rewards earned with the boost are kept
+        assert_eq!(
+            pending_reward(&deps, "loop_staker1"),
+            vec![("reward0000".to_string(), Uint128::from(714u128))]
+        );
+
+        // 40% of a dust stake rounds down to nothing, it works with zero and not its full stake
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("asset0000", &[]),
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "loop_staker3".to_string(),
+                amount: Uint128::from(1u128),
+                msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
+            }),
+        )
+        .unwrap();
+        assert_eq!(working_amount(&deps, "loop_staker3"), Uint128::zero());
+        let total_working: Uint128 = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::QueryTotalWorkingAmount {
+                    pool: "asset0000".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(total_working, Uint128::from(80u128));
+    }
+
+    #[test]
+    fn test_lock_tiers() {
+        let mut deps = mock_dependencies(&[]);
+        deps.querier.with_token_balances(&[(
+            &"reward0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(10000u128))],
+        )]);
+        let env = mock_env();
+        let info = mock_info("loop_staker1", &[]);
+        let init_msg = InstantiateMsg {
+            reserve_addr: "reserve addr".to_string(),
+            token_code_id: 6,
+        };
+        instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::AddStakeableToken {
+                token: "asset0000".to_string(),
+            },
+        )
+        .unwrap();
+        let reply_msg = Reply {
+            id: 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(
+                    vec![
+                        10, 13, 108, 105, 113, 117, 105, 100, 105, 116, 121, 48, 48, 48, 48,
+                    ]
+                    .into(),
+                ),
+            }),
+        };
+        reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UpdateReward {
+                pool: "asset0000".to_string(),
+                rewards: vec![("reward0000".to_string(), Uint128::from(1000u128))],
+            },
+        )
+        .unwrap();
+
+        let six_months = ExecuteMsg::UpdateLockTier {
+            tier: 6,
+            lock_period: 6 * 2592000,
+            multiplier: Decimal::from_ratio(2u128, 1u128),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker2", &[]),
+            six_months.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UpdateLockTier {
+                tier: 1,
+                lock_period: 2592000,
+                multiplier: Decimal::percent(50),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidLockTierMultiplier {});
+        execute(deps.as_mut(), mock_env(), info.clone(), six_months).unwrap();
+
+        let stake = |lock_tier: Option<u64>, staker: &str| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: staker.to_string(),
+                amount: Uint128::from(100u128),
+                msg: to_binary(&Cw20HookMsg::Stake { lock_tier }).unwrap(),
+            })
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("asset0000", &[]),
+            stake(Some(3), "loop_staker1"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::UnknownLockTier { tier: 3 });
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("asset0000", &[]),
+            stake(Some(6), "loop_staker1"),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("asset0000", &[]),
+            stake(None, "loop_staker2"),
+        )
+        .unwrap();
+
+        let unlock_time = env.block.time.seconds() + 6 * 2592000;
+        let tiers: Vec<UserLockTierResponse> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::QueryUserLockTiers {
+                    wallet: "loop_staker1".to_string(),
+                    pool: "asset0000".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            tiers,
+            vec![UserLockTierResponse {
+                tier: 6,
+                amount: Uint128::from(100u128),
+                multiplier: Decimal::from_ratio(2u128, 1u128),
+                unlock_time,
+            }]
+        );
+
+        // the locked stake weighs twice as much in the distribution
+        let new_env = mock_env_time(env.block.time.plus_seconds(86401));
+        execute(
+            deps.as_mut(),
+            new_env.clone(),
+            info.clone(),
+            ExecuteMsg::DistributeByLimit {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        for (staker, reward) in [("loop_staker1", 666u128), ("loop_staker2", 333u128)] {
+            let res: Vec<QueryUserRewardInPoolResponse> = from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::QueryUserRewardInPool {
+                        wallet: staker.to_string(),
+                        pool: "asset0000".to_string(),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            assert_eq!(
+                res[0].rewards_info,
+                vec![("reward0000".to_string(), Uint128::from(reward))]
+            );
+        }
+
+        // only the stake outside of a locked tier can be unstaked on its own
+        execute(
+            deps.as_mut(),
+            new_env.clone(),
+            mock_info("asset0000", &[]),
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "loop_staker1".to_string(),
+                amount: Uint128::from(50u128),
+                msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
+            }),
+        )
+        .unwrap();
+        let partial_unstake = |amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "loop_staker1".to_string(),
+                amount: Uint128::from(amount),
+                msg: to_binary(&Cw20HookMsg::UnstakeAndClaim {}).unwrap(),
+            })
+        };
+        let err = execute(
+            deps.as_mut(),
+            new_env.clone(),
+            mock_info("liquidity0000", &[]),
+            partial_unstake(60),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::StakeLocked { unlock_time });
+        let res = execute(
+            deps.as_mut(),
+            new_env.clone(),
+            mock_info("liquidity0000", &[]),
+            partial_unstake(50),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[1],
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "loop_staker1".to_string(),
+                    amount: Uint128::from(50u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            }))
+        );
+
+        let unstake = partial_unstake(100);
+        let err = execute(
+            deps.as_mut(),
+            new_env.clone(),
+            mock_info("liquidity0000", &[]),
+            unstake.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::StakeLocked { unlock_time });
+
+        // once the lock is over the bonus can be kicked away
+        let unlock_env = mock_env_time(Timestamp::from_seconds(unlock_time));
+        let err = execute(
+            deps.as_mut(),
+            new_env,
+            mock_info("anyone", &[]),
+            ExecuteMsg::Kick {
+                user: "loop_staker1".to_string(),
+                pool_address: "asset0000".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::KickNotAllowed {});
+        let res = execute(
+            deps.as_mut(),
+            unlock_env.clone(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Kick {
+                user: "loop_staker1".to_string(),
+                pool_address: "asset0000".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[3], attr("working_amount", "100"));
+        let tiers: Vec<UserLockTierResponse> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::QueryUserLockTiers {
+                    wallet: "loop_staker1".to_string(),
+                    pool: "asset0000".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(tiers.is_empty());
+
+        execute(
+            deps.as_mut(),
+            unlock_env,
+            mock_info("liquidity0000", &[]),
+            unstake,
+        )
+        .unwrap();
+        let total_working: Uint128 = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::QueryTotalWorkingAmount {
+                    pool: "asset0000".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(total_working, Uint128::from(100u128));
+    }
+
     #[test]
     fn test_claim_compounding_reward() {
         let mut deps = mock_dependencies(&[Coin {
@@ -2032,7 +3221,7 @@ mod tests {
 
         let reply_msg = Reply {
             id: 1,
-            result: ContractResult::Ok(SubMsgExecutionResponse {
+            result: SubMsgResult::Ok(SubMsgResponse {
                 events: vec![],
                 data: Some(
                     vec![
@@ -2054,7 +3243,7 @@ mod tests {
         let stake_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "loop_staker1".to_string(),
             amount: Uint128::from(100u128),
-            msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+            msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
         });
 
         execute(
@@ -2068,7 +3257,7 @@ mod tests {
         let stake_msg1 = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "loop_staker2".to_string(),
             amount: Uint128::from(100u128),
-            msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+            msg: to_binary(&Cw20HookMsg::Stake { lock_tier: None }).unwrap(),
         });
 
         execute(
@@ -2449,7 +3638,7 @@ mod tests {
             update_config_msg.clone(),
         );
         match res {
-            Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+            Err(ContractError::Unauthorized {}) => {}
             _ => panic!("Invalid error"),
         }
 
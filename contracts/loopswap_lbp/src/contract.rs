@@ -0,0 +1,597 @@
+use crate::error::ContractError;
+use crate::math::{compute_offer_amount, compute_swap, MAX_IN_RATIO, MAX_OUT_RATIO};
+use crate::state::{Config, LbpInfoRaw, CONFIG, FACTORY_CONTRACT_ADDR, LBP_INFO};
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Response, StdResult, Uint128, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use loopswap::asset::{Asset, AssetInfo, AssetInfoRaw};
+use loopswap::lbp::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, LbpInfo, MigrateMsg, PoolResponse,
+    QueryMsg, WeightsResponse,
+};
+use loopswap::pair::{ReverseSimulationResponse, SimulationResponse};
+use std::cmp::Ordering;
+
+const CONTRACT_NAME: &str = "crates.io:loopswap-lbp";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut<Empty>,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if msg.asset_infos[0] == msg.asset_infos[1] {
+        return Err(ContractError::AssetMismatch {});
+    }
+
+    if msg.start_time >= msg.end_time || msg.end_time <= env.block.time.seconds() {
+        return Err(ContractError::InvalidSaleWindow {});
+    }
+
+    if msg
+        .start_weights
+        .iter()
+        .chain(msg.end_weights.iter())
+        .any(|w| w.is_zero())
+    {
+        return Err(ContractError::InvalidWeights {});
+    }
+
+    if msg.commission_rate >= Decimal::one() {
+        return Err(ContractError::InvalidCommissionRate {});
+    }
+
+    LBP_INFO.save(
+        deps.storage,
+        &LbpInfoRaw {
+            contract_addr: deps.api.addr_canonicalize(env.contract.address.as_str())?,
+            asset_infos: [
+                msg.asset_infos[0].to_raw(deps.api)?,
+                msg.asset_infos[1].to_raw(deps.api)?,
+            ],
+            asset_decimals: msg.asset_decimals,
+        },
+    )?;
+    FACTORY_CONTRACT_ADDR.save(deps.storage, &info.sender)?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_validate(&msg.owner)?,
+            start_time: msg.start_time,
+            end_time: msg.end_time,
+            start_weights: msg.start_weights,
+            end_weights: msg.end_weights,
+            commission_rate: msg.commission_rate,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "instantiate"),
+        ("owner", msg.owner.as_str()),
+        ("start_time", &msg.start_time.to_string()),
+        ("end_time", &msg.end_time.to_string()),
+    ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut<Empty>,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::ProvideLiquidity { assets } => provide_liquidity(deps, env, info, assets),
+        ExecuteMsg::Swap {
+            offer_asset,
+            belief_price,
+            max_spread,
+            to,
+        } => {
+            if !offer_asset.is_native_token() {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let to_addr = if let Some(to_addr) = to {
+                Some(deps.api.addr_validate(&to_addr)?)
+            } else {
+                None
+            };
+
+            swap(
+                deps,
+                env,
+                info.clone(),
+                info.sender,
+                offer_asset,
+                belief_price,
+                max_spread,
+                to_addr,
+            )
+        }
+        ExecuteMsg::WithdrawLiquidity { receiver } => withdraw_liquidity(deps, env, info, receiver),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut<Empty>,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&cw20_msg.msg) {
+        Ok(Cw20HookMsg::Swap {
+            belief_price,
+            max_spread,
+            to,
+        }) => {
+            // only asset contract can execute this message
+            let lbp_info: LbpInfoRaw = LBP_INFO.load(deps.storage)?;
+            let sender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+            let authorized = lbp_info.asset_infos.iter().any(|asset_info| {
+                matches!(asset_info, AssetInfoRaw::Token { contract_addr } if contract_addr == &sender_raw)
+            });
+
+            if !authorized {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let to_addr = if let Some(to_addr) = to {
+                Some(deps.api.addr_validate(to_addr.as_str())?)
+            } else {
+                None
+            };
+
+            let offer_asset = Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender.to_string(),
+                },
+                amount: cw20_msg.amount,
+            };
+
+            swap(
+                deps,
+                env,
+                info,
+                Addr::unchecked(cw20_msg.sender),
+                offer_asset,
+                belief_price,
+                max_spread,
+                to_addr,
+            )
+        }
+        Err(err) => Err(ContractError::Std(err)),
+    }
+}
+
+/// CONTRACT - owner should approve contract to use the amount of token
+pub fn provide_liquidity(
+    deps: DepsMut<Empty>,
+    env: Env,
+    info: MessageInfo,
+    assets: [Asset; 2],
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if env.block.time.seconds() >= config.end_time {
+        return Err(ContractError::SaleEnded {});
+    }
+
+    for asset in assets.iter() {
+        asset.assert_sent_native_token_balance(&info)?;
+    }
+
+    let lbp_info: LbpInfoRaw = LBP_INFO.load(deps.storage)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for asset_info in lbp_info.asset_infos.iter() {
+        let asset_info = asset_info.to_normal(deps.api)?;
+        let deposit = assets
+            .iter()
+            .find(|a| a.info.equal(&asset_info))
+            .map(|a| a.amount)
+            .ok_or(ContractError::AssetMismatch {})?;
+
+        // If the pool is token contract, then we need to execute TransferFrom msg to receive funds
+        if let AssetInfo::Token { contract_addr } = asset_info {
+            if !deposit.is_zero() {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr,
+                    msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                        owner: info.sender.to_string(),
+                        recipient: env.contract.address.to_string(),
+                        amount: deposit,
+                    })?,
+                    funds: vec![],
+                }));
+            }
+        }
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "provide_liquidity"),
+        ("sender", info.sender.as_str()),
+        ("assets", &format!("{}, {}", assets[0], assets[1])),
+    ]))
+}
+
+pub fn withdraw_liquidity(
+    deps: DepsMut<Empty>,
+    env: Env,
+    info: MessageInfo,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if env.block.time.seconds() < config.end_time {
+        return Err(ContractError::SaleNotEnded {});
+    }
+
+    let receiver = if let Some(receiver) = receiver {
+        deps.api.addr_validate(&receiver)?
+    } else {
+        config.owner
+    };
+
+    let pools = query_pools(deps.as_ref(), env.contract.address)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for pool in pools.iter() {
+        if !pool.amount.is_zero() {
+            messages.push(pool.clone().into_msg(receiver.clone())?);
+        }
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "withdraw_liquidity"),
+        ("receiver", receiver.as_str()),
+        ("refund_assets", &format!("{}, {}", pools[0], pools[1])),
+    ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn swap(
+    deps: DepsMut<Empty>,
+    env: Env,
+    info: MessageInfo,
+    sender: Addr,
+    offer_asset: Asset,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    to: Option<Addr>,
+) -> Result<Response, ContractError> {
+    offer_asset.assert_sent_native_token_balance(&info)?;
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+    assert_sale_active(&config, now)?;
+
+    let lbp_info: LbpInfoRaw = LBP_INFO.load(deps.storage)?;
+    let pools: [Asset; 2] = query_pools(deps.as_ref(), env.contract.address)?;
+    let weights = config.weights_at(now);
+
+    // If the asset balance is already increased
+    // To calculated properly we should subtract user deposit from the pool
+    let (offer_idx, ask_idx) = if offer_asset.info.equal(&pools[0].info) {
+        (0, 1)
+    } else if offer_asset.info.equal(&pools[1].info) {
+        (1, 0)
+    } else {
+        return Err(ContractError::AssetMismatch {});
+    };
+
+    let offer_pool = pools[offer_idx].amount.checked_sub(offer_asset.amount)?;
+    let ask_pool = pools[ask_idx].clone();
+
+    let offer_amount = offer_asset.amount;
+    if offer_amount > offer_pool * MAX_IN_RATIO {
+        return Err(ContractError::MaxRatioExceeded {});
+    }
+
+    let (return_amount, spread_amount, commission_amount) = compute_swap(
+        offer_pool,
+        ask_pool.amount,
+        offer_amount,
+        weights[offer_idx],
+        weights[ask_idx],
+        config.commission_rate,
+    )?;
+
+    if return_amount > ask_pool.amount * MAX_OUT_RATIO {
+        return Err(ContractError::MaxRatioExceeded {});
+    }
+
+    assert_max_spread(
+        belief_price,
+        max_spread,
+        offer_amount,
+        return_amount,
+        spread_amount,
+        lbp_info.asset_decimals[offer_idx],
+        lbp_info.asset_decimals[ask_idx],
+    )?;
+
+    let receiver = to.unwrap_or_else(|| sender.clone());
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !return_amount.is_zero() {
+        let return_asset = Asset {
+            info: ask_pool.info.clone(),
+            amount: return_amount,
+        };
+        messages.push(return_asset.into_msg(receiver.clone())?);
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "swap"),
+        ("sender", sender.as_str()),
+        ("receiver", receiver.as_str()),
+        ("offer_asset", &offer_asset.info.to_string()),
+        ("ask_asset", &ask_pool.info.to_string()),
+        ("offer_amount", &offer_amount.to_string()),
+        ("return_amount", &return_amount.to_string()),
+        ("spread_amount", &spread_amount.to_string()),
+        ("commission_amount", &commission_amount.to_string()),
+        ("offer_weight", &weights[offer_idx].to_string()),
+        ("ask_weight", &weights[ask_idx].to_string()),
+    ]))
+}
+
+/// If `belief_price` and `max_spread` both are given,
+/// we compute new spread else we just use the curve
+/// spread to check `max_spread`
+pub fn assert_max_spread(
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    offer_amount: Uint128,
+    return_amount: Uint128,
+    spread_amount: Uint128,
+    offer_decimal: u8,
+    return_decimal: u8,
+) -> Result<(), ContractError> {
+    let (offer_amount, return_amount, spread_amount) = match offer_decimal.cmp(&return_decimal) {
+        Ordering::Greater => {
+            let diff_decimal = Uint128::from(10u64.pow((offer_decimal - return_decimal).into()));
+            (
+                offer_amount,
+                return_amount.checked_mul(diff_decimal)?,
+                spread_amount.checked_mul(diff_decimal)?,
+            )
+        }
+        Ordering::Less => {
+            let diff_decimal = Uint128::from(10u64.pow((return_decimal - offer_decimal).into()));
+            (
+                offer_amount.checked_mul(diff_decimal)?,
+                return_amount,
+                spread_amount,
+            )
+        }
+        Ordering::Equal => (offer_amount, return_amount, spread_amount),
+    };
+
+    if let (Some(max_spread), Some(belief_price)) = (max_spread, belief_price) {
+        if belief_price.is_zero() {
+            return Err(ContractError::MaxSpreadAssertion {});
+        }
+
+        let expected_return =
+            offer_amount.multiply_ratio(Decimal::one().atomics(), belief_price.atomics());
+        let spread_amount = expected_return.saturating_sub(return_amount);
+
+        if return_amount < expected_return
+            && Decimal::from_ratio(spread_amount, expected_return) > max_spread
+        {
+            return Err(ContractError::MaxSpreadAssertion {});
+        }
+    } else if let Some(max_spread) = max_spread {
+        let total = return_amount.checked_add(spread_amount)?;
+        if !total.is_zero() && Decimal::from_ratio(spread_amount, total) > max_spread {
+            return Err(ContractError::MaxSpreadAssertion {});
+        }
+    }
+
+    Ok(())
+}
+
+fn query_pools(deps: Deps<Empty>, contract_addr: Addr) -> StdResult<[Asset; 2]> {
+    let lbp_info: LbpInfoRaw = LBP_INFO.load(deps.storage)?;
+    let info_0: AssetInfo = lbp_info.asset_infos[0].to_normal(deps.api)?;
+    let info_1: AssetInfo = lbp_info.asset_infos[1].to_normal(deps.api)?;
+    Ok([
+        Asset {
+            amount: info_0.query_pool(&deps.querier, deps.api, contract_addr.clone())?,
+            info: info_0,
+        },
+        Asset {
+            amount: info_1.query_pool(&deps.querier, deps.api, contract_addr)?,
+            info: info_1,
+        },
+    ])
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps<Empty>, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Lbp {} => Ok(to_binary(&query_lbp_info(deps)?)?),
+        QueryMsg::Pool {} => Ok(to_binary(&query_pool(deps, env)?)?),
+        QueryMsg::Config {} => Ok(to_binary(&query_config(deps)?)?),
+        QueryMsg::Weights { time } => Ok(to_binary(&query_weights(deps, env, time)?)?),
+        QueryMsg::Simulation { offer_asset } => {
+            Ok(to_binary(&query_simulation(deps, env, offer_asset)?)?)
+        }
+        QueryMsg::ReverseSimulation { ask_asset } => {
+            Ok(to_binary(&query_reverse_simulation(deps, env, ask_asset)?)?)
+        }
+    }
+}
+
+pub fn query_lbp_info(deps: Deps<Empty>) -> Result<LbpInfo, ContractError> {
+    let lbp_info: LbpInfoRaw = LBP_INFO.load(deps.storage)?;
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    Ok(LbpInfo {
+        asset_infos: [
+            lbp_info.asset_infos[0].to_normal(deps.api)?,
+            lbp_info.asset_infos[1].to_normal(deps.api)?,
+        ],
+        contract_addr: deps.api.addr_humanize(&lbp_info.contract_addr)?.to_string(),
+        asset_decimals: lbp_info.asset_decimals,
+        start_time: config.start_time,
+        end_time: config.end_time,
+    })
+}
+
+pub fn query_pool(deps: Deps<Empty>, env: Env) -> Result<PoolResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let assets = query_pools(deps, env.contract.address)?;
+
+    Ok(PoolResponse {
+        assets,
+        weights: config.weights_at(env.block.time.seconds()),
+    })
+}
+
+pub fn query_config(deps: Deps<Empty>) -> Result<ConfigResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        owner: config.owner.to_string(),
+        start_time: config.start_time,
+        end_time: config.end_time,
+        start_weights: config.start_weights,
+        end_weights: config.end_weights,
+        commission_rate: config.commission_rate,
+    })
+}
+
+pub fn query_weights(
+    deps: Deps<Empty>,
+    env: Env,
+    time: Option<u64>,
+) -> Result<WeightsResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let time = time.unwrap_or_else(|| env.block.time.seconds());
+
+    Ok(WeightsResponse {
+        weights: config.weights_at(time),
+    })
+}
+
+pub fn query_simulation(
+    deps: Deps<Empty>,
+    env: Env,
+    offer_asset: Asset,
+) -> Result<SimulationResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+    assert_sale_active(&config, now)?;
+    let pools: [Asset; 2] = query_pools(deps, env.contract.address)?;
+    let weights = config.weights_at(now);
+
+    let (offer_idx, ask_idx) = if offer_asset.info.equal(&pools[0].info) {
+        (0, 1)
+    } else if offer_asset.info.equal(&pools[1].info) {
+        (1, 0)
+    } else {
+        return Err(ContractError::AssetMismatch {});
+    };
+
+    // Quote only what `swap` would accept
+    if offer_asset.amount > pools[offer_idx].amount * MAX_IN_RATIO {
+        return Err(ContractError::MaxRatioExceeded {});
+    }
+
+    let (return_amount, spread_amount, commission_amount) = compute_swap(
+        pools[offer_idx].amount,
+        pools[ask_idx].amount,
+        offer_asset.amount,
+        weights[offer_idx],
+        weights[ask_idx],
+        config.commission_rate,
+    )?;
+
+    if return_amount > pools[ask_idx].amount * MAX_OUT_RATIO {
+        return Err(ContractError::MaxRatioExceeded {});
+    }
+
+    Ok(SimulationResponse {
+        return_amount,
+        spread_amount,
+        commission_amount,
+        commission_rate: config.commission_rate,
+        referral_amount: Uint128::zero(),
+    })
+}
+
+pub fn query_reverse_simulation(
+    deps: Deps<Empty>,
+    env: Env,
+    ask_asset: Asset,
+) -> Result<ReverseSimulationResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+    assert_sale_active(&config, now)?;
+    let pools: [Asset; 2] = query_pools(deps, env.contract.address)?;
+    let weights = config.weights_at(now);
+
+    let (offer_idx, ask_idx) = if ask_asset.info.equal(&pools[0].info) {
+        (1, 0)
+    } else if ask_asset.info.equal(&pools[1].info) {
+        (0, 1)
+    } else {
+        return Err(ContractError::AssetMismatch {});
+    };
+
+    if ask_asset.amount > pools[ask_idx].amount * MAX_OUT_RATIO {
+        return Err(ContractError::MaxRatioExceeded {});
+    }
+
+    let (offer_amount, spread_amount, commission_amount) = compute_offer_amount(
+        pools[offer_idx].amount,
+        pools[ask_idx].amount,
+        ask_asset.amount,
+        weights[offer_idx],
+        weights[ask_idx],
+        config.commission_rate,
+    )?;
+
+    if offer_amount > pools[offer_idx].amount * MAX_IN_RATIO {
+        return Err(ContractError::MaxRatioExceeded {});
+    }
+
+    Ok(ReverseSimulationResponse {
+        offer_amount,
+        spread_amount,
+        commission_amount,
+        commission_rate: config.commission_rate,
+    })
+}
+
+fn assert_sale_active(config: &Config, now: u64) -> Result<(), ContractError> {
+    if now < config.start_time || now >= config.end_time {
+        return Err(ContractError::SaleNotActive {});
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
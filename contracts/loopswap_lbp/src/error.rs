@@ -0,0 +1,44 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Max spread assertion")]
+    MaxSpreadAssertion {},
+
+    #[error("Asset mismatch")]
+    AssetMismatch {},
+
+    #[error("Invalid sale window")]
+    InvalidSaleWindow {},
+
+    #[error("Invalid weights")]
+    InvalidWeights {},
+
+    #[error("Commission rate must be less than 1")]
+    InvalidCommissionRate {},
+
+    #[error("Sale is not active")]
+    SaleNotActive {},
+
+    #[error("Sale has already ended")]
+    SaleEnded {},
+
+    #[error("Sale has not ended yet")]
+    SaleNotEnded {},
+
+    #[error("Swap amount exceeds the max ratio of the pool")]
+    MaxRatioExceeded {},
+}
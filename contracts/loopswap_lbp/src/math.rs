@@ -0,0 +1,135 @@
+use cosmwasm_std::{Decimal, StdError, StdResult, Uint128};
+
+/// Swaps may take at most this share of the offer pool in and the ask pool out.
+/// Keeps the power approximation below inside its convergence range.
+pub const MAX_IN_RATIO: Decimal = Decimal::raw(500_000_000_000_000_000u128);
+pub const MAX_OUT_RATIO: Decimal = Decimal::raw(333_333_333_333_333_333u128);
+
+const POW_PRECISION: Decimal = Decimal::raw(100_000_000u128);
+const POW_MAX_ITERATIONS: u64 = 150;
+
+/// base ^ exp for a fractional exponent.
+/// The integer part is computed exactly, the remainder with a binomial series.
+pub fn pow(base: Decimal, exp: Decimal) -> StdResult<Decimal> {
+    let whole = exp.atomics().u128() / Decimal::one().atomics().u128();
+    let remain = exp - Decimal::from_ratio(whole, 1u128);
+
+    let whole_pow = base.checked_pow(whole as u32)?;
+    if remain.is_zero() {
+        return Ok(whole_pow);
+    }
+
+    Ok(whole_pow * pow_approx(base, remain)?)
+}
+
+/// (1 + x) ^ a = 1 + a*x + a(a-1)/2!*x^2 + ...  with x = base - 1, valid for 0 < base < 2
+fn pow_approx(base: Decimal, exp: Decimal) -> StdResult<Decimal> {
+    if base.is_zero() || base >= Decimal::from_ratio(2u128, 1u128) {
+        return Err(StdError::generic_err("pow base out of range"));
+    }
+
+    let (x, x_neg) = sub_sign(base, Decimal::one());
+    let mut term = Decimal::one();
+    let mut sum = term;
+    let mut negative = false;
+
+    for i in 1..=POW_MAX_ITERATIONS {
+        let big_k = Decimal::from_ratio(i, 1u64);
+        let (c, c_neg) = sub_sign(exp, big_k - Decimal::one());
+        term = term * c * x / big_k;
+        if term.is_zero() {
+            break;
+        }
+
+        if x_neg {
+            negative = !negative;
+        }
+        if c_neg {
+            negative = !negative;
+        }
+
+        if negative {
+            if term > sum {
+                return Err(StdError::generic_err("pow approximation underflow"));
+            }
+            sum -= term;
+        } else {
+            sum += term;
+        }
+
+        if term < POW_PRECISION {
+            break;
+        }
+    }
+
+    Ok(sum)
+}
+
+fn sub_sign(a: Decimal, b: Decimal) -> (Decimal, bool) {
+    if a >= b {
+        (a - b, false)
+    } else {
+        (b - a, true)
+    }
+}
+
+/// ask_amount = ask_pool * (1 - (offer_pool / (offer_pool + offer_amount)) ^ (offer_weight / ask_weight))
+/// Returns (return_amount, spread_amount, commission_amount), commission is taken from the return
+pub fn compute_swap(
+    offer_pool: Uint128,
+    ask_pool: Uint128,
+    offer_amount: Uint128,
+    offer_weight: Decimal,
+    ask_weight: Decimal,
+    commission_rate: Decimal,
+) -> StdResult<(Uint128, Uint128, Uint128)> {
+    if offer_pool.is_zero() || ask_pool.is_zero() {
+        return Err(StdError::generic_err("pool is empty"));
+    }
+
+    let base = Decimal::from_ratio(offer_pool, offer_pool.checked_add(offer_amount)?);
+    let ratio = pow(base, offer_weight / ask_weight)?;
+    let return_amount = ask_pool * (Decimal::one() - ratio);
+
+    // spot price (ask per offer) = (ask_pool / ask_weight) / (offer_pool / offer_weight)
+    let expected_return =
+        offer_amount.multiply_ratio(ask_pool, offer_pool) * (offer_weight / ask_weight);
+    let spread_amount = expected_return.saturating_sub(return_amount);
+
+    let commission_amount = return_amount * commission_rate;
+    let return_amount = return_amount.checked_sub(commission_amount)?;
+
+    Ok((return_amount, spread_amount, commission_amount))
+}
+
+/// offer_amount = offer_pool * ((ask_pool / (ask_pool - ask_amount)) ^ (ask_weight / offer_weight) - 1)
+/// Returns (offer_amount, spread_amount, commission_amount)
+pub fn compute_offer_amount(
+    offer_pool: Uint128,
+    ask_pool: Uint128,
+    ask_amount: Uint128,
+    offer_weight: Decimal,
+    ask_weight: Decimal,
+    commission_rate: Decimal,
+) -> StdResult<(Uint128, Uint128, Uint128)> {
+    if offer_pool.is_zero() || ask_pool.is_zero() {
+        return Err(StdError::generic_err("pool is empty"));
+    }
+
+    let before_commission_deduction =
+        ask_amount * (Decimal::one() / (Decimal::one() - commission_rate));
+    if before_commission_deduction >= ask_pool {
+        return Err(StdError::generic_err("ask amount exceeds the pool"));
+    }
+
+    let base = Decimal::from_ratio(ask_pool, ask_pool - before_commission_deduction);
+    let ratio = pow(base, ask_weight / offer_weight)?;
+    let offer_amount = offer_pool * (ratio - Decimal::one());
+
+    let before_spread_deduction =
+        offer_amount.multiply_ratio(ask_pool, offer_pool) * (offer_weight / ask_weight);
+    let spread_amount = before_spread_deduction.saturating_sub(before_commission_deduction);
+    let commission_amount = before_commission_deduction * commission_rate;
+
+    Ok((offer_amount, spread_amount, commission_amount))
+}
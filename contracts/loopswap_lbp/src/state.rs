@@ -0,0 +1,52 @@
+use cosmwasm_std::{Addr, CanonicalAddr, Decimal};
+use cw_storage_plus::Item;
+use loopswap::asset::AssetInfoRaw;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const LBP_INFO: Item<LbpInfoRaw> = Item::new("lbp_info");
+pub const FACTORY_CONTRACT_ADDR: Item<Addr> = Item::new("factory_contract_addr");
+pub const CONFIG: Item<Config> = Item::new("config");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct LbpInfoRaw {
+    pub asset_infos: [AssetInfoRaw; 2],
+    pub contract_addr: CanonicalAddr,
+    pub asset_decimals: [u8; 2],
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Config {
+    pub owner: Addr,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub start_weights: [Decimal; 2],
+    pub end_weights: [Decimal; 2],
+    pub commission_rate: Decimal,
+}
+
+impl Config {
+    /// Weights shift linearly from start_weights to end_weights over the sale window
+    pub fn weights_at(&self, time: u64) -> [Decimal; 2] {
+        if time <= self.start_time {
+            return self.start_weights;
+        }
+        if time >= self.end_time {
+            return self.end_weights;
+        }
+
+        let progress = Decimal::from_ratio(time - self.start_time, self.end_time - self.start_time);
+        [
+            interpolate(self.start_weights[0], self.end_weights[0], progress),
+            interpolate(self.start_weights[1], self.end_weights[1], progress),
+        ]
+    }
+}
+
+fn interpolate(start: Decimal, end: Decimal, progress: Decimal) -> Decimal {
+    if end >= start {
+        start + (end - start) * progress
+    } else {
+        start - (start - end) * progress
+    }
+}
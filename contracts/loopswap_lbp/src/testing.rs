@@ -0,0 +1,426 @@
+use crate::contract::{execute, instantiate, query_lbp_info, query_simulation, query_weights};
+use crate::error::ContractError;
+use crate::math::{compute_swap, pow};
+use loopswap::mock_querier::mock_dependencies;
+
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    to_binary, BankMsg, Coin, CosmosMsg, Decimal, Env, StdError, SubMsg, Timestamp, Uint128,
+    WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use loopswap::asset::{Asset, AssetInfo};
+use loopswap::lbp::{ExecuteMsg, InstantiateMsg};
+use std::str::FromStr;
+
+const START_TIME: u64 = 1_000_000;
+const END_TIME: u64 = 1_086_400;
+
+fn env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
+
+fn init_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        asset_infos: [
+            AssetInfo::Token {
+                contract_addr: "launch0000".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        ],
+        asset_decimals: [6u8, 6u8],
+        owner: "owner0000".to_string(),
+        start_time: START_TIME,
+        end_time: END_TIME,
+        start_weights: [Decimal::percent(96), Decimal::percent(4)],
+        end_weights: [Decimal::percent(50), Decimal::percent(50)],
+        commission_rate: Decimal::permille(3),
+    }
+}
+
+#[test]
+fn proper_initialization() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate(
+        deps.as_mut(),
+        env_at(START_TIME - 100),
+        mock_info("factory0000", &[]),
+        init_msg(),
+    )
+    .unwrap();
+
+    let lbp_info = query_lbp_info(deps.as_ref()).unwrap();
+    assert_eq!(lbp_info.contract_addr, MOCK_CONTRACT_ADDR);
+    assert_eq!(lbp_info.start_time, START_TIME);
+    assert_eq!(lbp_info.end_time, END_TIME);
+
+    // invalid window
+    let mut msg = init_msg();
+    msg.end_time = START_TIME;
+    let err = instantiate(
+        deps.as_mut(),
+        env_at(START_TIME - 100),
+        mock_info("factory0000", &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidSaleWindow {});
+
+    // zero weight
+    let mut msg = init_msg();
+    msg.end_weights = [Decimal::one(), Decimal::zero()];
+    let err = instantiate(
+        deps.as_mut(),
+        env_at(START_TIME - 100),
+        mock_info("factory0000", &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidWeights {});
+}
+
+#[test]
+fn weights_shift_linearly() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate(
+        deps.as_mut(),
+        env_at(START_TIME - 100),
+        mock_info("factory0000", &[]),
+        init_msg(),
+    )
+    .unwrap();
+
+    let weights = query_weights(deps.as_ref(), mock_env(), Some(START_TIME - 1)).unwrap();
+    assert_eq!(weights.weights, [Decimal::percent(96), Decimal::percent(4)]);
+
+    let halfway = START_TIME + (END_TIME - START_TIME) / 2;
+    let weights = query_weights(deps.as_ref(), mock_env(), Some(halfway)).unwrap();
+    assert_eq!(
+        weights.weights,
+        [Decimal::percent(73), Decimal::percent(27)]
+    );
+
+    let weights = query_weights(deps.as_ref(), mock_env(), Some(END_TIME + 1)).unwrap();
+    assert_eq!(
+        weights.weights,
+        [Decimal::percent(50), Decimal::percent(50)]
+    );
+}
+
+#[test]
+fn provide_liquidity() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(100u128),
+    }]);
+    instantiate(
+        deps.as_mut(),
+        env_at(START_TIME - 100),
+        mock_info("factory0000", &[]),
+        init_msg(),
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: [
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: "launch0000".to_string(),
+                },
+                amount: Uint128::from(9600u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(100u128),
+            },
+        ],
+    };
+
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    );
+    let err = execute(deps.as_mut(), env_at(START_TIME - 50), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let info = mock_info(
+        "owner0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    );
+    let res = execute(
+        deps.as_mut(),
+        env_at(START_TIME - 50),
+        info.clone(),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "launch0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: "owner0000".to_string(),
+                recipient: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Uint128::from(9600u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let err = execute(deps.as_mut(), env_at(END_TIME), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::SaleEnded {});
+}
+
+#[test]
+fn swap_only_inside_window() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(1_000_000_100u128),
+    }]);
+    deps.querier.with_token_balances(&[(
+        &"launch0000".to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(24_000_000_000u128),
+        )],
+    )]);
+    instantiate(
+        deps.as_mut(),
+        env_at(START_TIME - 100),
+        mock_info("factory0000", &[]),
+        init_msg(),
+    )
+    .unwrap();
+
+    let offer = Uint128::from(100u128);
+    let msg = ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: offer,
+        },
+        belief_price: None,
+        max_spread: None,
+        to: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: offer,
+        }],
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env_at(START_TIME - 1),
+        info.clone(),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::SaleNotActive {});
+    let err = execute(deps.as_mut(), env_at(END_TIME), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::SaleNotActive {});
+
+    let res = execute(deps.as_mut(), env_at(START_TIME), info, msg).unwrap();
+    let (expected_return, _, _) = compute_swap(
+        Uint128::from(1_000_000_000u128),
+        Uint128::from(24_000_000_000u128),
+        offer,
+        Decimal::percent(4),
+        Decimal::percent(96),
+        Decimal::permille(3),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "launch0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: expected_return,
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
+
+#[test]
+fn withdraw_liquidity_after_end() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(5000u128),
+    }]);
+    deps.querier.with_token_balances(&[(
+        &"launch0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(700u128))],
+    )]);
+    instantiate(
+        deps.as_mut(),
+        env_at(START_TIME - 100),
+        mock_info("factory0000", &[]),
+        init_msg(),
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::WithdrawLiquidity { receiver: None };
+    let err = execute(
+        deps.as_mut(),
+        env_at(END_TIME - 1),
+        mock_info("owner0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::SaleNotEnded {});
+
+    let err = execute(
+        deps.as_mut(),
+        env_at(END_TIME),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(END_TIME),
+        mock_info("owner0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "launch0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "owner0000".to_string(),
+                    amount: Uint128::from(700u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "owner0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(5000u128),
+                }],
+            })),
+        ]
+    );
+}
+
+#[test]
+fn simulation_rejects_unknown_asset() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(1000u128),
+    }]);
+    deps.querier.with_token_balances(&[(
+        &"launch0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000u128))],
+    )]);
+    instantiate(
+        deps.as_mut(),
+        env_at(START_TIME - 100),
+        mock_info("factory0000", &[]),
+        init_msg(),
+    )
+    .unwrap();
+
+    let res = query_simulation(
+        deps.as_ref(),
+        env_at(START_TIME),
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            amount: Uint128::from(10u128),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::AssetMismatch {});
+}
+
+#[test]
+fn simulation_applies_swap_checks() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(1000u128),
+    }]);
+    deps.querier.with_token_balances(&[(
+        &"launch0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000u128))],
+    )]);
+    instantiate(
+        deps.as_mut(),
+        env_at(START_TIME - 100),
+        mock_info("factory0000", &[]),
+        init_msg(),
+    )
+    .unwrap();
+
+    let offer = |amount: u128| Asset {
+        info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        amount: Uint128::from(amount),
+    };
+    let err = query_simulation(deps.as_ref(), env_at(START_TIME - 1), offer(10)).unwrap_err();
+    assert_eq!(err, ContractError::SaleNotActive {});
+    let err = query_simulation(deps.as_ref(), env_at(END_TIME), offer(10)).unwrap_err();
+    assert_eq!(err, ContractError::SaleNotActive {});
+    let err = query_simulation(deps.as_ref(), env_at(START_TIME), offer(600)).unwrap_err();
+    assert_eq!(err, ContractError::MaxRatioExceeded {});
+    query_simulation(deps.as_ref(), env_at(START_TIME), offer(10)).unwrap();
+}
+
+#[test]
+fn pow_matches_constant_product_at_equal_weights() {
+    // equal weights reduce to x * y = k
+    let (return_amount, _, commission_amount) = compute_swap(
+        Uint128::from(1_000_000u128),
+        Uint128::from(1_000_000u128),
+        Uint128::from(1_000u128),
+        Decimal::percent(50),
+        Decimal::percent(50),
+        Decimal::zero(),
+    )
+    .unwrap();
+    assert_eq!(commission_amount, Uint128::zero());
+    // 1_000_000 - 1_000_000 * 1_000_000 / 1_001_000 = 999.000999
+    assert_eq!(return_amount, Uint128::from(999u128));
+
+    let res = pow(
+        Decimal::from_str("0.5").unwrap(),
+        Decimal::from_str("2.5").unwrap(),
+    )
+    .unwrap();
+    let expected = Decimal::from_str("0.176776695296636881").unwrap();
+    assert!(res.max(expected) - res.min(expected) < Decimal::from_str("0.0000001").unwrap());
+
+    let err = pow(
+        Decimal::from_str("2.5").unwrap(),
+        Decimal::from_str("0.5").unwrap(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("pow base out of range"));
+}
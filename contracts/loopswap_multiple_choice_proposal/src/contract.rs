@@ -2,32 +2,34 @@ use std::fmt::format;
 use std::ops::Add;
 
 #[cfg(not(feature = "library"))]
+use crate::error::ContractError;
 use crate::msg::{ExecuteMsg, InstantiateMsg, MultipleChoiceOptionMsg, QueryMsg};
 use crate::proposal::{
-    advance_proposal_id, advance_proposal_version, MultipleChoiceOption, MultipleChoiceProposal,
-    MultipleChoiceVote,
+    advance_proposal_id, advance_proposal_version, proposal_version_key, MultipleChoiceOption,
+    MultipleChoiceProposal, MultipleChoiceVote, RankedVote, VotingStrategy,
 };
 use crate::query::{
     ProposalListResponse, ProposalResponse, VoteInfo, VoteListResponse, VoteResponse,
 };
 use crate::state::{
-    Ballot, Config, BALLOTS, CLOSED_STATUS, CONFIG, EXECUTED_STATUS, FAILED_STATUS, OPEN_STATUS,
-    PASSED_STATUS, POOL_AMOUNTS, PROPOSALS, PROPOSAL_COUNT, PROPOSAL_VERSION, PROPOSERS_INFO,
-    VOTING_CLOSED_STATUS,
+    Ballot, Config, VetoConfig, BALLOTS, CLOSED_STATUS, CONFIG, DAO_CORE, EXECUTED_STATUS,
+    FAILED_STATUS, OPEN_STATUS, PASSED_STATUS, POOL_AMOUNTS, PROPOSALS, PROPOSAL_COUNT,
+    PROPOSAL_VERSION, PROPOSERS_INFO, RANKED_BALLOTS, VETO, VOTING_CLOSED_STATUS,
 };
 
 use crate::status::Status;
 use crate::threshold::Threshold;
 use crate::voting::{self, validate_voting_period, Vote, Votes};
 use cosmwasm_std::{
-    coins, entry_point, to_binary, Addr, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Empty, Env,
-    MessageInfo, QuerierWrapper, QueryRequest, Response, StdError, StdResult, Storage, Uint128,
+    coins, entry_point, to_binary, Addr, Api, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Empty, Env,
+    MessageInfo, QuerierWrapper, QueryRequest, Response, StdResult, Storage, Uint128, WasmMsg,
     WasmQuery,
 };
 use cw2::set_contract_version;
 use cw20::BalanceResponse;
 use cw_storage_plus::Bound;
 use cw_utils::Duration;
+use loopswap::dao::ExecuteMsg as DaoExecuteMsg;
 use loopswap::factory::MigrateMsg;
 use loopswap::staking::QueryMsg as stakingMsg;
 use loopswap_staking::{msg::Cw20QueryMsg as stakingMsg_, state::Config as StakingConfig};
@@ -45,7 +47,7 @@ pub fn instantiate(
     _env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     // msg.threshold.validate()?;
@@ -53,6 +55,11 @@ pub fn instantiate(
     let (min_voting_period, max_voting_period) =
         validate_voting_period(msg.min_voting_period, msg.max_voting_period)?;
 
+    if let Some(veto) = msg.veto {
+        validate_veto_config(deps.api, &veto)?;
+        VETO.save(deps.storage, &veto)?;
+    }
+
     // let (initial_policy, pre_propose_messages) = msg
     //     .pre_propose_info
     //     .into_initial_policy_and_messages(dao.clone())?;
@@ -80,13 +87,19 @@ pub fn instantiate(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Propose {
             title,
             description,
             options,
             voting_period,
+            voting_strategy,
             amount,
         } => execute_propose(
             deps,
@@ -96,6 +109,7 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             description,
             voting_period,
             options,
+            voting_strategy.unwrap_or_default(),
             amount,
         ),
         ExecuteMsg::Vote { proposal_id, vote } => execute_vote(deps, env, info, proposal_id, vote),
@@ -124,9 +138,13 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             proposal_id,
             voting_period,
         } => execute_update_proposal_time(deps, info, env, proposal_id, voting_period),
+        ExecuteMsg::UpdateVetoConfig { veto } => execute_update_veto_config(deps, info, veto),
+        ExecuteMsg::UpdateDaoCore { dao_core } => execute_update_dao_core(deps, info, dao_core),
+        ExecuteMsg::Veto { proposal_id } => execute_veto(deps, env, info, proposal_id),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn execute_propose(
     deps: DepsMut,
     env: Env,
@@ -135,8 +153,9 @@ pub fn execute_propose(
     description: String,
     voting_period: Duration,
     options: Vec<MultipleChoiceOptionMsg>,
+    voting_strategy: VotingStrategy,
     amount: Uint128,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
 
     if !verifying_voting_period(
@@ -144,7 +163,7 @@ pub fn execute_propose(
         &config.min_voting_period,
         &config.max_voting_period,
     ) {
-        return Err(StdError::generic_err("invalid voting period"));
+        return Err(ContractError::InvalidVotingPeriod {});
     }
 
     let vote_power = get_voting_power(
@@ -155,7 +174,7 @@ pub fn execute_propose(
         0u64,
     )?;
     if vote_power.is_zero() {
-        return Err(StdError::generic_err("Power is zero can't create proposal"));
+        return Err(ContractError::ZeroProposalPower {});
     }
 
     let expiration = voting_period.after(&env.block);
@@ -164,9 +183,7 @@ pub fn execute_propose(
         .unwrap_or(Uint128::zero());
 
     if vote_power - locked_amount < config.proposal_creation_token_limit {
-        return Err(StdError::generic_err(
-            "Power is not enough to create proposal",
-        ));
+        return Err(ContractError::InsufficientProposalPower {});
     }
     locked_amount += config.proposal_creation_token_limit;
 
@@ -186,6 +203,7 @@ pub fn execute_propose(
             multiple_choice_options,
             amount,
             voting_period,
+            voting_strategy,
         };
         // Update the proposal's status. Addresses case where proposal
         // expires on the same block as it is created.
@@ -210,7 +228,7 @@ pub fn execute_propose(
     // into it's byte representation in storage.
     let proposal_size = cosmwasm_std::to_vec(&proposal)?.len() as u64;
     if proposal_size > MAX_PROPOSAL_SIZE {
-        return Err(StdError::generic_err("Proposal size is too large"));
+        return Err(ContractError::ProposalTooLarge {});
     }
 
     PROPOSALS.save(deps.storage, id, &proposal)?;
@@ -219,6 +237,7 @@ pub fn execute_propose(
         .add_attribute("action", "propose")
         .add_attribute("sender", sender)
         .add_attribute("proposal_id", id.to_string())
+        .add_attribute("voting_strategy", proposal.voting_strategy.to_string())
         .add_attribute("status", proposal.status.to_string()))
 }
 pub fn get_multiple_choice_options(
@@ -249,14 +268,14 @@ pub fn execute_update_proposal_time(
     env: Env,
     proposal_id: u64,
     voting_period: Duration,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let mut prop: MultipleChoiceProposal = PROPOSALS
         .may_load(deps.storage, proposal_id)?
-        .ok_or(StdError::generic_err("No Such Proposal"))?;
+        .ok_or(ContractError::NoSuchProposal {})?;
 
     if info.sender != prop.proposer {
-        return Err(StdError::generic_err("Unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     if !verifying_voting_period(
@@ -264,7 +283,7 @@ pub fn execute_update_proposal_time(
         &config.min_voting_period,
         &config.max_voting_period,
     ) {
-        return Err(StdError::generic_err("invalid voting period"));
+        return Err(ContractError::InvalidVotingPeriod {});
     }
     prop.voting_period = voting_period;
     prop.expiration = voting_period.after(&env.block);
@@ -279,12 +298,12 @@ pub fn execute_execute(
     env: Env,
     info: MessageInfo,
     proposal_id: u64,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
     let mut prop: MultipleChoiceProposal = PROPOSALS
         .may_load(deps.storage, proposal_id)?
-        .ok_or(StdError::generic_err("No Such Proposal"))?;
+        .ok_or(ContractError::NoSuchProposal {})?;
     let vote_power = get_voting_power(
         &deps.querier,
         deps.storage,
@@ -293,7 +312,7 @@ pub fn execute_execute(
         proposal_id,
     )?;
     if vote_power.is_zero() {
-        return Err(StdError::generic_err("Unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     // Check here that the proposal is passed. Allow it to be executed
@@ -306,10 +325,14 @@ pub fn execute_execute(
     // locked_amount -= config.proposal_creation_token_limit;
 
     // PROPOSERS_INFO.save(deps.storage, prop.proposer.to_string(), &locked_amount)?;
-    let msgs = prop.get_execution_message()?;
+    let proposal_version = proposal_version_key(deps.storage, proposal_id)?;
+    let msgs = proposal_messages(
+        deps.storage,
+        prop.get_execution_message(deps.storage, &proposal_version)?,
+    )?;
     let status = prop.current_status(&env.block)?;
     if status != Status::VotingClosed {
-        return Err(StdError::generic_err("Voting not closed yet"));
+        return Err(ContractError::VotingNotClosed {});
     }
     prop.status = Status::Open;
     prop.total_power = Uint128::zero();
@@ -321,7 +344,6 @@ pub fn execute_execute(
         choices.push(choice.clone());
     }
     prop.multiple_choice_options = choices;
-    println!("msgs {:?}", msgs);
     PROPOSALS.save(deps.storage, proposal_id, &prop)?;
     advance_proposal_version(deps.storage, proposal_id.clone())?;
     let response = Response::new()
@@ -339,10 +361,10 @@ pub fn execute_add_multiple_choice_options(
     info: MessageInfo,
     proposal_id: u64,
     options: Vec<MultipleChoiceOptionMsg>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut prop: MultipleChoiceProposal = PROPOSALS
         .may_load(deps.storage, proposal_id)?
-        .ok_or(StdError::generic_err("No Such Proposal"))?;
+        .ok_or(ContractError::NoSuchProposal {})?;
     for option in options {
         let choice = MultipleChoiceOption {
             address: option.address,
@@ -367,11 +389,11 @@ pub fn execute_vote(
     info: MessageInfo,
     proposal_id: u64,
     votes: Vec<MultipleChoiceVote>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let mut prop: MultipleChoiceProposal = PROPOSALS
         .may_load(deps.storage, proposal_id)?
-        .ok_or(StdError::generic_err("No such proposal"))?;
+        .ok_or(ContractError::NoSuchProposal {})?;
 
     let vote_power = get_voting_power(
         &deps.querier,
@@ -382,27 +404,60 @@ pub fn execute_vote(
     )?;
     validate_options(&votes, &prop)?;
     if vote_power.is_zero() {
-        let mut message = "Not Registered".to_string();
-        message.push_str(&format!(
-            "sender {} dao {}",
-            info.sender.clone(),
-            config.dao.to_string(),
-        ));
-        return Err(StdError::generic_err(&message));
+        return Err(ContractError::NotRegistered {
+            sender: info.sender.to_string(),
+        });
     }
 
     if prop.expiration.is_expired(&env.block) {
-        return Err(StdError::generic_err("Voting time expired"));
-    } else if prop.status == Status::Executed || prop.status == Status::Closed {
-        return Err(StdError::generic_err(format!(
-            "Proposal is in {} state",
-            prop.status
-        )));
+        return Err(ContractError::VotingExpired {});
+    } else if prop.status == Status::Executed
+        || prop.status == Status::Closed
+        || prop.status == Status::Vetoed
+    {
+        return Err(ContractError::InvalidProposalStatus {
+            status: prop.status,
+        });
     }
-    let version = PROPOSAL_VERSION.load(deps.storage, proposal_id)?;
-    let mut proposal_version = String::from(&proposal_id.to_string());
-    proposal_version.push_str(".");
-    proposal_version.push_str(&version.to_string());
+    let proposal_version = proposal_version_key(deps.storage, proposal_id)?;
+    if prop.voting_strategy == (VotingStrategy::RankedChoice {}) {
+        let ranking: Vec<u32> = votes.iter().map(|vote| vote.option_id).collect();
+        let key = (proposal_version.clone(), info.sender.to_string());
+        if let Some(previous) = RANKED_BALLOTS.may_load(deps.storage, key.clone())? {
+            if !prop.allow_revoting {
+                return Err(ContractError::AlreadyCast {});
+            }
+            prop.remove_ranked_vote(
+                deps.storage,
+                &proposal_version,
+                &previous.ranking,
+                previous.power,
+            )?;
+            prop.total_power -= previous.power;
+        }
+        prop.add_ranked_vote(deps.storage, &proposal_version, &ranking, vote_power)?;
+        RANKED_BALLOTS.save(
+            deps.storage,
+            key,
+            &RankedVote {
+                ranking,
+                power: vote_power,
+            },
+        )?;
+        prop.total_power += vote_power;
+        prop.update_status(&env.block)?;
+        PROPOSALS.save(deps.storage, proposal_id, &prop)?;
+
+        return Ok(Response::default()
+            .add_attribute("action", "vote")
+            .add_attribute("sender", info.sender)
+            .add_attribute("proposal_id", proposal_id.to_string())
+            .add_attribute("status", prop.status.to_string())
+            .add_attribute("power added", vote_power));
+    }
+
+    // Quadratic proposals count the square root of the voter's power.
+    let vote_power = prop.voting_strategy.vote_weight(vote_power);
     validate_votes_percentage(votes.clone())?;
     for options in votes {
         BALLOTS.update(
@@ -419,7 +474,7 @@ pub fn execute_vote(
                             // Don't allow casting the same vote more than
                             // once. This seems liable to be confusing
                             // behavior.
-                            Err(StdError::generic_err("Already Casted"))
+                            Err(ContractError::AlreadyCast {})
                         } else {
                             // Remove the old vote if this is a re-vote.
                             prop.multiple_choice_options
@@ -471,13 +526,13 @@ pub fn execute_vote(
 
 pub fn validate_votes_percentage(
     votes: Vec<MultipleChoiceVote>,
-) -> StdResult<bool> {
+) -> Result<bool, ContractError> {
     let mut vote_percentage = 0u32;
     for vote in votes {
         vote_percentage += vote.percentage;
     }
     if vote_percentage > 100u32 {
-        return Err(StdError::generic_err("total percenatge voting is invalid"));
+        return Err(ContractError::InvalidVotePercentage {});
     }
     Ok(true)
 }
@@ -485,9 +540,21 @@ pub fn validate_votes_percentage(
 pub fn validate_options(
     vote: &Vec<MultipleChoiceVote>,
     prop: &MultipleChoiceProposal,
-) -> StdResult<()> {
+) -> Result<(), ContractError> {
     if vote.len() > prop.multiple_choice_options.len() {
-        return Err(StdError::generic_err("not valid options provided"));
+        return Err(ContractError::InvalidOptions {});
+    }
+    let num_options = prop.multiple_choice_options.len() as u32;
+    for (index, choice) in vote.iter().enumerate() {
+        if choice.option_id == 0
+            || choice.option_id > num_options
+            || vote[..index].iter().any(|v| v.option_id == choice.option_id)
+        {
+            return Err(ContractError::InvalidOptions {});
+        }
+    }
+    if prop.voting_strategy == (VotingStrategy::RankedChoice {}) && vote.is_empty() {
+        return Err(ContractError::InvalidOptions {});
     }
     Ok(())
 }
@@ -496,9 +563,15 @@ pub fn execute_close(
     env: Env,
     info: MessageInfo,
     proposal_id: u64,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut prop: MultipleChoiceProposal = PROPOSALS.load(deps.storage, proposal_id)?;
     let config: Config = CONFIG.load(deps.storage)?;
+    // The proposer's tokens were released when it was vetoed.
+    if prop.status == Status::Vetoed {
+        return Err(ContractError::InvalidProposalStatus {
+            status: prop.status,
+        });
+    }
 
     // Update status to ensure that proposals which were open and have
     // expired are moved to "rejected."
@@ -507,9 +580,7 @@ pub fn execute_close(
         .load(deps.storage, prop.proposer.to_string())
         .unwrap_or(Uint128::zero());
     if prop.voting_start_time + config.token_hold_duration < env.block.time.seconds() {
-        return Err(StdError::generic_err(
-            "tokens are hold in the contract kindly wait for the specified time",
-        ));
+        return Err(ContractError::TokensLocked {});
     }
 
     prop.update_status(&env.block)?;
@@ -528,6 +599,119 @@ pub fn execute_close(
         .add_attribute("proposal_id", proposal_id.to_string()))
 }
 
+pub fn execute_veto(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let veto = VETO
+        .may_load(deps.storage)?
+        .ok_or(ContractError::Unauthorized {})?;
+    if info.sender != veto.vetoer {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut prop: MultipleChoiceProposal = PROPOSALS
+        .may_load(deps.storage, proposal_id)?
+        .ok_or(ContractError::NoSuchProposal {})?;
+    let status = prop.current_status(&env.block)?;
+    let allowed = match status {
+        Status::Open => veto.veto_open,
+        Status::VotingClosed => veto.veto_passed,
+        _ => false,
+    };
+    if !allowed {
+        return Err(ContractError::InvalidProposalStatus { status });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut locked_amount = PROPOSERS_INFO
+        .load(deps.storage, prop.proposer.to_string())
+        .unwrap_or(Uint128::zero());
+    locked_amount -= config.proposal_creation_token_limit;
+    PROPOSERS_INFO.save(deps.storage, prop.proposer.to_string(), &locked_amount)?;
+
+    prop.status = Status::Vetoed;
+    PROPOSALS.save(deps.storage, proposal_id, &prop)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "veto")
+        .add_attribute("sender", info.sender)
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("previous_status", status.to_string()))
+}
+
+pub fn execute_update_veto_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    veto: Option<VetoConfig>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match veto {
+        Some(veto) => {
+            validate_veto_config(deps.api, &veto)?;
+            VETO.save(deps.storage, &veto)?;
+        }
+        None => VETO.remove(deps.storage),
+    }
+
+    Ok(Response::default()
+        .add_attribute("action", "update_veto_config")
+        .add_attribute("sender", info.sender))
+}
+
+/// Hands the messages of a passed proposal to the DAO core if one is
+/// set, so they run with the DAO's admin roles and treasury.
+fn proposal_messages(
+    storage: &dyn Storage,
+    msgs: Vec<CosmosMsg<Empty>>,
+) -> StdResult<Vec<CosmosMsg<Empty>>> {
+    match DAO_CORE.may_load(storage)? {
+        Some(dao_core) if !msgs.is_empty() => Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: dao_core.to_string(),
+            msg: to_binary(&DaoExecuteMsg::ExecuteProposalHook { msgs })?,
+            funds: vec![],
+        })]),
+        _ => Ok(msgs),
+    }
+}
+
+pub fn execute_update_dao_core(
+    deps: DepsMut,
+    info: MessageInfo,
+    dao_core: Option<String>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match dao_core {
+        Some(dao_core) => {
+            let dao_core = deps.api.addr_validate(&dao_core)?;
+            DAO_CORE.save(deps.storage, &dao_core)?;
+        }
+        None => DAO_CORE.remove(deps.storage),
+    }
+
+    Ok(Response::default()
+        .add_attribute("action", "update_dao_core")
+        .add_attribute("sender", info.sender))
+}
+
+fn validate_veto_config(api: &dyn Api, veto: &VetoConfig) -> Result<(), ContractError> {
+    api.addr_validate(&veto.vetoer)?;
+    if !veto.veto_open && !veto.veto_passed {
+        return Err(ContractError::InvalidVetoConfig {});
+    }
+    Ok(())
+}
+
 pub fn get_voting_power(
     querier: &QuerierWrapper,
     store: &mut dyn Storage,
@@ -569,12 +753,12 @@ pub fn execute_update_config(
     dao_: Option<String>,
     token_hold_duration_: Option<u64>,
     proposal_creation_token_limit_: Option<Uint128>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
 
     // Only the DAO may call this method.
     if info.sender != config.admin {
-        return Err(StdError::generic_err("Unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
     let mut min_voting_period = config.min_voting_period;
     let mut max_voting_period = config.max_voting_period;
@@ -652,6 +836,11 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         } => query_list_votes(deps, proposal_id, start_after, limit),
         QueryMsg::Info {} => query_info(deps),
         QueryMsg::HoldAmount { address } => query_hold_amount(deps, address),
+        QueryMsg::VetoConfig {} => to_binary(&VETO.may_load(deps.storage)?),
+        QueryMsg::DaoCore {} => to_binary(&DAO_CORE.may_load(deps.storage)?),
+        QueryMsg::GetRankedVote { proposal_id, voter } => {
+            query_ranked_vote(deps, proposal_id, voter)
+        }
         // QueryMsg::ReverseProposals {
         //     start_before,
         //     limit,
@@ -761,6 +950,12 @@ pub fn query_user_list_vote(deps: Deps, proposal_id: u64, voter: String) -> StdR
     to_binary(&VoteListResponse { votes })
 }
 
+pub fn query_ranked_vote(deps: Deps, proposal_id: u64, voter: String) -> StdResult<Binary> {
+    let version = PROPOSAL_VERSION.load(deps.storage, proposal_id)?;
+    let proposal_version = format!("{}.{}", proposal_id, version);
+    to_binary(&RANKED_BALLOTS.may_load(deps.storage, (proposal_version, voter))?)
+}
+
 pub fn query_list_votes(
     deps: Deps,
     proposal_id: u64,
@@ -826,6 +1021,6 @@ pub fn query_info(deps: Deps) -> StdResult<Binary> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
     Ok(Response::default())
 }
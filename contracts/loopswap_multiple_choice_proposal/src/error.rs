@@ -0,0 +1,64 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+use crate::status::Status;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid voting period")]
+    InvalidVotingPeriod {},
+
+    #[error("Min and max voting period must both be heights or both be times")]
+    VotingPeriodUnitMismatch {},
+
+    #[error("Zero threshold")]
+    ZeroThreshold {},
+
+    #[error("Unreachable threshold")]
+    UnreachableThreshold {},
+
+    #[error("Power is zero can't create proposal")]
+    ZeroProposalPower {},
+
+    #[error("Power is not enough to create proposal")]
+    InsufficientProposalPower {},
+
+    #[error("Proposal size is too large")]
+    ProposalTooLarge {},
+
+    #[error("No such proposal")]
+    NoSuchProposal {},
+
+    #[error("Not registered, sender {sender} has no voting power")]
+    NotRegistered { sender: String },
+
+    #[error("Voting time expired")]
+    VotingExpired {},
+
+    #[error("Proposal is in {status} state")]
+    InvalidProposalStatus { status: Status },
+
+    #[error("Already casted")]
+    AlreadyCast {},
+
+    #[error("tokens are hold in the contract kindly wait for the specified time")]
+    TokensLocked {},
+
+    #[error("Voting not closed yet")]
+    VotingNotClosed {},
+
+    #[error("Total voting percentage is invalid")]
+    InvalidVotePercentage {},
+
+    #[error("Invalid options provided")]
+    InvalidOptions {},
+
+    #[error("Veto config must allow vetoing at least one stage")]
+    InvalidVetoConfig {},
+}
@@ -7,5 +7,7 @@ pub mod status;
 pub mod threshold;
 pub mod voting;
 
+mod error;
+
 #[cfg(test)]
 mod testing;
@@ -1,7 +1,8 @@
 use crate::proposal::{MultipleChoiceOption, MultipleChoiceVote};
+use crate::state::VetoConfig;
 use crate::threshold::Threshold;
 use crate::voting::Vote;
-// use crate::proposal::VotingStrategy;
+use crate::proposal::VotingStrategy;
 use cosmwasm_std::{CosmosMsg, Empty, Uint128};
 use cw_utils::Duration;
 use schemars::JsonSchema;
@@ -44,6 +45,9 @@ pub struct InstantiateMsg {
     pub proposal_creation_token_limit: Uint128,
 
     pub token_hold_duration: u64,
+
+    /// The optional veto council.
+    pub veto: Option<VetoConfig>,
 }
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -58,7 +62,8 @@ pub enum ExecuteMsg {
         /// proposal passing.
         options: Vec<MultipleChoiceOptionMsg>,
         voting_period: Duration,
-        // voting_strategy: VotingStrategy,
+        /// How votes are tallied, defaults to a weighted split.
+        voting_strategy: Option<VotingStrategy>,
         amount: Uint128,
     },
     /// Votes on a proposal. Voting power is determined by the DAO's
@@ -66,7 +71,9 @@ pub enum ExecuteMsg {
     Vote {
         /// The ID of the proposal to vote on.
         proposal_id: u64,
-        /// The senders position on the proposal.
+        /// The senders position on the proposal. For ranked-choice
+        /// proposals the options are listed in order of preference and
+        /// percentages are ignored.
         vote: Vec<MultipleChoiceVote>,
     },
     /// Causes the messages associated with a passed proposal to be
@@ -112,6 +119,20 @@ pub enum ExecuteMsg {
         proposal_id: u64,
         voting_period: Duration,
     },
+    /// Sets or removes the veto council.
+    UpdateVetoConfig {
+        veto: Option<VetoConfig>,
+    },
+    /// Sets or removes the DAO core. Messages of passed proposals are
+    /// executed by the DAO core if set, otherwise by this module.
+    UpdateDaoCore {
+        dao_core: Option<String>,
+    },
+    /// Vetoes a proposal. Only callable by the veto council, at the
+    /// stages its config allows.
+    Veto {
+        proposal_id: u64,
+    },
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
@@ -194,6 +215,15 @@ pub enum QueryMsg {
     HoldAmount {
         address: String,
     },
+    /// Gets the veto council config, if any.
+    VetoConfig {},
+    /// Gets the DAO core, if any.
+    DaoCore {},
+    /// Gets a voter's ranking on a ranked-choice proposal.
+    GetRankedVote {
+        proposal_id: u64,
+        voter: String,
+    },
 }
 
 // #[cw_serde]
@@ -1,15 +1,15 @@
 use crate::query::ProposalResponse;
-use crate::state::PROPOSAL_COUNT;
 use crate::state::{
     PassedResponse, CLOSED_STATUS, EXECUTED_STATUS, FAILED_STATUS, OPEN_STATUS, PASSED_STATUS,
     PROPOSAL_VERSION, VOTING_CLOSED_STATUS,
 };
+use crate::state::{PROPOSAL_COUNT, RANKED_TALLIES};
 use crate::status::Status;
 use crate::threshold::{PercentageThreshold, Threshold};
 use crate::voting::{does_vote_count_fail, does_vote_count_pass, Votes};
 use cosmwasm_std::{
-    to_binary, Addr, BlockInfo, CosmosMsg, Decimal, Empty, StdError, StdResult, Storage, Uint128,
-    WasmMsg,
+    to_binary, Addr, BlockInfo, CosmosMsg, Decimal, Empty, Isqrt, Order, StdError, StdResult,
+    Storage, Uint128, Uint256, WasmMsg,
 };
 use cw20_base::msg::ExecuteMsg as Cw20ExecuteMsg;
 use cw_utils::Duration;
@@ -44,6 +44,55 @@ pub struct MultipleChoiceProposal {
 
     pub multiple_choice_options: Vec<MultipleChoiceOption>,
     pub amount: Uint128,
+    /// How votes are tallied.
+    #[serde(default)]
+    pub voting_strategy: VotingStrategy,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum VotingStrategy {
+    /// Voters split their power across options by percentage and
+    /// every option receives its share of the amount.
+    Weighted {},
+    /// Voters rank options in order of preference. The instant runoff
+    /// winner receives the whole amount.
+    RankedChoice {},
+    /// Like weighted, but a voter's power counts as its square root.
+    Quadratic {},
+}
+
+impl Default for VotingStrategy {
+    fn default() -> Self {
+        VotingStrategy::Weighted {}
+    }
+}
+
+impl VotingStrategy {
+    /// The weight a voter with `power` adds to the tally.
+    pub fn vote_weight(&self, power: Uint128) -> Uint128 {
+        match self {
+            VotingStrategy::Quadratic {} => power.isqrt(),
+            _ => power,
+        }
+    }
+}
+
+impl std::fmt::Display for VotingStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VotingStrategy::Weighted {} => write!(f, "weighted"),
+            VotingStrategy::RankedChoice {} => write!(f, "ranked_choice"),
+            VotingStrategy::Quadratic {} => write!(f, "quadratic"),
+        }
+    }
+}
+
+/// Power behind a ranking of option ids, most preferred first.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct RankedVote {
+    pub ranking: Vec<u32>,
+    pub power: Uint128,
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
@@ -132,20 +181,35 @@ impl MultipleChoiceProposal {
     /// means that quorum has been met,
     /// one of the options that is not "None of the above"
     /// has won the most votes, and there is no tie.
-    pub fn get_execution_message(&self) -> StdResult<Vec<CosmosMsg<Empty>>> {
+    pub fn get_execution_message(
+        &self,
+        store: &dyn Storage,
+        proposal_version: &str,
+    ) -> StdResult<Vec<CosmosMsg<Empty>>> {
+        let ranked_winner = match self.voting_strategy {
+            VotingStrategy::RankedChoice {} => {
+                self.ranked_choice_winner(store, proposal_version)?
+            }
+            _ => None,
+        };
         // Proposal can only pass if quorum has been met.
         let mut msgs: Vec<CosmosMsg<Empty>> = vec![];
-        for choice in self.multiple_choice_options.clone() {
-            let amount = self.calculate_vote_result(&choice);
-            let message = CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: choice.address.unwrap(),
-                msg: to_binary(&FarmingExecuteMsg::UpdateReward {
-                    pool: choice.pool.unwrap(),
-                    rewards: vec![(choice.reward_token.unwrap(), choice.votes.power)],
-                })?,
-                funds: vec![],
-            });
+        for (index, choice) in self.multiple_choice_options.clone().into_iter().enumerate() {
+            let amount = self.calculate_vote_result(index as u32 + 1, &choice, ranked_winner);
             if amount != Uint128::zero() {
+                // Weighted proposals reward an option with its votes.
+                let reward = match self.voting_strategy {
+                    VotingStrategy::Weighted {} => choice.votes.power,
+                    _ => amount,
+                };
+                let message = CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: choice.address.unwrap(),
+                    msg: to_binary(&FarmingExecuteMsg::UpdateReward {
+                        pool: choice.pool.unwrap(),
+                        rewards: vec![(choice.reward_token.unwrap(), reward)],
+                    })?,
+                    funds: vec![],
+                });
                 msgs.push(message);
             }
         }
@@ -153,16 +217,161 @@ impl MultipleChoiceProposal {
         Ok(msgs)
     }
 
-    /// Find the option with the highest vote weight, and note if there is a tie.
-    pub fn calculate_vote_result(&self, choice: &MultipleChoiceOption) -> Uint128 {
-        if self.total_power == Uint128::zero() {
-            Uint128::zero();
+    /// The part of the proposal amount that goes to an option under
+    /// the proposal's voting strategy. Ranked-choice proposals give
+    /// the whole amount to `ranked_winner`.
+    pub fn calculate_vote_result(
+        &self,
+        option_id: u32,
+        choice: &MultipleChoiceOption,
+        ranked_winner: Option<u32>,
+    ) -> Uint128 {
+        match self.voting_strategy {
+            VotingStrategy::Weighted {} | VotingStrategy::Quadratic {} => {
+                // Option tallies already hold the strategy's weights.
+                if self.total_power == Uint128::zero() {
+                    return Uint128::zero();
+                }
+                let per = choice
+                    .votes
+                    .power
+                    .multiply_ratio(Uint128::from(100u128), self.total_power);
+
+                self.amount.multiply_ratio(per, Uint128::from(100u128))
+            }
+            VotingStrategy::RankedChoice {} => {
+                if ranked_winner == Some(option_id) {
+                    self.amount
+                } else {
+                    Uint128::zero()
+                }
+            }
+        }
+    }
+
+    /// Adds a ranked ballot to the tally of its ranking. The most
+    /// preferred option is credited with the power so option tallies
+    /// show first preferences.
+    pub fn add_ranked_vote(
+        &mut self,
+        store: &mut dyn Storage,
+        proposal_version: &str,
+        ranking: &[u32],
+        power: Uint128,
+    ) -> StdResult<()> {
+        RANKED_TALLIES.update(
+            store,
+            (proposal_version.to_string(), ranking_key(ranking)),
+            |tally| -> StdResult<_> { Ok(tally.unwrap_or_default() + power) },
+        )?;
+        if let Some(first) = ranking.first() {
+            self.multiple_choice_options[*first as usize - 1].add_vote(power, 100);
         }
-        let per = choice
-            .votes
-            .power
-            .multiply_ratio(Uint128::from(100u128), self.total_power);
+        Ok(())
+    }
 
-        self.amount.multiply_ratio(per, Uint128::from(100u128))
+    pub fn remove_ranked_vote(
+        &mut self,
+        store: &mut dyn Storage,
+        proposal_version: &str,
+        ranking: &[u32],
+        power: Uint128,
+    ) -> StdResult<()> {
+        let key = (proposal_version.to_string(), ranking_key(ranking));
+        let tally = RANKED_TALLIES
+            .load(store, key.clone())?
+            .checked_sub(power)?;
+        if tally.is_zero() {
+            RANKED_TALLIES.remove(store, key);
+        } else {
+            RANKED_TALLIES.save(store, key, &tally)?;
+        }
+        if let Some(first) = ranking.first() {
+            self.multiple_choice_options[*first as usize - 1].remove_vote(power);
+        }
+        Ok(())
     }
+
+    /// Runs an instant runoff over the ranked ballots. Every round each
+    /// ballot counts for its most preferred option still in the race.
+    /// An option with a majority of the counted power wins, otherwise
+    /// the option with the least power is eliminated, the later option
+    /// on a tie.
+    pub fn ranked_choice_winner(
+        &self,
+        store: &dyn Storage,
+        proposal_version: &str,
+    ) -> StdResult<Option<u32>> {
+        let ranked_votes = RANKED_TALLIES
+            .prefix(proposal_version.to_string())
+            .range(store, None, None, Order::Ascending)
+            .map(|item| {
+                let (ranking, power) = item?;
+                Ok(RankedVote {
+                    ranking: parse_ranking(&ranking)?,
+                    power,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let mut continuing: Vec<u32> = (1..=self.multiple_choice_options.len() as u32).collect();
+        while !continuing.is_empty() {
+            let mut tally = vec![Uint128::zero(); continuing.len()];
+            for vote in ranked_votes.iter() {
+                if let Some(index) = vote
+                    .ranking
+                    .iter()
+                    .find_map(|option| continuing.iter().position(|o| o == option))
+                {
+                    tally[index] += vote.power;
+                }
+            }
+
+            let counted: Uint128 = tally.iter().sum();
+            if counted.is_zero() {
+                return Ok(None);
+            }
+            if let Some(index) = tally
+                .iter()
+                .position(|power| power.full_mul(2u64) > Uint256::from(counted))
+            {
+                return Ok(Some(continuing[index]));
+            }
+
+            let (loser, _) = tally
+                .iter()
+                .enumerate()
+                .rev()
+                .min_by_key(|(_, power)| **power)
+                .unwrap();
+            continuing.remove(loser);
+        }
+        Ok(None)
+    }
+}
+
+/// The key ballots with the same ranking are merged under.
+fn ranking_key(ranking: &[u32]) -> String {
+    ranking
+        .iter()
+        .map(|option| option.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn parse_ranking(key: &str) -> StdResult<Vec<u32>> {
+    key.split(',')
+        .map(|option| {
+            option
+                .parse()
+                .map_err(|_| StdError::generic_err("invalid ranking"))
+        })
+        .collect()
+}
+
+/// The key ballots of the current voting round of a proposal are
+/// stored under.
+pub fn proposal_version_key(store: &dyn Storage, proposal_id: u64) -> StdResult<String> {
+    let version = PROPOSAL_VERSION.load(store, proposal_id)?;
+    Ok(format!("{}.{}", proposal_id, version))
 }
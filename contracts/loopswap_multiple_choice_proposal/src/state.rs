@@ -1,5 +1,6 @@
 use crate::proposal::MultipleChoiceProposal;
 use crate::proposal::MultipleChoiceVote;
+use crate::proposal::RankedVote;
 use crate::threshold::Threshold;
 use crate::voting::Vote;
 use cosmwasm_std::{Addr, Uint128};
@@ -46,6 +47,17 @@ pub struct Config {
 
     pub token_hold_duration: u64,
 }
+/// Who may veto proposals and at which stage.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct VetoConfig {
+    /// The council or multisig allowed to veto proposals.
+    pub vetoer: String,
+    /// Allows vetoing proposals that are still open for voting.
+    pub veto_open: bool,
+    /// Allows vetoing proposals whose voting closed but which have
+    /// not been executed yet.
+    pub veto_passed: bool,
+}
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct PassedResponse {
     pub is_passed: bool,
@@ -62,3 +74,13 @@ pub const PROPOSALS: Map<u64, MultipleChoiceProposal> = Map::new("proposals_v2")
 pub const BALLOTS: Map<(String, String, u32), Ballot> = Map::new("ballots");
 pub const PROPOSERS_INFO: Map<String, Uint128> = Map::new("Proposer Amount");
 pub const POOL_AMOUNTS: Map<(u64, u64), Uint128> = Map::new("Pool Amounts");
+/// The optional veto council.
+pub const VETO: Item<VetoConfig> = Item::new("veto");
+/// The DAO core passed proposals are executed through.
+pub const DAO_CORE: Item<Addr> = Item::new("dao_core");
+/// Ranked-choice ballots keyed by (proposal_version, voter).
+pub const RANKED_BALLOTS: Map<(String, String), RankedVote> = Map::new("ranked_ballots");
+/// Ranked-choice power merged by identical ranking, keyed by
+/// (proposal_version, ranking) with the ranking as comma separated
+/// option ids.
+pub const RANKED_TALLIES: Map<(String, String), Uint128> = Map::new("ranked_tallies");
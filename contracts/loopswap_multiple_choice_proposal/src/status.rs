@@ -17,6 +17,8 @@ pub enum Status {
     Closed,
     /// The proposal's execution failed.
     ExecutionFailed,
+    /// The proposal was vetoed by the veto council.
+    Vetoed,
 }
 
 impl std::fmt::Display for Status {
@@ -28,6 +30,7 @@ impl std::fmt::Display for Status {
             Status::Executed => write!(f, "executed"),
             Status::Closed => write!(f, "closed"),
             Status::ExecutionFailed => write!(f, "execution failed"),
+            Status::Vetoed => write!(f, "vetoed"),
         }
     }
 }
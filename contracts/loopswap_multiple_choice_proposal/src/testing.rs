@@ -1,5 +1,6 @@
 // use crate::contract::{execute_mint, instantiate, query, query_balance};
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, query, validate_votes_percentage};
+use crate::error::ContractError;
 use crate::proposal::{MultipleChoiceOption, MultipleChoiceVote};
 use crate::query::ProposalResponse;
 use crate::threshold::{PercentageThreshold, Threshold};
@@ -42,6 +43,7 @@ mod tests {
             dao: "Staking".to_string(),
             proposal_creation_token_limit: Uint128::from(1u128),
             token_hold_duration: 1,
+            veto: None,
             // marketing: None,
         };
         println!("{:?}", init_msg);
@@ -90,6 +92,7 @@ mod tests {
             description: "new".to_string(),
             voting_period: Duration::Time(120),
             options: choices,
+            voting_strategy: None,
             amount: Uint128::from(1000000u128),
         };
 
@@ -172,5 +175,408 @@ mod tests {
         // println!("{:?}", _res)
     }
 
-    // #[test]
+    fn init_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            max_voting_period: Duration::Time(300),
+            min_voting_period: Duration::Time(5),
+            only_members_execute: false,
+            allow_revoting: false,
+            close_proposal_on_execution_failure: false,
+            dao: "Staking".to_string(),
+            proposal_creation_token_limit: Uint128::from(1u128),
+            token_hold_duration: 1,
+            veto: None,
+        }
+    }
+
+    #[test]
+    fn typed_errors() {
+        let mut deps = mock_dependencies(&[]);
+
+        let mut msg = init_msg();
+        msg.min_voting_period = Duration::Height(5);
+        let err = instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::VotingPeriodUnitMismatch {});
+
+        let mut msg = init_msg();
+        msg.min_voting_period = Duration::Time(500);
+        let err = instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidVotingPeriod {});
+
+        instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), init_msg()).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::Propose {
+                title: "test".to_string(),
+                description: "new".to_string(),
+                options: vec![],
+                voting_period: Duration::Time(1000),
+                voting_strategy: None,
+                amount: Uint128::zero(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidVotingPeriod {});
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::Vote {
+                proposal_id: 1,
+                vote: vec![MultipleChoiceVote {
+                    option_id: 1,
+                    percentage: 100,
+                }],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NoSuchProposal {});
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::UpdateConfig {
+                max_voting_period: None,
+                min_voting_period: None,
+                dao: None,
+                token_hold_duration: None,
+                proposal_creation_token_limit: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+    }
+
+    #[test]
+    fn vote_percentage_over_hundred() {
+        let votes = vec![
+            MultipleChoiceVote {
+                option_id: 1,
+                percentage: 60,
+            },
+            MultipleChoiceVote {
+                option_id: 2,
+                percentage: 50,
+            },
+        ];
+        assert_eq!(
+            validate_votes_percentage(votes).unwrap_err(),
+            ContractError::InvalidVotePercentage {}
+        );
+    }
+}
+
+mod veto {
+    use super::*;
+    use crate::proposal::{MultipleChoiceProposal, VotingStrategy};
+    use crate::query::ProposalListResponse;
+    use crate::state::{VetoConfig, PROPOSALS, PROPOSERS_INFO};
+    use crate::status::Status;
+    use cosmwasm_std::Addr;
+    use cw_utils::Expiration;
+
+    #[test]
+    fn test_veto() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            max_voting_period: Duration::Time(300),
+            min_voting_period: Duration::Time(5),
+            only_members_execute: false,
+            allow_revoting: false,
+            close_proposal_on_execution_failure: false,
+            dao: "Staking".to_string(),
+            proposal_creation_token_limit: Uint128::from(1u128),
+            token_hold_duration: 1,
+            veto: Some(VetoConfig {
+                vetoer: "council".to_string(),
+                veto_open: false,
+                veto_passed: true,
+            }),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+        // the staking mock can't report voting power per duration, so
+        // the proposal is stored directly
+        let proposal = MultipleChoiceProposal {
+            title: "test".to_string(),
+            description: "new".to_string(),
+            proposer: Addr::unchecked("loop_staker1"),
+            expiration: Expiration::AtTime(mock_env().block.time.plus_seconds(120)),
+            voting_period: Duration::Time(120),
+            status: Status::Open,
+            allow_revoting: false,
+            total_power: Uint128::zero(),
+            voting_start_time: mock_env().block.time.seconds(),
+            multiple_choice_options: vec![],
+            amount: Uint128::zero(),
+            voting_strategy: VotingStrategy::Weighted {},
+        };
+        PROPOSALS.save(deps.as_mut().storage, 1, &proposal).unwrap();
+        PROPOSERS_INFO
+            .save(
+                deps.as_mut().storage,
+                "loop_staker1".to_string(),
+                &Uint128::from(1u128),
+            )
+            .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::Veto { proposal_id: 1 },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("council", &[]),
+            ExecuteMsg::Veto { proposal_id: 1 },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidProposalStatus {
+                status: Status::Open
+            }
+        );
+
+        let closed_env = mock_env_time(mock_env().block.time.plus_seconds(121));
+        execute(
+            deps.as_mut(),
+            closed_env.clone(),
+            mock_info("council", &[]),
+            ExecuteMsg::Veto { proposal_id: 1 },
+        )
+        .unwrap();
+        let hold: Uint128 = from_binary(
+            &query(
+                deps.as_ref(),
+                closed_env.clone(),
+                QueryMsg::HoldAmount {
+                    address: "loop_staker1".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(hold, Uint128::zero());
+
+        let err = execute(
+            deps.as_mut(),
+            closed_env.clone(),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::Close { proposal_id: 1 },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidProposalStatus {
+                status: Status::Vetoed
+            }
+        );
+
+        let list: ProposalListResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                closed_env,
+                QueryMsg::ListProposals {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(list.proposals[0].proposal.status, Status::Vetoed);
+    }
+}
+
+mod strategies {
+    use super::*;
+    use crate::contract::validate_options;
+    use crate::proposal::{MultipleChoiceProposal, VotingStrategy};
+    use crate::state::RANKED_TALLIES;
+    use crate::status::Status;
+    use crate::voting::Votes;
+    use cosmwasm_std::testing::MockStorage;
+    use cosmwasm_std::{Addr, Order, StdResult};
+    use cw_utils::Expiration;
+
+    fn proposal(voting_strategy: VotingStrategy, num_options: usize) -> MultipleChoiceProposal {
+        MultipleChoiceProposal {
+            title: "test".to_string(),
+            description: "new".to_string(),
+            proposer: Addr::unchecked("loop_staker1"),
+            expiration: Expiration::AtTime(mock_env().block.time.plus_seconds(120)),
+            voting_period: Duration::Time(120),
+            status: Status::Open,
+            allow_revoting: false,
+            total_power: Uint128::zero(),
+            voting_start_time: mock_env().block.time.seconds(),
+            multiple_choice_options: (1..=num_options)
+                .map(|i| MultipleChoiceOption {
+                    title: format!("pool{}", i),
+                    description: "de".to_string(),
+                    msgs: None,
+                    address: Some("farming".to_string()),
+                    pool: Some(format!("pool{}", i)),
+                    reward_token: Some("reward".to_string()),
+                    votes: Votes {
+                        power: Uint128::zero(),
+                    },
+                })
+                .collect(),
+            amount: Uint128::from(1100u128),
+            voting_strategy,
+        }
+    }
+
+    /// Tallies voters that put all of their power on one option.
+    fn tally(prop: &mut MultipleChoiceProposal, votes: &[(u32, u128)]) {
+        for (option_id, power) in votes {
+            let weight = prop
+                .voting_strategy
+                .vote_weight(Uint128::from(*power));
+            prop.multiple_choice_options[*option_id as usize - 1].add_vote(weight, 100);
+            prop.total_power += weight;
+        }
+    }
+
+    fn results(prop: &MultipleChoiceProposal) -> Vec<Uint128> {
+        prop.multiple_choice_options
+            .iter()
+            .enumerate()
+            .map(|(i, choice)| prop.calculate_vote_result(i as u32 + 1, choice, None))
+            .collect()
+    }
+
+    #[test]
+    fn weighted_and_quadratic_split() {
+        let mut prop = proposal(VotingStrategy::Weighted {}, 2);
+        tally(&mut prop, &[(1, 10000), (2, 100)]);
+        assert_eq!(
+            results(&prop),
+            vec![Uint128::from(1089u128), Uint128::zero()]
+        );
+        // weighted options are rewarded with their votes
+        let msgs = prop
+            .get_execution_message(&MockStorage::new(), "1.1")
+            .unwrap();
+        assert_eq!(
+            msgs,
+            vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "farming".to_string(),
+                msg: to_binary(&loopswap::farming::ExecuteMsg::UpdateReward {
+                    pool: "pool1".to_string(),
+                    rewards: vec![("reward".to_string(), Uint128::from(10000u128))],
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
+
+        // the square root dampens the large holder: 100 against 10
+        let mut prop = proposal(VotingStrategy::Quadratic {}, 2);
+        tally(&mut prop, &[(1, 10000), (2, 100)]);
+        assert_eq!(prop.total_power, Uint128::from(110u128));
+        assert_eq!(
+            results(&prop),
+            vec![Uint128::from(990u128), Uint128::from(99u128)]
+        );
+    }
+
+    #[test]
+    fn ranked_choice_instant_runoff() {
+        let mut store = MockStorage::new();
+        let mut prop = proposal(VotingStrategy::RankedChoice {}, 3);
+        for (ranking, power) in [
+            (vec![1, 2], 40u128),
+            (vec![2, 1], 35),
+            (vec![3, 2], 20),
+            (vec![3, 2], 5),
+        ] {
+            prop.add_ranked_vote(&mut store, "1.1", &ranking, Uint128::from(power))
+                .unwrap();
+        }
+        assert_eq!(
+            RANKED_TALLIES
+                .prefix("1.1".to_string())
+                .range(&store, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()
+                .unwrap(),
+            vec![
+                ("1,2".to_string(), Uint128::from(40u128)),
+                ("2,1".to_string(), Uint128::from(35u128)),
+                ("3,2".to_string(), Uint128::from(25u128)),
+            ]
+        );
+        assert_eq!(
+            prop.multiple_choice_options[2].votes.power,
+            Uint128::from(25u128)
+        );
+
+        // nobody has a majority of first preferences, option 3 is
+        // eliminated and its ballots move to option 2
+        let winner = prop.ranked_choice_winner(&store, "1.1").unwrap();
+        assert_eq!(winner, Some(2));
+        assert_eq!(
+            prop.multiple_choice_options
+                .iter()
+                .enumerate()
+                .map(|(i, choice)| prop.calculate_vote_result(i as u32 + 1, choice, winner))
+                .collect::<Vec<_>>(),
+            vec![Uint128::zero(), Uint128::from(1100u128), Uint128::zero()]
+        );
+        let msgs = prop.get_execution_message(&store, "1.1").unwrap();
+        assert_eq!(
+            msgs,
+            vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "farming".to_string(),
+                msg: to_binary(&loopswap::farming::ExecuteMsg::UpdateReward {
+                    pool: "pool2".to_string(),
+                    rewards: vec![("reward".to_string(), Uint128::from(1100u128))],
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
+
+        prop.remove_ranked_vote(&mut store, "1.1", &[3, 2], Uint128::from(25u128))
+            .unwrap();
+        assert!(!RANKED_TALLIES.has(&store, ("1.1".to_string(), "3,2".to_string())));
+        assert_eq!(prop.ranked_choice_winner(&store, "1.1").unwrap(), Some(1));
+
+        // ballots of another voting round are not counted
+        assert_eq!(prop.ranked_choice_winner(&store, "1.2").unwrap(), None);
+    }
+
+    #[test]
+    fn invalid_options() {
+        let prop = proposal(VotingStrategy::RankedChoice {}, 3);
+        let vote = |option_id| MultipleChoiceVote {
+            option_id,
+            percentage: 0,
+        };
+        assert_eq!(
+            validate_options(&vec![vote(1), vote(1)], &prop).unwrap_err(),
+            ContractError::InvalidOptions {}
+        );
+        assert_eq!(
+            validate_options(&vec![vote(4)], &prop).unwrap_err(),
+            ContractError::InvalidOptions {}
+        );
+        assert_eq!(
+            validate_options(&vec![], &prop).unwrap_err(),
+            ContractError::InvalidOptions {}
+        );
+        validate_options(&vec![vote(3), vote(1)], &prop).unwrap();
+    }
 }
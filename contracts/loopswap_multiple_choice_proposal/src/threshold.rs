@@ -1,7 +1,9 @@
-use cosmwasm_std::{Decimal, StdError, StdResult, Uint128};
+use cosmwasm_std::{Decimal, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::error::ContractError;
+
 /// A percentage of voting power that must vote yes for a proposal to
 /// pass. An example of why this is needed:
 ///
@@ -60,12 +62,12 @@ pub enum Threshold {
 }
 
 /// Asserts that the 0.0 < percent <= 1.0
-fn validate_percentage(percent: &PercentageThreshold) -> StdResult<()> {
+fn validate_percentage(percent: &PercentageThreshold) -> Result<(), ContractError> {
     if let PercentageThreshold::Percent(percent) = percent {
         if percent.is_zero() {
-            Err(StdError::generic_err("Zero threshold"))
+            Err(ContractError::ZeroThreshold {})
         } else if *percent > Decimal::one() {
-            Err(StdError::generic_err("Unreachable threshold"))
+            Err(ContractError::UnreachableThreshold {})
         } else {
             Ok(())
         }
@@ -75,12 +77,12 @@ fn validate_percentage(percent: &PercentageThreshold) -> StdResult<()> {
 }
 
 /// Asserts that a quorum <= 1. Quorums may be zero, to enable plurality-style voting.
-pub fn validate_quorum(quorum: &PercentageThreshold) -> StdResult<()> {
+pub fn validate_quorum(quorum: &PercentageThreshold) -> Result<(), ContractError> {
     match quorum {
         PercentageThreshold::Majority {} => Ok(()),
         PercentageThreshold::Percent(quorum) => {
             if *quorum > Decimal::one() {
-                Err(StdError::generic_err("Unreachable threshold"))
+                Err(ContractError::UnreachableThreshold {})
             } else {
                 Ok(())
             }
@@ -94,7 +96,7 @@ impl Threshold {
     /// - Quorums must never be over 100%.
     /// - Passing thresholds must never be over 100%, nor be 0%.
     /// - Absolute count thresholds must be non-zero.
-    pub fn validate(&self) -> StdResult<()> {
+    pub fn validate(&self) -> Result<(), ContractError> {
         match self {
             Threshold::AbsolutePercentage {
                 percentage: percentage_needed,
@@ -105,7 +107,7 @@ impl Threshold {
             }
             Threshold::AbsoluteCount { threshold } => {
                 if threshold.is_zero() {
-                    Err(StdError::generic_err("Zero threshold"))
+                    Err(ContractError::ZeroThreshold {})
                 } else {
                     Ok(())
                 }
@@ -4,6 +4,7 @@ use cw_utils::Duration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::error::ContractError;
 use crate::threshold::PercentageThreshold;
 
 // We multiply by this when calculating needed_votes in order to round
@@ -185,17 +186,20 @@ impl std::fmt::Display for Vote {
 
 /// Validates that the min voting period is less than the max voting
 /// period. Passes arguments through the function.
-pub fn validate_voting_period(min: Duration, max: Duration) -> StdResult<(Duration, Duration)> {
+pub fn validate_voting_period(
+    min: Duration,
+    max: Duration,
+) -> Result<(Duration, Duration), ContractError> {
     let min = {
         let valid = match (min, max) {
             (Duration::Time(min), Duration::Time(max)) => min <= max,
             (Duration::Height(min), Duration::Height(max)) => min <= max,
-            _ => return Err(StdError::generic_err("Duration Minutes conflict")),
+            _ => return Err(ContractError::VotingPeriodUnitMismatch {}),
         };
         if valid {
             min
         } else {
-            return Err(StdError::generic_err("Invalid voting period"));
+            return Err(ContractError::InvalidVotingPeriod {});
         }
     };
 
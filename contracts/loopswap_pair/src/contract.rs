@@ -2,7 +2,9 @@ use crate::error::ContractError;
 use crate::response::MsgInstantiateContractResponse;
 use crate::state::{
     ExtraCommissionFee, ExtraCommissionInfo, EXTRA_COMMISSION_FEE, EXTRA_COMMISSION_INFO,
-    FACTORY_CONTRACT_ADDR, PAIR_INFO, CONFIG, Config
+    FACTORY_CONTRACT_ADDR, PAIR_INFO, CONFIG, Config, PauseInfo, PAUSE_INFO, DYNAMIC_FEE,
+    PRICE_HISTORY, PriceObservation, StatsBucket, DAILY_STATS, HOURLY_STATS, PendingDeposit,
+    PENDING_DEPOSITS, PENDING_DEPOSIT_TOTAL,
 };
 
 #[cfg(not(feature = "library"))]
@@ -10,28 +12,51 @@ use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
     from_binary, to_binary, Addr, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps, DepsMut,
-    Empty, Env, MessageInfo, Reply, ReplyOn, Response, StdError, StdResult, SubMsg, Uint128,
-    WasmMsg,
+    Empty, Env, MessageInfo, Order, Reply, ReplyOn, Response, StdError, StdResult, Storage,
+    SubMsg, Uint128, WasmMsg,
 };
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
+use cw2::set_contract_version;
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
+use cw_storage_plus::{Bound, Map};
 use integer_sqrt::IntegerSquareRoot;
 use protobuf::Message;
 use std::cmp::Ordering;
 use std::str::FromStr;
 use loopswap::asset::{Asset, AssetInfo, PairInfo, PairInfoRaw};
 use loopswap::pair::{
-    Cw20HookMsg, ExecuteMsg, ExtraCommissionFeeResponse, InstantiateMsg, MigrateMsg, PoolResponse,
-    QueryMsg, ReverseSimulationResponse, SimulationResponse,
+    Cw20HookMsg, DynamicFeeConfig, DynamicFeeResponse, ExecuteMsg, ExtraCommissionFeeResponse,
+    InstantiateMsg, MigrateMsg, PauseInfoResponse, PendingDepositResponse, PoolResponse, QueryMsg,
+    Referral, ReverseSimulationResponse, SimulationResponse, StatisticsPeriod, StatisticsResponse,
 };
 use loopswap::querier::query_token_info;
 use loopswap::token::InstantiateMsg as TokenInstantiateMsg;
 
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:loopswap-pair";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 const INSTANTIATE_REPLY_ID: u64 = 1;
 
 /// Commission rate == 0.3%
 const COMMISSION_RATE: &str = "0.003";
+/// Upper bound of the price history kept for the dynamic fee
+const MAX_PRICE_OBSERVATIONS: usize = 50;
+
+const HOUR: u64 = 3600;
+const DAY: u64 = 86400;
+const SECONDS_PER_YEAR: u64 = 365 * DAY;
+/// Buckets kept for the longest period they serve, a day of hours and a month of days
+const HOURLY_STATS_RETENTION: u64 = 24;
+const DAILY_STATS_RETENTION: u64 = 30;
+/// Seconds a pending deposit waits for its other side
+const PENDING_DEPOSIT_TIMEOUT: u64 = 3600;
+/// Expired buckets removed per swap, keeps the gas of a single swap bounded
+const STATS_PRUNE_LIMIT: usize = 5;
+/// Basis points denominator of referral commissions
+const MAX_BPS: u64 = 10_000;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut<Empty>,
@@ -39,6 +64,8 @@ pub fn instantiate(
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
     let pair_info: &PairInfoRaw = &PairInfoRaw {
         contract_addr: deps.api.addr_canonicalize(env.contract.address.as_str())?,
         liquidity_token: CanonicalAddr::from(vec![]),
@@ -70,7 +97,9 @@ pub fn instantiate(
     CONFIG.save(deps.storage, &Config{
         admin: msg.admin,
         commission_rate: COMMISSION_RATE.to_string(),
+        max_referral_bps: 0,
     })?;
+    PAUSE_INFO.save(deps.storage, &PauseInfo::default())?;
     Ok(Response::new().add_submessage(SubMsg {
         // Create LP token
         msg: WasmMsg::Instantiate {
@@ -115,6 +144,8 @@ pub fn execute(
             belief_price,
             max_spread,
             to,
+            deadline,
+            referral,
         } => {
             if !offer_asset.is_native_token() {
                 return Err(ContractError::Unauthorized {});
@@ -135,6 +166,8 @@ pub fn execute(
                 belief_price,
                 max_spread,
                 to_addr,
+                deadline,
+                referral,
             )
         }
         ExecuteMsg::WithdrawExtraCommissionFee {} => withdraw_extra_commission_fee(deps, env),
@@ -144,6 +177,18 @@ pub fn execute(
         } => update_extra_commission_info(deps, env, info, new_contract_addr, new_fee_allocation), 
         ExecuteMsg::UpdateAdmin { new_admin } => update_admin(deps, info, new_admin),
         ExecuteMsg::UpdateCommissionRate { new_rate } => update_commission_rate(deps, info, new_rate),
+        ExecuteMsg::UpdateMaxReferralBps { max_referral_bps } => {
+            update_max_referral_bps(deps, info, max_referral_bps)
+        }
+        ExecuteMsg::Pause {} => set_paused(deps, info, true),
+        ExecuteMsg::Unpause {} => set_paused(deps, info, false),
+        ExecuteMsg::UpdateDynamicFee { dynamic_fee } => {
+            update_dynamic_fee(deps, info, dynamic_fee)
+        }
+        ExecuteMsg::UpdateCircuitBreaker { max_price_impact } => {
+            update_circuit_breaker(deps, info, max_price_impact)
+        }
+        ExecuteMsg::RefundDeposit { depositor } => refund_deposit(deps, env, info, depositor),
     }
 }
 
@@ -162,6 +207,8 @@ pub fn receive_cw20(
             belief_price,
             max_spread,
             to,
+            deadline,
+            referral,
         }) => {
             // only asset contract can execute this message
             let mut authorized: bool = false;
@@ -200,6 +247,8 @@ pub fn receive_cw20(
                 belief_price,
                 max_spread,
                 to_addr,
+                deadline,
+                referral,
             )
         }
         Ok(Cw20HookMsg::WithdrawLiquidity {}) => {
@@ -211,6 +260,23 @@ pub fn receive_cw20(
             let sender_addr = deps.api.addr_validate(cw20_msg.sender.as_str())?;
             withdraw_liquidity(deps, env, info, sender_addr, cw20_msg.amount)
         }
+        Ok(Cw20HookMsg::ProvideLiquidity {
+            assets,
+            slippage_tolerance,
+            receiver,
+        }) => {
+            let sender_addr = deps.api.addr_validate(cw20_msg.sender.as_str())?;
+            receive_provide_liquidity(
+                deps,
+                env,
+                contract_addr,
+                sender_addr,
+                cw20_msg.amount,
+                assets,
+                slippage_tolerance,
+                receiver,
+            )
+        }
         Err(err) => Err(ContractError::Std(err)),
     }
 }
@@ -234,7 +300,8 @@ pub fn reply(deps: DepsMut<Empty>, _env: Env, msg: Reply) -> StdResult<Response>
     Ok(Response::new().add_attribute("liquidity_token_addr", liquidity_token))
 }
 
-/// CONTRACT - should approve contract to use the amount of token
+/// CONTRACT - should approve contract to use the amount of token,
+/// unless it was already sent with a ProvideLiquidity hook
 pub fn provide_liquidity(
     deps: DepsMut<Empty>,
     env: Env,
@@ -243,13 +310,15 @@ pub fn provide_liquidity(
     slippage_tolerance: Option<Decimal>,
     receiver: Option<String>,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.as_ref())?;
+
     for asset in assets.iter() {
         asset.assert_sent_native_token_balance(&info)?;
     }
 
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
     let mut pools: [Asset; 2] =
-        pair_info.query_pools(&deps.querier, deps.api, env.contract.address.clone())?;
+        query_pools(deps.as_ref(), &pair_info, env.contract.address.clone())?;
     let deposits: [Uint128; 2] = [
         assets
             .iter()
@@ -263,19 +332,33 @@ pub fn provide_liquidity(
             .expect("Wrong asset info is given"),
     ];
 
+    // tokens parked by a ProvideLiquidity hook complete the deposit instead of TransferFrom
+    let parked: [Uint128; 2] = match PENDING_DEPOSITS.may_load(deps.storage, &info.sender)? {
+        Some(pending) if pending.assets == assets => {
+            if pending.expires_at <= env.block.time.seconds() {
+                return Err(ContractError::DepositExpired {});
+            }
+            remove_pending_deposit(deps.storage, &info.sender, &pending)?;
+            pending.deposited
+        }
+        _ => [Uint128::zero(), Uint128::zero()],
+    };
+
     let mut messages: Vec<CosmosMsg> = vec![];
     for (i, pool) in pools.iter_mut().enumerate() {
         // If the pool is token contract, then we need to execute TransferFrom msg to receive funds
         if let AssetInfo::Token { contract_addr, .. } = &pool.info {
-            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: contract_addr.to_string(),
-                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
-                    owner: info.sender.to_string(),
-                    recipient: env.contract.address.to_string(),
-                    amount: deposits[i],
-                })?,
-                funds: vec![],
-            }));
+            if parked[i].is_zero() {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                        owner: info.sender.to_string(),
+                        recipient: env.contract.address.to_string(),
+                        amount: deposits[i],
+                    })?,
+                    funds: vec![],
+                }));
+            }
         } else {
             // If the asset is native token, balance is already increased
             // To calculated properly we should subtract user deposit from the pool
@@ -283,11 +366,144 @@ pub fn provide_liquidity(
         }
     }
 
+    let receiver = receiver.unwrap_or_else(|| info.sender.to_string());
+    let (mint_msg, share) = mint_liquidity(
+        deps.as_ref(),
+        &pair_info,
+        &deposits,
+        &pools,
+        &slippage_tolerance,
+        &receiver,
+    )?;
+    messages.push(mint_msg);
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "provide_liquidity"),
+        ("sender", info.sender.as_str()),
+        ("receiver", receiver.as_str()),
+        ("assets", &format!("{}, {}", assets[0], assets[1])),
+        ("share", &share.to_string()),
+    ]))
+}
+
+/// A CW20 side of a deposit sent with the ProvideLiquidity hook,
+/// parked until the other side arrives or completing a parked deposit
+#[allow(clippy::too_many_arguments)]
+pub fn receive_provide_liquidity(
+    deps: DepsMut<Empty>,
+    env: Env,
+    token: Addr,
+    sender: Addr,
+    amount: Uint128,
+    assets: [Asset; 2],
+    slippage_tolerance: Option<Decimal>,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.as_ref())?;
+
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let mut pools: [Asset; 2] =
+        query_pools(deps.as_ref(), &pair_info, env.contract.address.clone())?;
+
+    // only asset contract can execute this message
+    let index = pools
+        .iter()
+        .position(|pool| match &pool.info {
+            AssetInfo::Token { contract_addr } => contract_addr == token.as_str(),
+            AssetInfo::NativeToken { .. } => false,
+        })
+        .ok_or(ContractError::Unauthorized {})?;
+    let other = 1 - index;
+
+    let mut deposits = [Uint128::zero(), Uint128::zero()];
+    for (i, pool) in pools.iter().enumerate() {
+        deposits[i] = assets
+            .iter()
+            .find(|a| a.info.equal(&pool.info))
+            .map(|a| a.amount)
+            .ok_or(ContractError::AssetMismatch {})?;
+    }
+    if deposits[index] != amount {
+        return Err(ContractError::DepositMismatch {});
+    }
+    if deposits[other].is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let now = env.block.time.seconds();
+    let pending = match PENDING_DEPOSITS.may_load(deps.storage, &sender)? {
+        Some(pending) => {
+            if pending.expires_at <= now {
+                return Err(ContractError::DepositExpired {});
+            }
+            if pending.assets != assets || !pending.deposited[index].is_zero() {
+                return Err(ContractError::DepositMismatch {});
+            }
+            pending
+        }
+        None => {
+            let mut pending = PendingDeposit {
+                assets: assets.clone(),
+                deposited: [Uint128::zero(), Uint128::zero()],
+                slippage_tolerance,
+                receiver,
+                expires_at: now + PENDING_DEPOSIT_TIMEOUT,
+            };
+            pending.deposited[index] = amount;
+            PENDING_DEPOSITS.save(deps.storage, &sender, &pending)?;
+
+            let mut pending_total = PENDING_DEPOSIT_TOTAL
+                .may_load(deps.storage)?
+                .unwrap_or_default();
+            pending_total[index] = pending_total[index].checked_add(amount)?;
+            PENDING_DEPOSIT_TOTAL.save(deps.storage, &pending_total)?;
+
+            return Ok(Response::new().add_attributes(vec![
+                ("action", "pending_deposit"),
+                ("sender", sender.as_str()),
+                ("asset", &format!("{}{}", amount, token)),
+                ("expires_at", &pending.expires_at.to_string()),
+            ]));
+        }
+    };
+
+    // the other side is parked and excluded from the pools, this side was just received
+    remove_pending_deposit(deps.storage, &sender, &pending)?;
+    pools[index].amount = pools[index].amount.checked_sub(amount)?;
+
+    let receiver = pending.receiver.unwrap_or_else(|| sender.to_string());
+    let (mint_msg, share) = mint_liquidity(
+        deps.as_ref(),
+        &pair_info,
+        &deposits,
+        &pools,
+        &pending.slippage_tolerance,
+        &receiver,
+    )?;
+
+    Ok(Response::new().add_message(mint_msg).add_attributes(vec![
+        ("action", "provide_liquidity"),
+        ("sender", sender.as_str()),
+        ("receiver", receiver.as_str()),
+        ("assets", &format!("{}, {}", assets[0], assets[1])),
+        ("share", &share.to_string()),
+    ]))
+}
+
+/// Checks the slippage and builds the LP token mint for `deposits` into `pools`
+fn mint_liquidity(
+    deps: Deps<Empty>,
+    pair_info: &PairInfoRaw,
+    deposits: &[Uint128; 2],
+    pools: &[Asset; 2],
+    slippage_tolerance: &Option<Decimal>,
+    receiver: &str,
+) -> Result<(CosmosMsg, Uint128), ContractError> {
     // assert slippage tolerance
-    assert_slippage_tolerance(&slippage_tolerance, &deposits, &pools)?;
+    assert_slippage_tolerance(slippage_tolerance, deposits, pools)?;
 
     let liquidity_token = deps.api.addr_humanize(&pair_info.liquidity_token)?;
-    let total_share = query_token_info(&deps.querier, liquidity_token)?.total_supply;
+    let total_share = query_token_info(&deps.querier, liquidity_token.clone())?.total_supply;
     let share = if total_share == Uint128::zero() {
         // Initial share = collateral amount
         Uint128::from((deposits[0].u128() * deposits[1].u128()).integer_sqrt())
@@ -308,29 +524,88 @@ pub fn provide_liquidity(
         return Err(ContractError::InvalidZeroAmount {});
     }
 
-    // mint LP token to sender
-    let receiver = receiver.unwrap_or_else(|| info.sender.to_string());
-    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: deps
-            .api
-            .addr_humanize(&pair_info.liquidity_token)?
-            .to_string(),
+    // mint LP token to receiver
+    let mint_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: liquidity_token.to_string(),
         msg: to_binary(&Cw20ExecuteMsg::Mint {
             recipient: receiver.to_string(),
             amount: share,
         })?,
         funds: vec![],
-    }));
+    });
+
+    Ok((mint_msg, share))
+}
+
+// The depositor can always take a pending deposit back, anyone else once it expired
+pub fn refund_deposit(
+    deps: DepsMut<Empty>,
+    env: Env,
+    info: MessageInfo,
+    depositor: Option<String>,
+) -> Result<Response, ContractError> {
+    let depositor = match depositor {
+        Some(depositor) => deps.api.addr_validate(&depositor)?,
+        None => info.sender.clone(),
+    };
+
+    let pending = match PENDING_DEPOSITS.may_load(deps.storage, &depositor)? {
+        Some(pending) => pending,
+        None => return Err(StdError::generic_err("No pending deposit").into()),
+    };
+    if depositor != info.sender && pending.expires_at > env.block.time.seconds() {
+        return Err(ContractError::DepositNotExpired {});
+    }
+    remove_pending_deposit(deps.storage, &depositor, &pending)?;
+
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for (i, amount) in pending.deposited.iter().enumerate() {
+        if !amount.is_zero() {
+            let asset = Asset {
+                info: pair_info.asset_infos[i].to_normal(deps.api)?,
+                amount: *amount,
+            };
+            messages.push(asset.into_msg(depositor.clone())?);
+        }
+    }
 
     Ok(Response::new().add_messages(messages).add_attributes(vec![
-        ("action", "provide_liquidity"),
-        ("sender", info.sender.as_str()),
-        ("receiver", receiver.as_str()),
-        ("assets", &format!("{}, {}", assets[0], assets[1])),
-        ("share", &share.to_string()),
+        ("action", "refund_deposit"),
+        ("depositor", depositor.as_str()),
     ]))
 }
 
+fn remove_pending_deposit(
+    storage: &mut dyn Storage,
+    depositor: &Addr,
+    pending: &PendingDeposit,
+) -> StdResult<()> {
+    PENDING_DEPOSITS.remove(storage, depositor);
+
+    let mut pending_total = PENDING_DEPOSIT_TOTAL.may_load(storage)?.unwrap_or_default();
+    for (total, deposited) in pending_total.iter_mut().zip(pending.deposited.iter()) {
+        *total = total.checked_sub(*deposited)?;
+    }
+    PENDING_DEPOSIT_TOTAL.save(storage, &pending_total)
+}
+
+/// Pool balances without the tokens parked by pending deposits
+fn query_pools(
+    deps: Deps<Empty>,
+    pair_info: &PairInfoRaw,
+    contract_addr: Addr,
+) -> StdResult<[Asset; 2]> {
+    let mut pools = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
+    if let Some(pending_total) = PENDING_DEPOSIT_TOTAL.may_load(deps.storage)? {
+        for (pool, pending) in pools.iter_mut().zip(pending_total.iter()) {
+            pool.amount = pool.amount.checked_sub(*pending)?;
+        }
+    }
+
+    Ok(pools)
+}
+
 pub fn withdraw_liquidity(
     deps: DepsMut<Empty>,
     env: Env,
@@ -341,7 +616,7 @@ pub fn withdraw_liquidity(
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
     let liquidity_addr: Addr = deps.api.addr_humanize(&pair_info.liquidity_token)?;
 
-    let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, env.contract.address)?;
+    let pools: [Asset; 2] = query_pools(deps.as_ref(), &pair_info, env.contract.address)?;
     let total_share: Uint128 = query_token_info(&deps.querier, liquidity_addr)?.total_supply;
 
     let share_ratio: Decimal = Decimal::from_ratio(amount, total_share);
@@ -390,9 +665,18 @@ pub fn swap(
     belief_price: Option<Decimal>,
     max_spread: Option<Decimal>,
     to: Option<Addr>,
+    deadline: Option<u64>,
+    referral: Option<Referral>,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.as_ref())?;
+    if let Some(deadline) = deadline {
+        if env.block.time.seconds() > deadline {
+            return Err(ContractError::DeadlineExceeded {});
+        }
+    }
     offer_asset.assert_sent_native_token_balance(&info)?;
-    let config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string() });
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string(), max_referral_bps: 0 });
+    let referral = validate_referral(deps.as_ref(), &config, referral)?;
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
 
     let mut extra_commission_fee =
@@ -411,7 +695,7 @@ pub fn swap(
             25u128
         };
 
-    let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, env.contract.address)?;
+    let pools: [Asset; 2] = query_pools(deps.as_ref(), &pair_info, env.contract.address)?;
 
     let offer_pool: Asset;
     let ask_pool: Asset;
@@ -443,12 +727,19 @@ pub fn swap(
     }
 
     let offer_amount = offer_asset.amount;
+    let commission_rate = current_commission_rate(deps.storage, &config, env.block.time.seconds())?;
     let (return_amount, spread_amount, commission_amount) =
-        compute_swap(offer_pool.amount, ask_pool.amount, offer_amount, config.commission_rate);
-
+        compute_swap(offer_pool.amount, ask_pool.amount, offer_amount, commission_rate.to_string());
+
+    // the referrer is paid out of the return, so the spread limit applies
+    // to what is left for the receiver
+    let referral_amount = referral
+        .as_ref()
+        .map(|(_, commission_bps)| compute_referral_amount(return_amount, *commission_bps))
+        .unwrap_or_default();
     let return_asset = Asset {
         info: ask_pool.info.clone(),
-        amount: return_amount,
+        amount: return_amount.checked_sub(referral_amount)?,
     };
 
     // check max spread limit if exist
@@ -457,10 +748,21 @@ pub fn swap(
         max_spread,
         offer_asset.clone(),
         return_asset.clone(),
-        spread_amount,
+        spread_amount.checked_add(referral_amount)?,
         offer_decimal,
         ask_decimal,
     )?;
+
+    // reject swaps that move the price further than the circuit breaker allows
+    let pause_info = PAUSE_INFO.may_load(deps.storage)?.unwrap_or_default();
+    if let Some(max_price_impact) = pause_info.max_price_impact {
+        let price_impact =
+            compute_price_impact(offer_pool.amount, ask_pool.amount, offer_amount, return_amount);
+        if price_impact > max_price_impact.into() {
+            return Err(ContractError::MaxPriceImpactExceeded {});
+        }
+    }
+
     if ask_pool.eq(&pools[0]) {
         extra_commission_fee.amount0 = extra_commission_fee.amount0.checked_add(
             commission_amount.multiply_ratio(extra_commission_fee_allocation, 100u128),
@@ -472,17 +774,67 @@ pub fn swap(
     }
     EXTRA_COMMISSION_FEE.save(deps.storage, &extra_commission_fee)?;
 
+    let offer_index = if offer_asset.info.equal(&pools[0].info) { 0 } else { 1 };
+    record_swap_stats(
+        deps.storage,
+        env.block.time.seconds(),
+        offer_index,
+        offer_amount,
+        commission_amount,
+    )?;
+
+    if let Some(dynamic_fee) = DYNAMIC_FEE.may_load(deps.storage)? {
+        // price of asset 0 in asset 1 once the swap is settled
+        let (pool0, pool1) = if ask_pool.eq(&pools[0]) {
+            (ask_pool.amount - return_amount, offer_pool.amount + offer_amount)
+        } else {
+            (offer_pool.amount + offer_amount, ask_pool.amount - return_amount)
+        };
+        if !pool0.is_zero() {
+            record_price(
+                deps.storage,
+                &dynamic_fee,
+                env.block.time.seconds(),
+                Decimal::from_ratio(pool1, pool0),
+            )?;
+        }
+    }
+
     //let tax_amount = return_asset.compute_tax(&deps.querier)?;
 
     let receiver = to.unwrap_or_else(|| sender.clone());
 
+    let return_amount = return_asset.amount;
+
     let mut messages: Vec<CosmosMsg> = vec![];
     if !return_amount.is_zero() {
-        messages.push(return_asset.into_msg(receiver.clone())?);
+        messages.push(
+            Asset {
+                info: return_asset.info.clone(),
+                amount: return_amount,
+            }
+            .into_msg(receiver.clone())?,
+        );
+    }
+
+    let mut referral_attrs: Vec<(&str, String)> = vec![];
+    if let Some((referral_addr, _)) = referral {
+        if !referral_amount.is_zero() {
+            messages.push(
+                Asset {
+                    info: return_asset.info,
+                    amount: referral_amount,
+                }
+                .into_msg(referral_addr.clone())?,
+            );
+        }
+        referral_attrs.push(("referral_address", referral_addr.to_string()));
+        referral_attrs.push(("referral_amount", referral_amount.to_string()));
     }
 
     // 1. send collateral token from the contract to a user
     // 2. send inactive commission to collector
+    // 3. send referral commission to the referrer
     Ok(Response::new().add_messages(messages).add_attributes(vec![
         ("action", "swap"),
         ("sender", sender.as_str()),
@@ -494,7 +846,8 @@ pub fn swap(
         // ("tax_amount", &tax_amount.to_string()),
         ("spread_amount", &spread_amount.to_string()),
         ("commission_amount", &commission_amount.to_string()),
-    ]))
+        ("commission_rate", &commission_rate.to_string()),
+    ]).add_attributes(referral_attrs))
 }
 
 pub fn withdraw_extra_commission_fee(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
@@ -549,7 +902,7 @@ pub fn update_extra_commission_info(
     new_contract_addr: Option<String>,
     new_fee_allocation: Option<Uint128>,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string() });
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string(), max_referral_bps: 0 });
     let mut extra_commission_info = EXTRA_COMMISSION_INFO.load(deps.storage)?;
 
     // permission check
@@ -571,7 +924,7 @@ pub fn update_extra_commission_info(
 
 pub fn update_admin(deps: DepsMut, info : MessageInfo, new_admin: String)-> Result<Response, ContractError> {
 
-    let mut config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string() });
+    let mut config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string(), max_referral_bps: 0 });
 
     if config.admin != info.sender {
         return Err(StdError::generic_err("unauthorized").into());
@@ -581,9 +934,26 @@ pub fn update_admin(deps: DepsMut, info : MessageInfo, new_admin: String)-> Resu
     Ok(Response::new().add_attribute("action", "admin updated"))
 }
 
+pub fn update_max_referral_bps(deps: DepsMut, info: MessageInfo, max_referral_bps: u64) -> Result<Response, ContractError> {
+    let mut config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string(), max_referral_bps: 0 });
+
+    if config.admin != info.sender {
+        return Err(StdError::generic_err("unauthorized").into());
+    }
+    if max_referral_bps > MAX_BPS {
+        return Err(ContractError::ReferralCommissionExceeded {});
+    }
+    config.max_referral_bps = max_referral_bps;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_max_referral_bps"),
+        ("max_referral_bps", &max_referral_bps.to_string()),
+    ]))
+}
+
 pub fn update_commission_rate(deps: DepsMut, info : MessageInfo, new_rate: String)-> Result<Response, ContractError> {
 
-    let mut config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string() });
+    let mut config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string(), max_referral_bps: 0 });
 
     if config.admin != info.sender {
         return Err(StdError::generic_err("unauthorized").into());
@@ -593,20 +963,246 @@ pub fn update_commission_rate(deps: DepsMut, info : MessageInfo, new_rate: Strin
     Ok(Response::new().add_attribute("action", "admin updated"))
 }
 
+// Admin or factory can pause the pair, withdrawals stay open while paused
+pub fn set_paused(deps: DepsMut, info: MessageInfo, paused: bool) -> Result<Response, ContractError> {
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string(), max_referral_bps: 0 });
+    let factory_addr = FACTORY_CONTRACT_ADDR.load(deps.storage)?;
+
+    if config.admin != info.sender && factory_addr != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut pause_info = PAUSE_INFO.may_load(deps.storage)?.unwrap_or_default();
+    pause_info.paused = paused;
+    PAUSE_INFO.save(deps.storage, &pause_info)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", if paused { "pause" } else { "unpause" }),
+        ("sender", info.sender.as_str()),
+    ]))
+}
+
+pub fn update_circuit_breaker(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_price_impact: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string(), max_referral_bps: 0 });
+
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(max_price_impact) = max_price_impact {
+        if max_price_impact.is_zero() || max_price_impact > Decimal::one() {
+            return Err(StdError::generic_err("max_price_impact must be between 0 and 1").into());
+        }
+    }
+
+    let mut pause_info = PAUSE_INFO.may_load(deps.storage)?.unwrap_or_default();
+    pause_info.max_price_impact = max_price_impact;
+    PAUSE_INFO.save(deps.storage, &pause_info)?;
+
+    Ok(Response::new().add_attribute("action", "update_circuit_breaker"))
+}
+
+pub fn update_dynamic_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    dynamic_fee: Option<DynamicFeeConfig>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string(), max_referral_bps: 0 });
+
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match dynamic_fee {
+        Some(dynamic_fee) => {
+            if dynamic_fee.min_fee > dynamic_fee.max_fee
+                || dynamic_fee.max_fee >= Decimal::one()
+                || dynamic_fee.window == 0
+            {
+                return Err(StdError::generic_err(
+                    "min_fee must not exceed max_fee, max_fee must be below 1 and window above 0",
+                )
+                .into());
+            }
+            DYNAMIC_FEE.save(deps.storage, &dynamic_fee)?;
+        }
+        None => {
+            DYNAMIC_FEE.remove(deps.storage);
+            PRICE_HISTORY.remove(deps.storage);
+        }
+    }
+
+    Ok(Response::new().add_attribute("action", "update_dynamic_fee"))
+}
+
+/// Commission rate a swap pays at `time`, the fixed rate unless the dynamic fee is enabled
+fn current_commission_rate(
+    storage: &dyn Storage,
+    config: &Config,
+    time: u64,
+) -> StdResult<Decimal> {
+    match DYNAMIC_FEE.may_load(storage)? {
+        Some(dynamic_fee) => {
+            let history = PRICE_HISTORY.may_load(storage)?.unwrap_or_default();
+            let volatility = compute_volatility(&history, time.saturating_sub(dynamic_fee.window));
+            Ok(compute_dynamic_fee(&dynamic_fee, volatility))
+        }
+        None => Decimal::from_str(&config.commission_rate),
+    }
+}
+
+fn record_price(
+    storage: &mut dyn Storage,
+    dynamic_fee: &DynamicFeeConfig,
+    time: u64,
+    price: Decimal,
+) -> StdResult<()> {
+    let since = time.saturating_sub(dynamic_fee.window);
+    let mut history: Vec<PriceObservation> = PRICE_HISTORY
+        .may_load(storage)?
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|observation| observation.time >= since)
+        .collect();
+
+    history.push(PriceObservation { time, price });
+    if history.len() > MAX_PRICE_OBSERVATIONS {
+        history.drain(..history.len() - MAX_PRICE_OBSERVATIONS);
+    }
+
+    PRICE_HISTORY.save(storage, &history)
+}
+
+/// Realized volatility, the root mean square of the relative price
+/// changes between consecutive swaps observed since `since`
+fn compute_volatility(history: &[PriceObservation], since: u64) -> Decimal {
+    let prices: Vec<Decimal> = history
+        .iter()
+        .filter(|observation| observation.time >= since)
+        .map(|observation| observation.price)
+        .collect();
+
+    let mut sum_of_squares = Decimal::zero();
+    let mut count = 0u128;
+    for window in prices.windows(2) {
+        let (prev, next) = (window[0], window[1]);
+        if prev.is_zero() {
+            continue;
+        }
+
+        let diff = if next > prev { next - prev } else { prev - next };
+        let change = diff / prev;
+        sum_of_squares += change * change;
+        count += 1;
+    }
+
+    if count == 0 {
+        return Decimal::zero();
+    }
+
+    (sum_of_squares / Uint128::from(count)).sqrt()
+}
+
+fn compute_dynamic_fee(dynamic_fee: &DynamicFeeConfig, volatility: Decimal) -> Decimal {
+    let fee = dynamic_fee.min_fee + volatility * dynamic_fee.volatility_multiplier;
+    fee.min(dynamic_fee.max_fee)
+}
+
+/// Adds the swap to the current hourly and daily buckets and drops expired ones
+fn record_swap_stats(
+    storage: &mut dyn Storage,
+    time: u64,
+    offer_index: usize,
+    offer_amount: Uint128,
+    commission_amount: Uint128,
+) -> StdResult<()> {
+    let stats: [(Map<u64, StatsBucket>, u64, u64); 2] = [
+        (HOURLY_STATS, HOUR, HOURLY_STATS_RETENTION),
+        (DAILY_STATS, DAY, DAILY_STATS_RETENTION),
+    ];
+
+    for (bucket_map, bucket_size, retention) in stats.iter() {
+        let bucket_start = time - time % bucket_size;
+        let mut bucket = bucket_map
+            .may_load(storage, bucket_start)?
+            .unwrap_or_default();
+        bucket.volume[offer_index] = bucket.volume[offer_index].checked_add(offer_amount)?;
+        // commission is taken from the ask asset
+        bucket.fees[1 - offer_index] = bucket.fees[1 - offer_index].checked_add(commission_amount)?;
+        bucket.swap_count += 1;
+        bucket_map.save(storage, bucket_start, &bucket)?;
+
+        let expire_before = bucket_start.saturating_sub(bucket_size * retention);
+        let expired = bucket_map
+            .keys(storage, None, Some(Bound::exclusive(expire_before)), Order::Ascending)
+            .take(STATS_PRUNE_LIMIT)
+            .collect::<StdResult<Vec<u64>>>()?;
+        for key in expired {
+            bucket_map.remove(storage, key);
+        }
+    }
+
+    Ok(())
+}
+
+// Referral is only accepted up to the cap set by the admin
+fn validate_referral(
+    deps: Deps,
+    config: &Config,
+    referral: Option<Referral>,
+) -> Result<Option<(Addr, u64)>, ContractError> {
+    match referral {
+        None => Ok(None),
+        Some(referral) => {
+            if referral.commission_bps > config.max_referral_bps {
+                return Err(ContractError::ReferralCommissionExceeded {});
+            }
+            let address = deps.api.addr_validate(&referral.address)?;
+            Ok(Some((address, referral.commission_bps)))
+        }
+    }
+}
+
+fn compute_referral_amount(return_amount: Uint128, commission_bps: u64) -> Uint128 {
+    return_amount.multiply_ratio(commission_bps, MAX_BPS)
+}
+
+fn assert_not_paused(deps: Deps) -> Result<(), ContractError> {
+    let pause_info = PAUSE_INFO.may_load(deps.storage)?.unwrap_or_default();
+    if pause_info.paused {
+        return Err(ContractError::Paused {});
+    }
+
+    Ok(())
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps<Empty>, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps<Empty>, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::Pair {} => Ok(to_binary(&query_pair_info(deps)?)?),
         QueryMsg::Pool {} => Ok(to_binary(&query_pool(deps)?)?),
-        QueryMsg::Simulation { offer_asset } => {
-            Ok(to_binary(&query_simulation(deps, offer_asset)?)?)
-        }
+        QueryMsg::Simulation {
+            offer_asset,
+            referral,
+        } => Ok(to_binary(&query_simulation(deps, env, offer_asset, referral)?)?),
         QueryMsg::ReverseSimulation { ask_asset } => {
-            Ok(to_binary(&query_reverse_simulation(deps, ask_asset)?)?)
+            Ok(to_binary(&query_reverse_simulation(deps, env, ask_asset)?)?)
         }
         QueryMsg::ExtraCommissionFee {} => Ok(to_binary(&query_extra_commission_fee(deps)?)?),
         QueryMsg::ExtraCommissionInfo{} => Ok(to_binary(&query_extra_commission_info(deps)?)?),
         QueryMsg::QueryConfig{} => Ok(to_binary(&query_config(deps)?)?),
+        QueryMsg::PauseInfo {} => Ok(to_binary(&query_pause_info(deps)?)?),
+        QueryMsg::DynamicFee {} => Ok(to_binary(&query_dynamic_fee(deps, env)?)?),
+        QueryMsg::Statistics { period } => {
+            Ok(to_binary(&query_statistics(deps, env, period)?)?)
+        }
+        QueryMsg::PendingDeposit { address } => {
+            Ok(to_binary(&query_pending_deposit(deps, address)?)?)
+        }
     }
 }
 
@@ -620,7 +1216,7 @@ pub fn query_pair_info(deps: Deps<Empty>) -> Result<PairInfo, ContractError> {
 pub fn query_pool(deps: Deps<Empty>) -> Result<PoolResponse, ContractError> {
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
     let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
-    let assets: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
+    let assets: [Asset; 2] = query_pools(deps, &pair_info, contract_addr)?;
     let total_share: Uint128 = query_token_info(
         &deps.querier,
         deps.api.addr_humanize(&pair_info.liquidity_token)?,
@@ -657,18 +1253,127 @@ pub fn query_extra_commission_fee(deps: Deps) -> Result<ExtraCommissionFeeRespon
 }
 
 
+pub fn query_statistics(
+    deps: Deps<Empty>,
+    env: Env,
+    period: StatisticsPeriod,
+) -> Result<StatisticsResponse, ContractError> {
+    let (bucket_map, bucket_size, bucket_count) = match period {
+        StatisticsPeriod::Hour => (HOURLY_STATS, HOUR, 1),
+        StatisticsPeriod::Day => (HOURLY_STATS, HOUR, 24),
+        StatisticsPeriod::Week => (DAILY_STATS, DAY, 7),
+        StatisticsPeriod::Month => (DAILY_STATS, DAY, 30),
+    };
+
+    // the current bucket and the ones before it
+    let time = env.block.time.seconds();
+    let window_start = (time - time % bucket_size).saturating_sub(bucket_size * (bucket_count - 1));
+    let mut total = StatsBucket::default();
+    for item in bucket_map.range(
+        deps.storage,
+        Some(Bound::inclusive(window_start)),
+        None,
+        Order::Ascending,
+    ) {
+        let (_, bucket) = item?;
+        for i in 0..2 {
+            total.volume[i] = total.volume[i].checked_add(bucket.volume[i])?;
+            total.fees[i] = total.fees[i].checked_add(bucket.fees[i])?;
+        }
+        total.swap_count += bucket.swap_count;
+    }
+
+    let pools = query_pool(deps)?.assets;
+    let fee_allocation = match EXTRA_COMMISSION_INFO.may_load(deps.storage)? {
+        Some(extra_commission_info) => extra_commission_info.fee_allocation.u128(),
+        None => 25u128,
+    };
+
+    // fees left to LPs valued in asset 0, over the pool value in asset 0
+    let apr = if pools[0].amount.is_zero() || pools[1].amount.is_zero() {
+        Decimal::zero()
+    } else {
+        let lp_share = 100u128.saturating_sub(fee_allocation);
+        let lp_fees = total.fees[0].multiply_ratio(lp_share, 100u128)
+            + total.fees[1]
+                .multiply_ratio(lp_share, 100u128)
+                .multiply_ratio(pools[0].amount, pools[1].amount);
+        let pool_value = pools[0].amount.checked_mul(Uint128::from(2u128))?;
+
+        Decimal::from_ratio(lp_fees, pool_value)
+            * Decimal::from_ratio(SECONDS_PER_YEAR, bucket_size * bucket_count)
+    };
+
+    Ok(StatisticsResponse {
+        period,
+        volume: [
+            Asset {
+                info: pools[0].info.clone(),
+                amount: total.volume[0],
+            },
+            Asset {
+                info: pools[1].info.clone(),
+                amount: total.volume[1],
+            },
+        ],
+        fees: [
+            Asset {
+                info: pools[0].info.clone(),
+                amount: total.fees[0],
+            },
+            Asset {
+                info: pools[1].info.clone(),
+                amount: total.fees[1],
+            },
+        ],
+        swap_count: total.swap_count,
+        apr,
+    })
+}
+
+pub fn query_pending_deposit(
+    deps: Deps<Empty>,
+    address: String,
+) -> StdResult<Option<PendingDepositResponse>> {
+    let address = deps.api.addr_validate(&address)?;
+    let pending = match PENDING_DEPOSITS.may_load(deps.storage, &address)? {
+        Some(pending) => pending,
+        None => return Ok(None),
+    };
+
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    Ok(Some(PendingDepositResponse {
+        assets: pending.assets,
+        deposited: [
+            Asset {
+                info: pair_info.asset_infos[0].to_normal(deps.api)?,
+                amount: pending.deposited[0],
+            },
+            Asset {
+                info: pair_info.asset_infos[1].to_normal(deps.api)?,
+                amount: pending.deposited[1],
+            },
+        ],
+        slippage_tolerance: pending.slippage_tolerance,
+        receiver: pending.receiver,
+        expires_at: pending.expires_at,
+    }))
+}
+
 pub fn query_extra_commission_info(deps: Deps) -> StdResult<ExtraCommissionInfo> {
     EXTRA_COMMISSION_INFO.load(deps.storage)
 }
 
 pub fn query_simulation(
     deps: Deps<Empty>,
+    env: Env,
     offer_asset: Asset,
+    referral: Option<Referral>,
 ) -> Result<SimulationResponse, ContractError> {
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
-    let config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string() });
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string(), max_referral_bps: 0 });
     let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
-    let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
+    let pools: [Asset; 2] = query_pools(deps, &pair_info, contract_addr)?;
 
     let offer_pool: Asset;
     let ask_pool: Asset;
@@ -682,13 +1387,19 @@ pub fn query_simulation(
         return Err(ContractError::AssetMismatch {});
     }
 
+    let commission_rate = current_commission_rate(deps.storage, &config, env.block.time.seconds())?;
     let (return_amount, spread_amount, commission_amount) =
-        compute_swap(offer_pool.amount, ask_pool.amount, offer_asset.amount, config.commission_rate);
+        compute_swap(offer_pool.amount, ask_pool.amount, offer_asset.amount, commission_rate.to_string());
+    let referral_amount = validate_referral(deps, &config, referral)?
+        .map(|(_, commission_bps)| compute_referral_amount(return_amount, commission_bps))
+        .unwrap_or_default();
 
     Ok(SimulationResponse {
-        return_amount,
+        return_amount: return_amount.checked_sub(referral_amount)?,
         spread_amount,
         commission_amount,
+        commission_rate,
+        referral_amount,
     })
 }
 
@@ -696,15 +1407,46 @@ pub fn query_config(deps: Deps) -> StdResult<Config> {
     CONFIG.load(deps.storage)
 }
 
+pub fn query_pause_info(deps: Deps) -> StdResult<PauseInfoResponse> {
+    let pause_info = PAUSE_INFO.may_load(deps.storage)?.unwrap_or_default();
+
+    Ok(PauseInfoResponse {
+        paused: pause_info.paused,
+        max_price_impact: pause_info.max_price_impact,
+    })
+}
+
+pub fn query_dynamic_fee(deps: Deps, env: Env) -> StdResult<DynamicFeeResponse> {
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string(), max_referral_bps: 0 });
+    let dynamic_fee = DYNAMIC_FEE.may_load(deps.storage)?;
+    let volatility = match &dynamic_fee {
+        Some(dynamic_fee) => {
+            let history = PRICE_HISTORY.may_load(deps.storage)?.unwrap_or_default();
+            compute_volatility(
+                &history,
+                env.block.time.seconds().saturating_sub(dynamic_fee.window),
+            )
+        }
+        None => Decimal::zero(),
+    };
+
+    Ok(DynamicFeeResponse {
+        dynamic_fee,
+        volatility,
+        commission_rate: current_commission_rate(deps.storage, &config, env.block.time.seconds())?,
+    })
+}
+
 pub fn query_reverse_simulation(
     deps: Deps<Empty>,
+    env: Env,
     ask_asset: Asset,
 ) -> Result<ReverseSimulationResponse, ContractError> {
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
     
     let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
-    let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
-    let config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string() });
+    let pools: [Asset; 2] = query_pools(deps, &pair_info, contract_addr)?;
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or(Config { admin: "".to_string(), commission_rate: COMMISSION_RATE.to_string(), max_referral_bps: 0 });
     let offer_pool: Asset;
     let ask_pool: Asset;
     if ask_asset.info.equal(&pools[0].info) {
@@ -717,13 +1459,15 @@ pub fn query_reverse_simulation(
         return Err(ContractError::AssetMismatch {});
     }
 
+    let commission_rate = current_commission_rate(deps.storage, &config, env.block.time.seconds())?;
     let (offer_amount, spread_amount, commission_amount) =
-        compute_offer_amount(offer_pool.amount, ask_pool.amount, ask_asset.amount, config.commission_rate);
+        compute_offer_amount(offer_pool.amount, ask_pool.amount, ask_asset.amount, commission_rate.to_string());
 
     Ok(ReverseSimulationResponse {
         offer_amount,
         spread_amount,
         commission_amount,
+        commission_rate,
     })
 }
 
@@ -822,6 +1566,30 @@ fn compute_offer_amount(
     )
 }
 
+/// Relative drop of the ask price caused by a swap
+/// 1 - ((ask_pool - return_amount) / (offer_pool + offer_amount)) / (ask_pool / offer_pool)
+fn compute_price_impact(
+    offer_pool: Uint128,
+    ask_pool: Uint128,
+    offer_amount: Uint128,
+    return_amount: Uint128,
+) -> Decimal256 {
+    let offer_pool: Uint256 = offer_pool.into();
+    let ask_pool: Uint256 = ask_pool.into();
+    let offer_amount: Uint256 = offer_amount.into();
+    let return_amount: Uint256 = return_amount.into();
+
+    if ask_pool.is_zero() || offer_pool.is_zero() {
+        return Decimal256::zero();
+    }
+
+    let price_ratio = Decimal256::from_ratio(
+        (ask_pool - return_amount) * offer_pool,
+        ask_pool * (offer_pool + offer_amount),
+    );
+    Decimal256::one() - price_ratio
+}
+
 /// If `belief_price` and `max_spread` both are given,
 /// we compute new spread else we just use terraswap
 /// spread to check `max_spread`
@@ -925,6 +1693,15 @@ fn assert_slippage_tolerance(
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
-    CONFIG.save(deps.storage, &Config { admin: msg.new_admin, commission_rate: msg.new_commission_rate })?;
+    let max_referral_bps = CONFIG
+        .may_load(deps.storage)?
+        .map(|config| config.max_referral_bps)
+        .unwrap_or_default();
+    CONFIG.save(deps.storage, &Config {
+        admin: msg.new_admin,
+        commission_rate: msg.new_commission_rate,
+        max_referral_bps,
+    })?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     Ok(Response::default())
 }
@@ -26,4 +26,25 @@ pub enum ContractError {
 
     #[error("Too small offer amount")]
     TooSmallOfferAmount {},
+
+    #[error("Pair is paused")]
+    Paused {},
+
+    #[error("Price impact exceeds the circuit breaker limit")]
+    MaxPriceImpactExceeded {},
+
+    #[error("Deposit does not match the pending deposit")]
+    DepositMismatch {},
+
+    #[error("Pending deposit expired")]
+    DepositExpired {},
+
+    #[error("Pending deposit has not expired")]
+    DepositNotExpired {},
+
+    #[error("Swap deadline exceeded")]
+    DeadlineExceeded {},
+
+    #[error("Referral commission exceeds the maximum")]
+    ReferralCommissionExceeded {},
 }
@@ -1,6 +1,7 @@
-use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::Item;
-use loopswap::asset::PairInfoRaw;
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw_storage_plus::{Item, Map};
+use loopswap::asset::{Asset, PairInfoRaw};
+use loopswap::pair::DynamicFeeConfig;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -9,6 +10,15 @@ pub const FACTORY_CONTRACT_ADDR: Item<Addr> = Item::new("factory_contract_addr")
 pub const EXTRA_COMMISSION_INFO: Item<ExtraCommissionInfo> = Item::new("extra_commission_info");
 pub const EXTRA_COMMISSION_FEE: Item<ExtraCommissionFee> = Item::new("extra_commission_fee");
 pub const CONFIG: Item<Config> = Item::new("Config");
+pub const PAUSE_INFO: Item<PauseInfo> = Item::new("pause_info");
+pub const DYNAMIC_FEE: Item<DynamicFeeConfig> = Item::new("dynamic_fee");
+pub const PRICE_HISTORY: Item<Vec<PriceObservation>> = Item::new("price_history");
+/// Swap statistics keyed by the start time of the bucket
+pub const HOURLY_STATS: Map<u64, StatsBucket> = Map::new("hourly_stats");
+pub const DAILY_STATS: Map<u64, StatsBucket> = Map::new("daily_stats");
+pub const PENDING_DEPOSITS: Map<&Addr, PendingDeposit> = Map::new("pending_deposits");
+/// Tokens parked by all pending deposits, they are not part of the pool
+pub const PENDING_DEPOSIT_TOTAL: Item<[Uint128; 2]> = Item::new("pending_deposit_total");
 #[derive(Serialize, Deserialize, Clone, Debug,  PartialEq, Eq, JsonSchema)]
 pub struct ExtraCommissionFee {
     pub amount0: Uint128,
@@ -25,4 +35,40 @@ pub struct ExtraCommissionInfo {
 pub struct Config {
     pub admin: String,
     pub commission_rate: String,
+    /// Highest referral commission a swap may request, in basis points
+    #[serde(default)]
+    pub max_referral_bps: u64,
+}
+
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PauseInfo {
+    /// Swaps and deposits are blocked while paused, withdrawals are always allowed
+    pub paused: bool,
+    /// Circuit breaker, a single swap moving the price more than this is rejected
+    pub max_price_impact: Option<Decimal>,
+}
+
+/// Price of asset 0 in asset 1 right after a swap
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PriceObservation {
+    pub time: u64,
+    pub price: Decimal,
+}
+
+/// Amounts are indexed like the pair asset_infos
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct StatsBucket {
+    pub volume: [Uint128; 2],
+    pub fees: [Uint128; 2],
+    pub swap_count: u64,
+}
+
+/// One side of a liquidity deposit waiting for the other, amounts are indexed like the pair asset_infos
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PendingDeposit {
+    pub assets: [Asset; 2],
+    pub deposited: [Uint128; 2],
+    pub slippage_tolerance: Option<Decimal>,
+    pub receiver: Option<String>,
+    pub expires_at: u64,
 }
@@ -1,9 +1,11 @@
 use crate::contract::{
-    assert_max_spread, execute, instantiate, query_pair_info, query_pool, query_reverse_simulation,
-    query_simulation, reply,
+    assert_max_spread, execute, instantiate, query_dynamic_fee, query_pair_info, query_pause_info,
+    query_pending_deposit, query_pool, query_reverse_simulation, query_simulation,
+    query_statistics, reply,
 };
 use crate::error::ContractError;
-use terraswap::mock_querier::mock_dependencies;
+use crate::state::{PriceObservation, PRICE_HISTORY};
+use loopswap::mock_querier::mock_dependencies;
 
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
@@ -11,12 +13,13 @@ use cosmwasm_std::{
     SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
-use terraswap::asset::{Asset, AssetInfo, PairInfo};
-use terraswap::pair::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolResponse, ReverseSimulationResponse,
-    SimulationResponse,
+use loopswap::asset::{Asset, AssetInfo, PairInfo};
+use loopswap::pair::{
+    Cw20HookMsg, DynamicFeeConfig, ExecuteMsg, InstantiateMsg, PoolResponse, Referral,
+    ReverseSimulationResponse, SimulationResponse, StatisticsPeriod,
 };
-use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
+use loopswap::token::InstantiateMsg as TokenInstantiateMsg;
+use std::str::FromStr;
 
 #[test]
 fn proper_initialization() {
@@ -33,6 +36,9 @@ fn proper_initialization() {
         ],
         token_code_id: 10u64,
         asset_decimals: [6u8, 8u8],
+        is_stable_pair: false,
+        admin: "admin0000".to_string(),
+        extra_commission_contract_addr: "extra0000".to_string(),
     };
 
     // we can just call .unwrap() to assert this was a success
@@ -45,7 +51,7 @@ fn proper_initialization() {
             msg: WasmMsg::Instantiate {
                 code_id: 10u64,
                 msg: to_binary(&TokenInstantiateMsg {
-                    name: "terraswap liquidity token".to_string(),
+                    name: "loopswap liquidity token".to_string(),
                     symbol: "uLP".to_string(),
                     decimals: 6,
                     initial_balances: vec![],
@@ -124,6 +130,9 @@ fn provide_liquidity() {
         ],
         token_code_id: 10u64,
         asset_decimals: [6u8, 8u8],
+        is_stable_pair: false,
+        admin: "admin0000".to_string(),
+        extra_commission_contract_addr: "extra0000".to_string(),
     };
 
     let env = mock_env();
@@ -531,6 +540,9 @@ fn withdraw_liquidity() {
         ],
         token_code_id: 10u64,
         asset_decimals: [6u8, 8u8],
+        is_stable_pair: false,
+        admin: "admin0000".to_string(),
+        extra_commission_contract_addr: "extra0000".to_string(),
     };
 
     let env = mock_env();
@@ -648,6 +660,9 @@ fn try_native_to_token() {
         ],
         token_code_id: 10u64,
         asset_decimals: [6u8, 8u8],
+        is_stable_pair: false,
+        admin: "admin0000".to_string(),
+        extra_commission_contract_addr: "extra0000".to_string(),
     };
 
     let env = mock_env();
@@ -682,6 +697,8 @@ fn try_native_to_token() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
+        referral: None,
     };
     let env = mock_env();
     let info = mock_info(
@@ -715,12 +732,14 @@ fn try_native_to_token() {
 
     let simulation_res: SimulationResponse = query_simulation(
         deps.as_ref(),
+        mock_env(),
         Asset {
             info: AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
             amount: offer_amount,
         },
+        None,
     )
     .unwrap();
     assert_eq!(expected_return_amount, simulation_res.return_amount);
@@ -730,6 +749,7 @@ fn try_native_to_token() {
     // check reverse simulation res
     let reverse_simulation_res: ReverseSimulationResponse = query_reverse_simulation(
         deps.as_ref(),
+        mock_env(),
         Asset {
             info: AssetInfo::Token {
                 contract_addr: "asset0000".to_string(),
@@ -768,6 +788,7 @@ fn try_native_to_token() {
             attr("return_amount", expected_return_amount.to_string()),
             attr("spread_amount", expected_spread_amount.to_string()),
             attr("commission_amount", expected_commission_amount.to_string()),
+            attr("commission_rate", "0.003"),
         ]
     );
 
@@ -822,6 +843,9 @@ fn try_token_to_native() {
         ],
         token_code_id: 10u64,
         asset_decimals: [8u8, 8u8],
+        is_stable_pair: false,
+        admin: "admin0000".to_string(),
+        extra_commission_contract_addr: "extra0000".to_string(),
     };
 
     let env = mock_env();
@@ -856,6 +880,8 @@ fn try_token_to_native() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
+        referral: None,
     };
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
@@ -873,6 +899,8 @@ fn try_token_to_native() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
+            referral: None,
         })
         .unwrap(),
     });
@@ -907,12 +935,14 @@ fn try_token_to_native() {
 
     let simulation_res: SimulationResponse = query_simulation(
         deps.as_ref(),
+        mock_env(),
         Asset {
             amount: offer_amount,
             info: AssetInfo::Token {
                 contract_addr: "asset0000".to_string(),
             },
         },
+        None,
     )
     .unwrap();
     assert_eq!(expected_return_amount, simulation_res.return_amount);
@@ -922,6 +952,7 @@ fn try_token_to_native() {
     // check reverse simulation res
     let reverse_simulation_res: ReverseSimulationResponse = query_reverse_simulation(
         deps.as_ref(),
+        mock_env(),
         Asset {
             amount: expected_return_amount,
             info: AssetInfo::NativeToken {
@@ -959,6 +990,7 @@ fn try_token_to_native() {
             attr("return_amount", expected_return_amount.to_string()),
             attr("spread_amount", expected_spread_amount.to_string()),
             attr("commission_amount", expected_commission_amount.to_string()),
+            attr("commission_rate", "0.003"),
         ]
     );
 
@@ -981,6 +1013,8 @@ fn try_token_to_native() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
+            referral: None,
         })
         .unwrap(),
     });
@@ -1198,6 +1232,9 @@ fn test_query_pool() {
         ],
         token_code_id: 10u64,
         asset_decimals: [6u8, 8u8],
+        is_stable_pair: false,
+        admin: "admin0000".to_string(),
+        extra_commission_contract_addr: "extra0000".to_string(),
     };
 
     let env = mock_env();
@@ -1242,3 +1279,821 @@ fn test_query_pool() {
     );
     assert_eq!(res.total_share, total_share_amount);
 }
+
+fn init_swap_pair(
+    collateral_pool_amount: Uint128,
+    asset_pool_amount: Uint128,
+    offer_amount: Uint128,
+) -> cosmwasm_std::OwnedDeps<
+    cosmwasm_std::testing::MockStorage,
+    cosmwasm_std::testing::MockApi,
+    loopswap::mock_querier::WasmMockQuerier,
+> {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: collateral_pool_amount + offer_amount, /* user deposit must be pre-applied */
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &"liquidity0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000u128))],
+        ),
+        (
+            &"asset0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &asset_pool_amount)],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        asset_decimals: [6u8, 6u8],
+        is_stable_pair: false,
+        admin: "admin0000".to_string(),
+        extra_commission_contract_addr: "extra0000".to_string(),
+    };
+
+    // factory0000 instantiates the pair
+    let info = mock_info("factory0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // store liquidity token
+    let reply_msg = Reply {
+        id: 1,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(
+                vec![
+                    10, 13, 108, 105, 113, 117, 105, 100, 105, 116, 121, 48, 48, 48, 48,
+                ]
+                .into(),
+            ),
+        }),
+    };
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    deps
+}
+
+#[test]
+fn pause_blocks_swaps_and_deposits() {
+    let offer_amount = Uint128::from(1000u128);
+    let mut deps = init_swap_pair(
+        Uint128::from(1_000_000u128),
+        Uint128::from(1_000_000u128),
+        offer_amount,
+    );
+
+    // only admin or factory can pause
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::Pause {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("factory0000", &[]),
+        ExecuteMsg::Pause {},
+    )
+    .unwrap();
+    assert!(query_pause_info(deps.as_ref()).unwrap().paused);
+
+    let swap_msg = ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: offer_amount,
+        },
+        belief_price: None,
+        max_spread: None,
+        to: None,
+        deadline: None,
+        referral: None,
+    };
+    let swap_info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: offer_amount,
+        }],
+    );
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        swap_info.clone(),
+        swap_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        swap_info.clone(),
+        ExecuteMsg::ProvideLiquidity {
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: offer_amount,
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: "asset0000".to_string(),
+                    },
+                    amount: offer_amount,
+                },
+            ],
+            slippage_tolerance: None,
+            receiver: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+
+    // withdrawals still work while paused
+    let withdraw_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {}).unwrap(),
+        amount: Uint128::from(100u128),
+    });
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("liquidity0000", &[]),
+        withdraw_msg,
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin0000", &[]),
+        ExecuteMsg::Unpause {},
+    )
+    .unwrap();
+    execute(deps.as_mut(), mock_env(), swap_info, swap_msg).unwrap();
+}
+
+#[test]
+fn circuit_breaker_rejects_large_swap() {
+    let offer_amount = Uint128::from(100_000u128);
+    let mut deps = init_swap_pair(
+        Uint128::from(1_000_000u128),
+        Uint128::from(1_000_000u128),
+        offer_amount,
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("factory0000", &[]),
+        ExecuteMsg::UpdateCircuitBreaker {
+            max_price_impact: Some(Decimal::percent(5)),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin0000", &[]),
+        ExecuteMsg::UpdateCircuitBreaker {
+            max_price_impact: Some(Decimal::percent(5)),
+        },
+    )
+    .unwrap();
+
+    // 10% of the pool moves the price by ~17%, the swap is rejected and the pair stays open
+    let swap_msg = ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: offer_amount,
+        },
+        belief_price: None,
+        max_spread: None,
+        to: None,
+        deadline: None,
+        referral: None,
+    };
+    let swap_info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: offer_amount,
+        }],
+    );
+    let err = execute(deps.as_mut(), mock_env(), swap_info, swap_msg).unwrap_err();
+    assert_eq!(err, ContractError::MaxPriceImpactExceeded {});
+
+    let pause_info = query_pause_info(deps.as_ref()).unwrap();
+    assert!(!pause_info.paused);
+    assert_eq!(pause_info.max_price_impact, Some(Decimal::percent(5)));
+}
+
+#[test]
+fn dynamic_fee_follows_volatility() {
+    let offer_amount = Uint128::from(1000u128);
+    let mut deps = init_swap_pair(
+        Uint128::from(1_000_000u128),
+        Uint128::from(1_000_000u128),
+        offer_amount,
+    );
+
+    let dynamic_fee = DynamicFeeConfig {
+        min_fee: Decimal::permille(1),
+        max_fee: Decimal::percent(1),
+        volatility_multiplier: Decimal::percent(5),
+        window: 3600,
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateDynamicFee {
+            dynamic_fee: Some(dynamic_fee.clone()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin0000", &[]),
+        ExecuteMsg::UpdateDynamicFee {
+            dynamic_fee: Some(dynamic_fee),
+        },
+    )
+    .unwrap();
+
+    let offer_asset = Asset {
+        info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        amount: offer_amount,
+    };
+
+    // no history yet, the minimum applies
+    let simulation_res =
+        query_simulation(deps.as_ref(), mock_env(), offer_asset.clone(), None).unwrap();
+    assert_eq!(simulation_res.commission_rate, Decimal::permille(1));
+
+    // two 10% moves => volatility 0.1, fee = 0.001 + 0.1 * 0.05
+    let now = mock_env().block.time.seconds();
+    PRICE_HISTORY
+        .save(
+            &mut deps.storage,
+            &vec![
+                PriceObservation {
+                    time: now - 7200,
+                    price: Decimal::percent(50),
+                },
+                PriceObservation {
+                    time: now - 30,
+                    price: Decimal::one(),
+                },
+                PriceObservation {
+                    time: now - 20,
+                    price: Decimal::percent(110),
+                },
+                PriceObservation {
+                    time: now - 10,
+                    price: Decimal::percent(99),
+                },
+            ],
+        )
+        .unwrap();
+
+    let dynamic_fee_res = query_dynamic_fee(deps.as_ref(), mock_env()).unwrap();
+    assert_eq!(dynamic_fee_res.volatility, Decimal::percent(10));
+    assert_eq!(dynamic_fee_res.commission_rate, Decimal::permille(6));
+
+    let simulation_res =
+        query_simulation(deps.as_ref(), mock_env(), offer_asset.clone(), None).unwrap();
+    assert_eq!(simulation_res.commission_rate, Decimal::permille(6));
+    let reverse_simulation_res = query_reverse_simulation(
+        deps.as_ref(),
+        mock_env(),
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            amount: simulation_res.return_amount,
+        },
+    )
+    .unwrap();
+    assert_eq!(reverse_simulation_res.commission_rate, Decimal::permille(6));
+
+    // the swap pays the simulated fee and records its price, old observations expire
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(
+            "addr0000",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: offer_amount,
+            }],
+        ),
+        ExecuteMsg::Swap {
+            offer_asset,
+            belief_price: None,
+            max_spread: None,
+            to: None,
+            deadline: None,
+            referral: None,
+        },
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .contains(&attr("commission_amount", simulation_res.commission_amount)));
+    assert!(res.attributes.contains(&attr("commission_rate", "0.006")));
+
+    let history = PRICE_HISTORY.load(&deps.storage).unwrap();
+    assert_eq!(history.len(), 4);
+    assert_eq!(history[3].time, now);
+
+    // extreme volatility is capped at max_fee
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10);
+    PRICE_HISTORY
+        .save(
+            &mut deps.storage,
+            &vec![
+                PriceObservation {
+                    time: now,
+                    price: Decimal::one(),
+                },
+                PriceObservation {
+                    time: now + 5,
+                    price: Decimal::from_str("3").unwrap(),
+                },
+            ],
+        )
+        .unwrap();
+    let dynamic_fee_res = query_dynamic_fee(deps.as_ref(), env).unwrap();
+    assert_eq!(dynamic_fee_res.commission_rate, Decimal::percent(1));
+
+    // disabling goes back to the fixed rate
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin0000", &[]),
+        ExecuteMsg::UpdateDynamicFee { dynamic_fee: None },
+    )
+    .unwrap();
+    let dynamic_fee_res = query_dynamic_fee(deps.as_ref(), mock_env()).unwrap();
+    assert_eq!(dynamic_fee_res.dynamic_fee, None);
+    assert_eq!(dynamic_fee_res.commission_rate, Decimal::permille(3));
+}
+
+#[test]
+fn statistics_per_period() {
+    let offer_amount = Uint128::from(1000u128);
+    let mut deps = init_swap_pair(
+        Uint128::from(1_000_000u128),
+        Uint128::from(1_000_000u128),
+        offer_amount,
+    );
+
+    let swap_msg = ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: offer_amount,
+        },
+        belief_price: None,
+        max_spread: None,
+        to: None,
+        deadline: None,
+        referral: None,
+    };
+    let swap_info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: offer_amount,
+        }],
+    );
+
+    // two swaps a day ago, one now
+    let mut env = mock_env();
+    env.block.time = env.block.time.minus_seconds(86400);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        swap_info.clone(),
+        swap_msg.clone(),
+    )
+    .unwrap();
+    execute(deps.as_mut(), env, swap_info.clone(), swap_msg.clone()).unwrap();
+    let res = execute(deps.as_mut(), mock_env(), swap_info, swap_msg).unwrap();
+    let commission_amount = Uint128::from_str(
+        &res.attributes
+            .iter()
+            .find(|attr| attr.key == "commission_amount")
+            .unwrap()
+            .value,
+    )
+    .unwrap();
+
+    let hour = query_statistics(deps.as_ref(), mock_env(), StatisticsPeriod::Hour).unwrap();
+    assert_eq!(hour.swap_count, 1);
+    assert_eq!(hour.volume[0].amount, offer_amount);
+    assert_eq!(hour.volume[1].amount, Uint128::zero());
+    assert_eq!(hour.fees[0].amount, Uint128::zero());
+    assert_eq!(hour.fees[1].amount, commission_amount);
+
+    // 75% of the fee goes to LPs, valued in uusd over twice the uusd pool
+    let pool0 = Uint128::from(1_001_000u128);
+    let pool1 = Uint128::from(1_000_000u128);
+    let lp_fees = commission_amount
+        .multiply_ratio(75u128, 100u128)
+        .multiply_ratio(pool0, pool1);
+    assert_eq!(
+        hour.apr,
+        Decimal::from_ratio(lp_fees, pool0 * Uint128::from(2u128))
+            * Decimal::from_ratio(365u64 * 86400u64, 3600u64)
+    );
+
+    let day = query_statistics(deps.as_ref(), mock_env(), StatisticsPeriod::Day).unwrap();
+    assert_eq!(day.swap_count, 1);
+
+    let week = query_statistics(deps.as_ref(), mock_env(), StatisticsPeriod::Week).unwrap();
+    assert_eq!(week.swap_count, 3);
+    assert_eq!(week.volume[0].amount, offer_amount * Uint128::from(3u128));
+}
+
+#[test]
+fn provide_liquidity_with_send_hook() {
+    let deposit = Uint128::from(10_000u128);
+    // the pair already holds the uusd side of the completing deposit and the parked tokens
+    let mut deps = init_swap_pair(
+        Uint128::from(1_000_000u128),
+        Uint128::from(1_010_000u128),
+        deposit,
+    );
+
+    let assets = [
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: deposit,
+        },
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            amount: deposit,
+        },
+    ];
+    let hook_msg = |amount: Uint128| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr0000".to_string(),
+            amount,
+            msg: to_binary(&Cw20HookMsg::ProvideLiquidity {
+                assets: assets.clone(),
+                slippage_tolerance: None,
+                receiver: None,
+            })
+            .unwrap(),
+        })
+    };
+
+    // only the pair tokens can send the hook, with the amount of the deposit
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("asset0001", &[]),
+        hook_msg(deposit),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("asset0000", &[]),
+        hook_msg(Uint128::from(1u128)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::DepositMismatch {});
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("asset0000", &[]),
+        hook_msg(deposit),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 0);
+    assert_eq!(res.attributes[0], attr("action", "pending_deposit"));
+
+    let pending = query_pending_deposit(deps.as_ref(), "addr0000".to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(pending.deposited[1].amount, deposit);
+    assert_eq!(pending.expires_at, mock_env().block.time.seconds() + 3600);
+
+    // parked tokens are not part of the pool
+    let pool = query_pool(deps.as_ref()).unwrap();
+    assert_eq!(pool.assets[1].amount, Uint128::from(1_000_000u128));
+
+    // the native side completes the deposit without TransferFrom
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(
+            "addr0000",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: deposit,
+            }],
+        ),
+        ExecuteMsg::ProvideLiquidity {
+            assets: assets.clone(),
+            slippage_tolerance: None,
+            receiver: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "liquidity0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(10u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    assert_eq!(
+        query_pending_deposit(deps.as_ref(), "addr0000".to_string()).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn refund_expired_deposit() {
+    let deposit = Uint128::from(10_000u128);
+    let mut deps = init_swap_pair(
+        Uint128::from(1_000_000u128),
+        Uint128::from(1_010_000u128),
+        Uint128::zero(),
+    );
+
+    let assets = [
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: deposit,
+        },
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            amount: deposit,
+        },
+    ];
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("asset0000", &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr0000".to_string(),
+            amount: deposit,
+            msg: to_binary(&Cw20HookMsg::ProvideLiquidity {
+                assets: assets.clone(),
+                slippage_tolerance: None,
+                receiver: None,
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap();
+
+    let refund_msg = ExecuteMsg::RefundDeposit {
+        depositor: Some("addr0000".to_string()),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        refund_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::DepositNotExpired {});
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(3600);
+
+    // an expired deposit can not be completed
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(
+            "addr0000",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: deposit,
+            }],
+        ),
+        ExecuteMsg::ProvideLiquidity {
+            assets,
+            slippage_tolerance: None,
+            receiver: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::DepositExpired {});
+
+    let res = execute(deps.as_mut(), env, mock_info("addr0001", &[]), refund_msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "asset0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: deposit,
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    assert_eq!(
+        query_pending_deposit(deps.as_ref(), "addr0000".to_string()).unwrap(),
+        None
+    );
+    let pool = query_pool(deps.as_ref()).unwrap();
+    assert_eq!(pool.assets[1].amount, Uint128::from(1_010_000u128));
+}
+
+#[test]
+fn swap_deadline_and_referral() {
+    let offer_amount = Uint128::from(100_000u128);
+    let mut deps = init_swap_pair(
+        Uint128::from(10_000_000u128),
+        Uint128::from(10_000_000u128),
+        offer_amount,
+    );
+    let swap_info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: offer_amount,
+        }],
+    );
+    let swap_msg = |deadline: Option<u64>, referral: Option<Referral>| ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: offer_amount,
+        },
+        belief_price: None,
+        max_spread: None,
+        to: None,
+        deadline,
+        referral,
+    };
+    let referral = Referral {
+        address: "referrer0000".to_string(),
+        commission_bps: 50,
+    };
+
+    let env = mock_env();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        swap_info.clone(),
+        swap_msg(Some(env.block.time.seconds() - 1), None),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::DeadlineExceeded {});
+
+    // referrals are disabled until the admin sets a cap
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        swap_info.clone(),
+        swap_msg(None, Some(referral.clone())),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ReferralCommissionExceeded {});
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateMaxReferralBps {
+            max_referral_bps: 100,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("unauthorized"))
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin0000", &[]),
+        ExecuteMsg::UpdateMaxReferralBps {
+            max_referral_bps: 10_001,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ReferralCommissionExceeded {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin0000", &[]),
+        ExecuteMsg::UpdateMaxReferralBps {
+            max_referral_bps: 100,
+        },
+    )
+    .unwrap();
+
+    // the 1% spread alone is within 1.2%, the referral cut pushes it over
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        swap_info.clone(),
+        ExecuteMsg::Swap {
+            offer_asset: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: offer_amount,
+            },
+            belief_price: None,
+            max_spread: Some(Decimal::permille(12)),
+            to: None,
+            deadline: None,
+            referral: Some(referral.clone()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::MaxSpreadAssertion {});
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        swap_info,
+        swap_msg(Some(env.block.time.seconds()), Some(referral)),
+    )
+    .unwrap();
+    // 99009 out, 297 commission, 0.5% of the remaining 98712 goes to the referrer
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(98_219u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "referrer0000".to_string(),
+                    amount: Uint128::from(493u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+    assert_eq!(res.attributes[6], attr("return_amount", "98219"));
+    assert_eq!(res.attributes[10], attr("referral_address", "referrer0000"));
+    assert_eq!(res.attributes[11], attr("referral_amount", "493"));
+}
@@ -0,0 +1,119 @@
+use cosmwasm_std::{from_binary, CosmosMsg, Empty, StdResult, Storage, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::state::{CategoryConfig, CATEGORY_CONFIGS, CONFIG, TIMELOCK_DELAYS};
+
+/// Timelock delay in seconds of categories the admin has not set a delay
+/// for. Text proposals carry no messages and are not delayed.
+pub const DEFAULT_TIMELOCK_DELAY: u64 = 172_800;
+
+/// The kind of action a proposal performs. Governance settings such as
+/// the timelock delay are configured per category. Variants are ordered
+/// from least to most impactful.
+#[derive(
+    Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalCategory {
+    /// Signalling proposal without any effect on chain.
+    #[default]
+    Text,
+    /// Changes fees, rewards or other contract parameters.
+    ParameterChange,
+    /// Moves funds out of the treasury.
+    TreasurySpend,
+    /// Migrates contracts to new code.
+    ContractUpgrade,
+    /// Time critical fixes.
+    Emergency,
+}
+
+impl ProposalCategory {
+    pub fn all() -> Vec<ProposalCategory> {
+        vec![
+            ProposalCategory::Text,
+            ProposalCategory::ParameterChange,
+            ProposalCategory::TreasurySpend,
+            ProposalCategory::ContractUpgrade,
+            ProposalCategory::Emergency,
+        ]
+    }
+
+    /// Works out the category of a proposal from its messages, taking
+    /// the most impactful one. Proposers can only pick a higher
+    /// category, Emergency proposals must be picked explicitly.
+    pub fn from_msgs(msgs: &[CosmosMsg<Empty>]) -> ProposalCategory {
+        msgs.iter()
+            .map(ProposalCategory::from_msg)
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn from_msg(msg: &CosmosMsg<Empty>) -> ProposalCategory {
+        match msg {
+            CosmosMsg::Wasm(WasmMsg::Migrate { .. })
+            | CosmosMsg::Wasm(WasmMsg::UpdateAdmin { .. })
+            | CosmosMsg::Wasm(WasmMsg::ClearAdmin { .. }) => ProposalCategory::ContractUpgrade,
+            CosmosMsg::Bank(_) => ProposalCategory::TreasurySpend,
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, funds, .. }) => {
+                let moves_tokens = matches!(
+                    from_binary(msg),
+                    Ok(Cw20ExecuteMsg::Transfer { .. })
+                        | Ok(Cw20ExecuteMsg::Send { .. })
+                        | Ok(Cw20ExecuteMsg::IncreaseAllowance { .. })
+                );
+                if moves_tokens || !funds.is_empty() {
+                    ProposalCategory::TreasurySpend
+                } else {
+                    ProposalCategory::ParameterChange
+                }
+            }
+            _ => ProposalCategory::ParameterChange,
+        }
+    }
+}
+
+/// The governance settings of a category. Categories without their own
+/// settings use the module's threshold and voting period bounds.
+pub fn load_category_config(
+    storage: &dyn Storage,
+    category: ProposalCategory,
+) -> StdResult<CategoryConfig> {
+    if let Some(config) = CATEGORY_CONFIGS.may_load(storage, category.to_string())? {
+        return Ok(config);
+    }
+    let config = CONFIG.load(storage)?;
+    Ok(CategoryConfig {
+        threshold: config.threshold,
+        min_voting_period: config.min_voting_period,
+        max_voting_period: config.max_voting_period,
+        deposit: None,
+    })
+}
+
+/// The timelock delay of a category, falling back to
+/// `DEFAULT_TIMELOCK_DELAY` so a category nobody configured is still
+/// delayed. The admin can set a delay of 0 to execute right away.
+pub fn load_timelock_delay(storage: &dyn Storage, category: ProposalCategory) -> StdResult<u64> {
+    if let Some(delay) = TIMELOCK_DELAYS.may_load(storage, category.to_string())? {
+        return Ok(delay);
+    }
+    Ok(match category {
+        ProposalCategory::Text => 0,
+        _ => DEFAULT_TIMELOCK_DELAY,
+    })
+}
+
+impl std::fmt::Display for ProposalCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProposalCategory::Text => write!(f, "text"),
+            ProposalCategory::ParameterChange => write!(f, "parameter_change"),
+            ProposalCategory::TreasurySpend => write!(f, "treasury_spend"),
+            ProposalCategory::ContractUpgrade => write!(f, "contract_upgrade"),
+            ProposalCategory::Emergency => write!(f, "emergency"),
+        }
+    }
+}
@@ -2,33 +2,47 @@ use std::fmt::format;
 use std::ops::Add;
 
 #[cfg(not(feature = "library"))]
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::category::{load_category_config, load_timelock_delay, ProposalCategory};
+use crate::delegation::{
+    collect_delegated_votes, delegated_power, delegated_share, remove_delegation,
+    revoke_delegated_vote, save_delegated_votes, save_delegation,
+};
+use crate::error::ContractError;
 use crate::proposal::{advance_proposal_id, SingleChoiceProposal};
 use crate::query::{
-    ProposalListResponse, ProposalResponse, VoteInfo, VoteListResponse, VoteResponse,
+    CategoriesResponse, CategoryInfo, DelegateInfo, DelegateListResponse, DelegationResponse,
+    ProposalListResponse, ProposalResponse, TimelockDelay, TimelockResponse, VoteInfo,
+    VoteListResponse, VoteResponse,
 };
+use crate::signature::verify_vote_signature;
 use crate::state::{
-    Ballot, Config, BALLOTS, CLOSED_STATUS, CONFIG, EXECUTED_STATUS, FAILED_STATUS, OPEN_STATUS,
-    PASSED_STATUS, PROPOSALS, PROPOSAL_COUNT, PROPOSERS_INFO, VOTING_CLOSED_STATUS,
+    Ballot, CategoryConfig, Config, Delegation, DepositConfig, OptimisticConfig, VetoConfig,
+    BALLOTS, CATEGORY_CONFIGS, CLOSED_STATUS, CONFIG, DAO_CORE, DELEGATES, DELEGATIONS, DEPOSIT,
+    EXECUTED_STATUS, FAILED_STATUS, GUARDIAN, OPEN_STATUS, OPTIMISTIC, PASSED_STATUS, PROPOSALS,
+    PROPOSAL_COUNT, PROPOSERS_INFO, TIMELOCK_DELAYS, VETO, VOTE_NONCES, VOTING_CLOSED_STATUS,
 };
 
 use crate::status::{self, Status};
 use crate::threshold::Threshold;
 use crate::voting::{validate_voting_period, Vote, Votes};
 use cosmwasm_std::{
-    entry_point, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo,
-    QueryRequest, Response, StdError, StdResult, Uint128, WasmQuery,
+    entry_point, from_binary, to_binary, Addr, Api, Binary, CosmosMsg, Decimal, Deps, DepsMut,
+    Empty, Env, MessageInfo, QueryRequest, Response, StdResult, Storage, Uint128, WasmMsg,
+    WasmQuery,
 };
 use cw2::set_contract_version;
-use cw20::BalanceResponse;
+use cw20::{BalanceResponse, Cw20ReceiveMsg};
 use cw_storage_plus::Bound;
 use cw_utils::Duration;
+use loopswap::asset::{Asset, AssetInfo};
+use loopswap::dao::ExecuteMsg as DaoExecuteMsg;
 use loopswap::factory::MigrateMsg;
-use loopswap::staking::QueryMsg as stakingMsg;
-use loopswap_staking::state::Config as StakingConfig;
+use loopswap::staking::{DurationsResponse, QueryMsg as stakingMsg};
 // use loopswap_staking::{msg::QueryMsg as QueryStakingMsg, state::Config as StakingConfig};
 
 pub const DEFAULT_LIMIT: u64 = 30;
+const MAX_LIMIT: u64 = 30;
 pub const MAX_PROPOSAL_SIZE: u64 = 30_000;
 
 pub(crate) const CONTRACT_NAME: &str = "crates.io:cwd-proposal-single";
@@ -40,7 +54,7 @@ pub fn instantiate(
     _env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     msg.threshold.validate()?;
@@ -48,6 +62,15 @@ pub fn instantiate(
     let (min_voting_period, max_voting_period) =
         validate_voting_period(msg.min_voting_period, msg.max_voting_period)?;
 
+    if let Some(veto) = msg.veto {
+        validate_veto_config(deps.api, &veto)?;
+        VETO.save(deps.storage, &veto)?;
+    }
+    if let Some(deposit) = msg.deposit {
+        validate_deposit_config(deps.api, &deposit)?;
+        DEPOSIT.save(deps.storage, &deposit)?;
+    }
+
     // let (initial_policy, pre_propose_messages) = msg
     //     .pre_propose_info
     //     .into_initial_policy_and_messages(dao.clone())?;
@@ -75,23 +98,73 @@ pub fn instantiate(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
     match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::Propose {
             title,
             description,
             msgs,
             voting_period,
-        } => execute_propose(
+            category,
+        } => {
+            let deposit = native_deposit(deps.storage, &info)?;
+            execute_propose(
+                deps,
+                env,
+                info.sender,
+                title,
+                description,
+                msgs,
+                voting_period,
+                category,
+                deposit,
+            )
+        }
+        ExecuteMsg::ProposeOptimistic {
+            title,
+            description,
+            msgs,
+            voting_period,
+            category,
+        } => execute_propose_optimistic(
             deps,
             env,
-            info.sender,
+            info,
             title,
             description,
             msgs,
             voting_period,
+            category,
         ),
-        ExecuteMsg::Vote { proposal_id, vote } => execute_vote(deps, env, info, proposal_id, vote),
+        ExecuteMsg::Vote { proposal_id, vote } => {
+            execute_vote(deps, env, info.sender, proposal_id, vote)
+        }
+        ExecuteMsg::VoteBySignature {
+            proposal_id,
+            vote,
+            voter_pubkey,
+            signature,
+            nonce,
+        } => {
+            let voter = verify_vote_signature(
+                deps.api,
+                deps.storage,
+                &env,
+                proposal_id,
+                &vote,
+                &voter_pubkey,
+                &signature,
+                nonce,
+            )?;
+            Ok(execute_vote(deps, env, voter, proposal_id, vote)?
+                .add_attribute("relayer", info.sender))
+        }
         ExecuteMsg::Execute { proposal_id } => execute_execute(deps, env, info, proposal_id),
         ExecuteMsg::Close { proposal_id } => execute_close(deps, env, info, proposal_id),
         ExecuteMsg::UpdateConfig {
@@ -111,9 +184,118 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             token_hold_duration,
             proposal_creation_token_limit,
         ),
+        ExecuteMsg::UpdateTimelockDelay { category, delay } => {
+            execute_update_timelock_delay(deps, info, category, delay)
+        }
+        ExecuteMsg::UpdateCategoryConfig { category, config } => {
+            execute_update_category_config(deps, info, category, config)
+        }
+        ExecuteMsg::UpdateGuardian { guardian } => execute_update_guardian(deps, info, guardian),
+        ExecuteMsg::Cancel { proposal_id } => execute_cancel(deps, info, proposal_id),
+        ExecuteMsg::UpdateVetoConfig { veto } => execute_update_veto_config(deps, info, veto),
+        ExecuteMsg::UpdateDaoCore { dao_core } => execute_update_dao_core(deps, info, dao_core),
+        ExecuteMsg::Veto { proposal_id } => execute_veto(deps, env, info, proposal_id),
+        ExecuteMsg::UpdateOptimisticConfig { optimistic } => {
+            execute_update_optimistic_config(deps, info, optimistic)
+        }
+        ExecuteMsg::UpdateDepositConfig { deposit } => {
+            execute_update_deposit_config(deps, info, deposit)
+        }
+        ExecuteMsg::RegisterDelegate {} => execute_register_delegate(deps, info),
+        ExecuteMsg::UnregisterDelegate {} => execute_unregister_delegate(deps, info),
+        ExecuteMsg::Delegate {
+            delegate,
+            percentage,
+        } => execute_delegate(deps, info, delegate, percentage),
+        ExecuteMsg::Undelegate {} => execute_undelegate(deps, info),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Propose {
+            title,
+            description,
+            msgs,
+            voting_period,
+            category,
+        } => {
+            // Only the deposit token can create proposals this way.
+            let token = match DEPOSIT.may_load(deps.storage)? {
+                Some(deposit) => deposit.token,
+                None => return Err(ContractError::Unauthorized {}),
+            };
+            let authorized = match &token {
+                AssetInfo::Token { contract_addr } => *contract_addr == info.sender,
+                AssetInfo::NativeToken { .. } => false,
+            };
+            if !authorized {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let proposer = deps.api.addr_validate(&cw20_msg.sender)?;
+            execute_propose(
+                deps,
+                env,
+                proposer,
+                title,
+                description,
+                msgs,
+                voting_period,
+                category,
+                Some(Asset {
+                    info: token,
+                    amount: cw20_msg.amount,
+                }),
+            )
+        }
+    }
+}
+
+/// The deposit sent as funds, if the deposit is paid in a native denom.
+/// Any other funds are rejected instead of being kept by the contract.
+fn native_deposit(
+    storage: &dyn Storage,
+    info: &MessageInfo,
+) -> Result<Option<Asset>, ContractError> {
+    let token = match DEPOSIT.may_load(storage)? {
+        Some(deposit) => deposit.token,
+        None => {
+            nonpayable(info)?;
+            return Ok(None);
+        }
+    };
+    let amount = match &token {
+        AssetInfo::NativeToken { denom } => {
+            if info.funds.iter().any(|coin| coin.denom != *denom) {
+                return Err(ContractError::UnexpectedFunds {});
+            }
+            info.funds.iter().map(|coin| coin.amount).sum::<Uint128>()
+        }
+        AssetInfo::Token { .. } => {
+            nonpayable(info)?;
+            return Ok(None);
+        }
+    };
+    Ok(Some(Asset {
+        info: token,
+        amount,
+    }))
+}
+
+fn nonpayable(info: &MessageInfo) -> Result<(), ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::UnexpectedFunds {});
     }
+    Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn execute_propose(
     deps: DepsMut,
     env: Env,
@@ -122,21 +304,41 @@ pub fn execute_propose(
     description: String,
     msgs: Vec<CosmosMsg<Empty>>,
     voting_period: Duration,
-) -> StdResult<Response> {
+    category: Option<ProposalCategory>,
+    deposit: Option<Asset>,
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
+    let category = ProposalCategory::from_msgs(&msgs).max(category.unwrap_or_default());
+    let category_config = load_category_config(deps.storage, category)?;
+
+    if let Some(required) = DEPOSIT.may_load(deps.storage)? {
+        let amount = category_config.deposit.unwrap_or(required.amount);
+        let paid = deposit.as_ref().map(|d| d.amount).unwrap_or_default();
+        if paid != amount {
+            return Err(ContractError::InvalidDeposit {
+                deposit: Asset {
+                    info: required.token,
+                    amount,
+                }
+                .to_string(),
+            });
+        }
+    }
+    // Categories may not require a deposit.
+    let deposit = deposit.filter(|d| !d.amount.is_zero());
 
     if !verifying_voting_period(
         &voting_period,
-        &config.min_voting_period,
-        &config.max_voting_period,
+        &category_config.min_voting_period,
+        &category_config.max_voting_period,
     ) {
-        return Err(StdError::generic_err("invalid voting period"));
+        return Err(ContractError::InvalidVotingPeriod {});
     }
 
     let vote_power = get_voting_power(deps.as_ref(), sender.clone(), config.dao.to_string())?;
     // let power = Uint128::from(1000000u128);
     if vote_power.is_zero() {
-        return Err(StdError::generic_err("Power is zero can't create proposal"));
+        return Err(ContractError::ZeroProposalPower {});
     }
 
     let expiration = voting_period.after(&env.block);
@@ -145,9 +347,7 @@ pub fn execute_propose(
         .unwrap_or(Uint128::zero());
 
     if vote_power - locked_amount < config.proposal_creation_token_limit {
-        return Err(StdError::generic_err(
-            "Power is not enough to create proposal",
-        ));
+        return Err(ContractError::InsufficientProposalPower {});
     }
     locked_amount += config.proposal_creation_token_limit;
 
@@ -160,13 +360,17 @@ pub fn execute_propose(
             description,
             proposer: sender.clone(),
             expiration,
-            threshold: config.threshold,
+            threshold: category_config.threshold,
             total_power: Uint128::zero(),
             msgs,
             status: Status::Open,
             votes: Votes::zero(),
             allow_revoting: false,
             voting_start_time: env.block.time.seconds(),
+            category,
+            eta: None,
+            deposit,
+            optimistic: false,
         };
         // Update the proposal's status. Addresses case where proposal
         // expires on the same block as it is created.
@@ -191,7 +395,7 @@ pub fn execute_propose(
     // into it's byte representation in storage.
     let proposal_size = cosmwasm_std::to_vec(&proposal)?.len() as u64;
     if proposal_size > MAX_PROPOSAL_SIZE {
-        return Err(StdError::generic_err("Proposal size is too large"));
+        return Err(ContractError::ProposalTooLarge {});
     }
 
     PROPOSALS.save(deps.storage, id, &proposal)?;
@@ -201,15 +405,85 @@ pub fn execute_propose(
         .add_attribute("action", "propose")
         .add_attribute("sender", sender)
         .add_attribute("proposal_id", id.to_string())
+        .add_attribute("category", proposal.category.to_string())
         .add_attribute("status", proposal.status.to_string()))
 }
 
+#[allow(clippy::too_many_arguments)]
+pub fn execute_propose_optimistic(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    title: String,
+    description: String,
+    msgs: Vec<CosmosMsg<Empty>>,
+    voting_period: Duration,
+    category: Option<ProposalCategory>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let optimistic = OPTIMISTIC
+        .may_load(deps.storage)?
+        .ok_or(ContractError::Unauthorized {})?;
+    if !optimistic
+        .proposers
+        .iter()
+        .any(|proposer| *proposer == info.sender)
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let category = ProposalCategory::from_msgs(&msgs).max(category.unwrap_or_default());
+    let category_config = load_category_config(deps.storage, category)?;
+    if !verifying_voting_period(
+        &voting_period,
+        &category_config.min_voting_period,
+        &category_config.max_voting_period,
+    ) {
+        return Err(ContractError::InvalidVotingPeriod {});
+    }
+
+    // Whitelisted proposers hold no tokens for the proposal, so there
+    // is nothing to release once it ends.
+    let proposal = SingleChoiceProposal {
+        title,
+        description,
+        proposer: info.sender.clone(),
+        expiration: voting_period.after(&env.block),
+        threshold: optimistic.objection_threshold,
+        total_power: get_total_power(deps.as_ref(), config.dao)?,
+        msgs,
+        status: Status::Open,
+        votes: Votes::zero(),
+        allow_revoting: false,
+        voting_start_time: env.block.time.seconds(),
+        category,
+        eta: None,
+        deposit: None,
+        optimistic: true,
+    };
+    let id = advance_proposal_id(deps.storage)?;
+
+    let proposal_size = cosmwasm_std::to_vec(&proposal)?.len() as u64;
+    if proposal_size > MAX_PROPOSAL_SIZE {
+        return Err(ContractError::ProposalTooLarge {});
+    }
+
+    PROPOSALS.save(deps.storage, id, &proposal)?;
+
+    Ok(Response::default()
+        .add_attribute("action", "propose_optimistic")
+        .add_attribute("sender", info.sender)
+        .add_attribute("proposal_id", id.to_string())
+        .add_attribute("category", proposal.category.to_string()))
+}
+
 pub fn execute_execute(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     proposal_id: u64,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     // if config.only_members_execute {
 
@@ -217,84 +491,189 @@ pub fn execute_execute(
 
     let mut prop: SingleChoiceProposal = PROPOSALS
         .may_load(deps.storage, proposal_id)?
-        .ok_or(StdError::generic_err("No Such Proposal"))?;
+        .ok_or(ContractError::NoSuchProposal {})?;
     let vote_power = get_voting_power(deps.as_ref(), info.sender.clone(), config.dao.to_string())?;
     // let power = Uint128::from(1000000u128);
     if vote_power.is_zero() {
-        return Err(StdError::generic_err("Unauthorized"));
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // A queued proposal only needs its eta to be reached.
+    if prop.status == Status::Queued {
+        let eta = prop.eta.unwrap_or_default();
+        if env.block.time.seconds() < eta {
+            return Err(ContractError::TimelockNotExpired { eta });
+        }
+        return dispatch_proposal(deps, info, config, proposal_id, prop);
     }
 
     // Check here that the proposal is passed. Allow it to be executed
     // even if it is expired so long as it passed during its voting
     // period.
-
-    let mut locked_amount = PROPOSERS_INFO
-        .load(deps.storage, prop.proposer.to_string())
-        .unwrap_or(Uint128::zero());
-    locked_amount -= config.proposal_creation_token_limit;
-
-    PROPOSERS_INFO.save(deps.storage, prop.proposer.to_string(), &locked_amount)?;
     let description = prop.is_passed(&env.block).description;
-    if description != "" {
-        return Err(StdError::generic_err(description));
+    // is_passed only explains why while the voting period is still running
+    if !description.is_empty() {
+        return Err(ContractError::VotingNotExpired {});
     }
     prop.update_status(&env.block);
-    // if matches!(old_status , Status::Open {voting_status} ) && prop.is_passed(&env.block) {
-    //     prop.status = Status::Passed;
-    // }
-    println!("{:?}", prop.status);
     if prop.status != Status::Passed {
-        return Err(StdError::generic_err("Proposal is not in 'passed' state"));
+        return Err(ContractError::NotPassed {});
     }
+
+    // The proposal leaves the passed state here, so the proposer's
+    // tokens are no longer held.
+    release_proposer_hold(deps.storage, &config, &prop)?;
+    let refund = settle_deposit(deps.storage, &mut prop, true)?;
+
+    let delay = load_timelock_delay(deps.storage, prop.category)?;
+    if delay == 0 {
+        return Ok(dispatch_proposal(deps, info, config, proposal_id, prop)?.add_messages(refund));
+    }
+
+    let eta = env.block.time.seconds() + delay;
+    prop.status = Status::Queued;
+    prop.eta = Some(eta);
+    PROPOSALS.save(deps.storage, proposal_id, &prop)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "queue")
+        .add_attribute("sender", info.sender)
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("eta", eta.to_string())
+        .add_messages(refund))
+}
+
+/// Releases the tokens the proposer held to create the proposal.
+fn release_proposer_hold(
+    storage: &mut dyn Storage,
+    config: &Config,
+    prop: &SingleChoiceProposal,
+) -> StdResult<()> {
+    if prop.optimistic {
+        return Ok(());
+    }
+    let locked_amount = PROPOSERS_INFO
+        .load(storage, prop.proposer.to_string())
+        .unwrap_or(Uint128::zero());
+    PROPOSERS_INFO.save(
+        storage,
+        prop.proposer.to_string(),
+        &(locked_amount - config.proposal_creation_token_limit),
+    )
+}
+
+/// Refunds the proposal deposit to the proposer or slashes it to the
+/// treasury. Does nothing if the deposit was already settled.
+fn settle_deposit(
+    storage: &dyn Storage,
+    prop: &mut SingleChoiceProposal,
+    refund: bool,
+) -> StdResult<Option<CosmosMsg>> {
+    let deposit = match prop.deposit.take() {
+        Some(deposit) => deposit,
+        None => return Ok(None),
+    };
+    // Without a deposit config there is no treasury to slash to.
+    let recipient = match DEPOSIT.may_load(storage)? {
+        Some(config) if !refund => Addr::unchecked(config.treasury),
+        _ => prop.proposer.clone(),
+    };
+    Ok(Some(deposit.into_msg(recipient)?))
+}
+
+fn dispatch_proposal(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Config,
+    proposal_id: u64,
+    mut prop: SingleChoiceProposal,
+) -> Result<Response, ContractError> {
     prop.status = Status::Executed;
     PROPOSALS.save(deps.storage, proposal_id, &prop)?;
+    let msgs = proposal_messages(deps.storage, prop.msgs)?;
 
     Ok(Response::new()
         .add_attribute("action", "execute")
         .add_attribute("sender", info.sender)
         .add_attribute("proposal_id", proposal_id.to_string())
         .add_attribute("dao", config.dao)
-        .add_messages(prop.msgs))
+        .add_messages(msgs))
+}
+
+pub fn execute_cancel(
+    deps: DepsMut,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let guardian = GUARDIAN.may_load(deps.storage)?;
+    if guardian != Some(info.sender.clone()) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut prop: SingleChoiceProposal = PROPOSALS
+        .may_load(deps.storage, proposal_id)?
+        .ok_or(ContractError::NoSuchProposal {})?;
+    if prop.status != Status::Queued {
+        return Err(ContractError::NotQueued {});
+    }
+    prop.status = Status::Cancelled;
+    PROPOSALS.save(deps.storage, proposal_id, &prop)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel")
+        .add_attribute("sender", info.sender)
+        .add_attribute("proposal_id", proposal_id.to_string()))
 }
 
 pub fn execute_vote(
     deps: DepsMut,
     env: Env,
-    info: MessageInfo,
+    voter: Addr,
     proposal_id: u64,
     vote: Vote,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let mut prop: SingleChoiceProposal = PROPOSALS
         .may_load(deps.storage, proposal_id)?
-        .ok_or(StdError::generic_err("No such proposal"))?;
+        .ok_or(ContractError::NoSuchProposal {})?;
 
     // let vote_power=Uint128::from(100000u128);
-    let vote_power = get_voting_power(deps.as_ref(), info.sender.clone(), config.dao.to_string())?;
+    let own_power = get_voting_power(deps.as_ref(), voter.clone(), config.dao.to_string())?;
+    // Registered delegates also vote with the power of delegators who
+    // did not vote on this proposal themselves.
+    let delegated_votes =
+        collect_delegated_votes(deps.as_ref(), &config.dao, proposal_id, &voter)?;
+    let vote_power = delegated_votes
+        .iter()
+        .fold(own_power, |power, (_, share)| power + *share);
 
     if vote_power.is_zero() {
-        let mut message = "Not Registered".to_string();
-        message.push_str(&format!(
-            "sender {} dao {}",
-            info.sender.clone(),
-            config.dao.to_string(),
-        ));
-        return Err(StdError::generic_err(&message));
+        return Err(ContractError::NotRegistered {
+            sender: voter.to_string(),
+        });
     }
 
     if prop.expiration.is_expired(&env.block) {
-        return Err(StdError::generic_err("Voting time expired"));
-    } else if prop.status == Status::Executed || prop.status == Status::Closed {
-        return Err(StdError::generic_err(format!(
-            "Proposal is in {} state",
-            prop.status
-        )));
+        return Err(ContractError::VotingExpired {});
+    } else if prop.status == Status::Executed
+        || prop.status == Status::Closed
+        || prop.status == Status::Queued
+        || prop.status == Status::Cancelled
+        || prop.status == Status::Vetoed
+    {
+        return Err(ContractError::InvalidProposalStatus {
+            status: prop.status,
+        });
     }
     // prop.status = Status::Executed;
 
+    // A direct vote overrides the one cast by the voter's delegate.
+    revoke_delegated_vote(deps.storage, proposal_id, &voter, &mut prop)?;
+    save_delegated_votes(deps.storage, proposal_id, &voter, &delegated_votes)?;
+
     BALLOTS.update(
         deps.storage,
-        (proposal_id, info.sender.clone()),
+        (proposal_id, voter.clone()),
         |bal| match bal {
             Some(current_ballot) => {
                 if prop.allow_revoting {
@@ -302,7 +681,7 @@ pub fn execute_vote(
                         // Don't allow casting the same vote more than
                         // once. This seems liable to be confusing
                         // behavior.
-                        Err(StdError::generic_err("Already Casted"))
+                        Err(ContractError::AlreadyCast {})
                     } else {
                         // Remove the old vote if this is a re-vote.
                         prop.votes
@@ -340,7 +719,7 @@ pub fn execute_vote(
 
     Ok(Response::default()
         .add_attribute("action", "vote")
-        .add_attribute("sender", info.sender)
+        .add_attribute("sender", voter)
         .add_attribute("proposal_id", proposal_id.to_string())
         .add_attribute("position", vote.to_string())
         .add_attribute("status", prop.status.to_string())
@@ -352,7 +731,7 @@ pub fn execute_close(
     env: Env,
     info: MessageInfo,
     proposal_id: u64,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut prop: SingleChoiceProposal = PROPOSALS.load(deps.storage, proposal_id)?;
     let config: Config = CONFIG.load(deps.storage)?;
     // Update status to ensure that proposals which were open and have
@@ -361,33 +740,37 @@ pub fn execute_close(
     // if info.sender != config.admin {
     //     return Err(StdError::generic_err("Unauthorized"));
     // }
-    let mut locked_amount = PROPOSERS_INFO
-        .load(deps.storage, prop.proposer.to_string())
-        .unwrap_or(Uint128::zero());
     if prop.voting_start_time + config.token_hold_duration > env.block.time.seconds() {
-        return Err(StdError::generic_err(
-            "tokens are hold in the contract kindly wait for the specified time",
-        ));
+        return Err(ContractError::TokensLocked {});
     }
 
     prop.update_status(&env.block);
     if prop.status != Status::Rejected {
-        return Err(StdError::generic_err("Only rejected proposals closed."));
+        return Err(ContractError::NotRejected {});
     }
 
-    locked_amount -= config.proposal_creation_token_limit;
+    release_proposer_hold(deps.storage, &config, &prop)?;
 
-    PROPOSERS_INFO.save(deps.storage, prop.proposer.to_string(), &locked_amount)?;
-    let old_status = prop.clone().status;
+    let refund = match DEPOSIT.may_load(deps.storage)? {
+        Some(deposit) => deposit
+            .refund_policy
+            .refunds_rejected(prop.is_quorum_reached()),
+        None => true,
+    };
+    let deposit_msg = settle_deposit(deps.storage, &mut prop, refund)?;
     prop.status = Status::Closed;
     PROPOSALS.save(deps.storage, proposal_id, &prop)?;
 
-    // Add prepropose / deposit module hook which will handle deposit refunds.
-
-    Ok(Response::default()
+    let mut res = Response::default()
         .add_attribute("action", "close")
         .add_attribute("sender", info.sender)
-        .add_attribute("proposal_id", proposal_id.to_string()))
+        .add_attribute("proposal_id", proposal_id.to_string());
+    if let Some(msg) = deposit_msg {
+        res = res
+            .add_attribute("deposit", if refund { "refunded" } else { "slashed" })
+            .add_message(msg);
+    }
+    Ok(res)
 }
 
 pub fn get_voting_power(deps: Deps, sender: Addr, dao: String) -> StdResult<Uint128> {
@@ -409,7 +792,7 @@ pub fn get_voting_power(deps: Deps, sender: Addr, dao: String) -> StdResult<Uint
 pub fn get_total_power(deps: Deps, dao: String) -> StdResult<Uint128> {
     let mut total_power = Uint128::zero();
 
-    let config: StakingConfig = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+    let config: DurationsResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: dao.to_string(),
         msg: to_binary(&stakingMsg::QueryConfig {})?,
     }))?;
@@ -442,12 +825,12 @@ pub fn execute_update_config(
     dao_: Option<String>,
     token_hold_duration_: Option<u64>,
     proposal_creation_token_limit_: Option<Uint128>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
 
     // Only the DAO may call this method.
     if info.sender != config.admin {
-        return Err(StdError::generic_err("Unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
     let mut threshold = config.threshold;
     if threshold_.is_some() {
@@ -495,6 +878,347 @@ pub fn execute_update_config(
         .add_attribute("sender", info.sender))
 }
 
+pub fn execute_veto(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let veto = VETO
+        .may_load(deps.storage)?
+        .ok_or(ContractError::Unauthorized {})?;
+    if info.sender != veto.vetoer {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut prop: SingleChoiceProposal = PROPOSALS
+        .may_load(deps.storage, proposal_id)?
+        .ok_or(ContractError::NoSuchProposal {})?;
+    let status = prop.current_status(&env.block);
+    let allowed = match status {
+        Status::Open => veto.veto_open,
+        Status::Passed | Status::Queued => veto.veto_passed,
+        _ => false,
+    };
+    if !allowed {
+        return Err(ContractError::InvalidProposalStatus { status });
+    }
+
+    // Queued proposals already released the proposer's tokens.
+    if status != Status::Queued {
+        let config = CONFIG.load(deps.storage)?;
+        release_proposer_hold(deps.storage, &config, &prop)?;
+    }
+
+    // Deposits of queued proposals were refunded when they were queued.
+    let slash = settle_deposit(deps.storage, &mut prop, false)?;
+    prop.status = Status::Vetoed;
+    PROPOSALS.save(deps.storage, proposal_id, &prop)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "veto")
+        .add_attribute("sender", info.sender)
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("previous_status", status.to_string())
+        .add_messages(slash))
+}
+
+pub fn execute_register_delegate(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    if DELEGATES.has(deps.storage, &info.sender) {
+        return Err(ContractError::DelegateAlreadyRegistered {});
+    }
+    DELEGATES.save(deps.storage, &info.sender, &Empty {})?;
+
+    Ok(Response::default()
+        .add_attribute("action", "register_delegate")
+        .add_attribute("delegate", info.sender))
+}
+
+pub fn execute_unregister_delegate(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    if !DELEGATES.has(deps.storage, &info.sender) {
+        return Err(ContractError::DelegateNotRegistered {
+            delegate: info.sender.to_string(),
+        });
+    }
+    DELEGATES.remove(deps.storage, &info.sender);
+
+    Ok(Response::default()
+        .add_attribute("action", "unregister_delegate")
+        .add_attribute("delegate", info.sender))
+}
+
+pub fn execute_delegate(
+    deps: DepsMut,
+    info: MessageInfo,
+    delegate: String,
+    percentage: Decimal,
+) -> Result<Response, ContractError> {
+    let delegate = deps.api.addr_validate(&delegate)?;
+    if delegate == info.sender {
+        return Err(ContractError::SelfDelegation {});
+    }
+    if !DELEGATES.has(deps.storage, &delegate) {
+        return Err(ContractError::DelegateNotRegistered {
+            delegate: delegate.to_string(),
+        });
+    }
+    if percentage.is_zero() || percentage > Decimal::one() {
+        return Err(ContractError::InvalidDelegationPercentage {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let power = get_voting_power(deps.as_ref(), info.sender.clone(), config.dao)?;
+    if (power * percentage).is_zero() {
+        return Err(ContractError::NoDelegatedPower {});
+    }
+
+    save_delegation(
+        deps.storage,
+        &info.sender,
+        &Delegation {
+            delegate: delegate.clone(),
+            percentage,
+        },
+    )?;
+
+    Ok(Response::default()
+        .add_attribute("action", "delegate")
+        .add_attribute("delegator", info.sender)
+        .add_attribute("delegate", delegate)
+        .add_attribute("percentage", percentage.to_string()))
+}
+
+/// Votes a delegate already cast with the delegator's power stay
+/// counted unless the delegator votes on those proposals directly.
+pub fn execute_undelegate(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let delegation =
+        remove_delegation(deps.storage, &info.sender)?.ok_or(ContractError::NoDelegation {})?;
+
+    Ok(Response::default()
+        .add_attribute("action", "undelegate")
+        .add_attribute("delegator", info.sender)
+        .add_attribute("delegate", delegation.delegate))
+}
+
+pub fn execute_update_veto_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    veto: Option<VetoConfig>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match veto {
+        Some(veto) => {
+            validate_veto_config(deps.api, &veto)?;
+            VETO.save(deps.storage, &veto)?;
+        }
+        None => VETO.remove(deps.storage),
+    }
+
+    Ok(Response::default()
+        .add_attribute("action", "update_veto_config")
+        .add_attribute("sender", info.sender))
+}
+
+pub fn execute_update_category_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    category: ProposalCategory,
+    category_config: Option<CategoryConfig>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match category_config {
+        Some(category_config) => {
+            category_config.threshold.validate()?;
+            let (min_voting_period, max_voting_period) = validate_voting_period(
+                category_config.min_voting_period,
+                category_config.max_voting_period,
+            )?;
+            CATEGORY_CONFIGS.save(
+                deps.storage,
+                category.to_string(),
+                &CategoryConfig {
+                    min_voting_period,
+                    max_voting_period,
+                    ..category_config
+                },
+            )?;
+        }
+        None => CATEGORY_CONFIGS.remove(deps.storage, category.to_string()),
+    }
+
+    Ok(Response::default()
+        .add_attribute("action", "update_category_config")
+        .add_attribute("sender", info.sender)
+        .add_attribute("category", category.to_string()))
+}
+
+pub fn execute_update_deposit_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    deposit: Option<DepositConfig>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Proposals keep the deposit they were created with.
+    match deposit {
+        Some(deposit) => {
+            validate_deposit_config(deps.api, &deposit)?;
+            DEPOSIT.save(deps.storage, &deposit)?;
+        }
+        None => DEPOSIT.remove(deps.storage),
+    }
+
+    Ok(Response::default()
+        .add_attribute("action", "update_deposit_config")
+        .add_attribute("sender", info.sender))
+}
+
+pub fn execute_update_optimistic_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    optimistic: Option<OptimisticConfig>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Open optimistic proposals keep their objection threshold.
+    match optimistic {
+        Some(optimistic) => {
+            if optimistic.proposers.is_empty() {
+                return Err(ContractError::InvalidOptimisticConfig {});
+            }
+            for proposer in optimistic.proposers.iter() {
+                deps.api.addr_validate(proposer)?;
+            }
+            optimistic.objection_threshold.validate()?;
+            OPTIMISTIC.save(deps.storage, &optimistic)?;
+        }
+        None => OPTIMISTIC.remove(deps.storage),
+    }
+
+    Ok(Response::default()
+        .add_attribute("action", "update_optimistic_config")
+        .add_attribute("sender", info.sender))
+}
+
+fn validate_deposit_config(api: &dyn Api, deposit: &DepositConfig) -> Result<(), ContractError> {
+    api.addr_validate(&deposit.treasury)?;
+    if let AssetInfo::Token { contract_addr } = &deposit.token {
+        api.addr_validate(contract_addr)?;
+    }
+    if deposit.amount.is_zero() {
+        return Err(ContractError::InvalidDepositConfig {});
+    }
+    Ok(())
+}
+
+/// Hands the messages of a passed proposal to the DAO core if one is
+/// set, so they run with the DAO's admin roles and treasury.
+fn proposal_messages(
+    storage: &dyn Storage,
+    msgs: Vec<CosmosMsg<Empty>>,
+) -> StdResult<Vec<CosmosMsg<Empty>>> {
+    match DAO_CORE.may_load(storage)? {
+        Some(dao_core) if !msgs.is_empty() => Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: dao_core.to_string(),
+            msg: to_binary(&DaoExecuteMsg::ExecuteProposalHook { msgs })?,
+            funds: vec![],
+        })]),
+        _ => Ok(msgs),
+    }
+}
+
+pub fn execute_update_dao_core(
+    deps: DepsMut,
+    info: MessageInfo,
+    dao_core: Option<String>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match dao_core {
+        Some(dao_core) => {
+            let dao_core = deps.api.addr_validate(&dao_core)?;
+            DAO_CORE.save(deps.storage, &dao_core)?;
+        }
+        None => DAO_CORE.remove(deps.storage),
+    }
+
+    Ok(Response::default()
+        .add_attribute("action", "update_dao_core")
+        .add_attribute("sender", info.sender))
+}
+
+fn validate_veto_config(api: &dyn Api, veto: &VetoConfig) -> Result<(), ContractError> {
+    api.addr_validate(&veto.vetoer)?;
+    if !veto.veto_open && !veto.veto_passed {
+        return Err(ContractError::InvalidVetoConfig {});
+    }
+    Ok(())
+}
+
+pub fn execute_update_timelock_delay(
+    deps: DepsMut,
+    info: MessageInfo,
+    category: ProposalCategory,
+    delay: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    TIMELOCK_DELAYS.save(deps.storage, category.to_string(), &delay)?;
+
+    Ok(Response::default()
+        .add_attribute("action", "update_timelock_delay")
+        .add_attribute("category", category.to_string())
+        .add_attribute("delay", delay.to_string()))
+}
+
+pub fn execute_update_guardian(
+    deps: DepsMut,
+    info: MessageInfo,
+    guardian: Option<String>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match guardian {
+        Some(guardian) => {
+            let guardian = deps.api.addr_validate(&guardian)?;
+            GUARDIAN.save(deps.storage, &guardian)?;
+        }
+        None => GUARDIAN.remove(deps.storage),
+    }
+
+    Ok(Response::default()
+        .add_attribute("action", "update_guardian")
+        .add_attribute("sender", info.sender))
+}
+
 pub fn verifying_voting_period(
     voting_period: &Duration,
     min_vp: &Duration,
@@ -654,6 +1378,23 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         } => query_list_votes(deps, proposal_id, start_after, limit),
         QueryMsg::Info {} => query_info(deps),
         QueryMsg::HoldAmount { address } => query_hold_amount(deps, address),
+        QueryMsg::ListQueuedProposals { start_after, limit } => {
+            query_list_queued_proposals(deps, env, start_after, limit)
+        }
+        QueryMsg::Categories {} => query_categories(deps),
+        QueryMsg::Timelock {} => query_timelock(deps),
+        QueryMsg::VetoConfig {} => to_binary(&VETO.may_load(deps.storage)?),
+        QueryMsg::DaoCore {} => to_binary(&DAO_CORE.may_load(deps.storage)?),
+        QueryMsg::DepositConfig {} => to_binary(&DEPOSIT.may_load(deps.storage)?),
+        QueryMsg::OptimisticConfig {} => to_binary(&OPTIMISTIC.may_load(deps.storage)?),
+        QueryMsg::ListDelegates { start_after, limit } => {
+            query_list_delegates(deps, start_after, limit)
+        }
+        QueryMsg::Delegation { delegator } => query_delegation(deps, delegator),
+        QueryMsg::VoteNonce { proposal_id, voter } => {
+            let voter = deps.api.addr_validate(&voter)?;
+            to_binary(&VOTE_NONCES.may_load(deps.storage, (proposal_id, &voter))?)
+        }
         // QueryMsg::ReverseProposals {
         //     start_before,
         //     limit,
@@ -711,6 +1452,98 @@ pub fn query_list_proposals(
     to_binary(&ProposalListResponse { proposals: props })
 }
 
+pub fn query_list_queued_proposals(
+    deps: Deps,
+    env: Env,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> StdResult<Binary> {
+    let min = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    let props = PROPOSALS
+        .range(deps.storage, min, None, cosmwasm_std::Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, proposal)) => proposal.status == Status::Queued,
+            Err(_) => true,
+        })
+        .take(limit as usize)
+        .map(|item| {
+            let (id, proposal) = item?;
+            Ok(proposal.into_response(&env.block, id))
+        })
+        .collect::<StdResult<Vec<ProposalResponse>>>()?;
+
+    to_binary(&ProposalListResponse { proposals: props })
+}
+
+pub fn query_categories(deps: Deps) -> StdResult<Binary> {
+    let categories = ProposalCategory::all()
+        .into_iter()
+        .map(|category| {
+            let config = load_category_config(deps.storage, category)?;
+            Ok(CategoryInfo { category, config })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&CategoriesResponse { categories })
+}
+
+pub fn query_timelock(deps: Deps) -> StdResult<Binary> {
+    let guardian = GUARDIAN.may_load(deps.storage)?;
+    let delays = ProposalCategory::all()
+        .into_iter()
+        .map(|category| {
+            let delay = load_timelock_delay(deps.storage, category)?;
+            Ok(TimelockDelay { category, delay })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&TimelockResponse { guardian, delays })
+}
+
+pub fn query_list_delegates(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u64>,
+) -> StdResult<Binary> {
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let min = start_after.as_ref().map(Bound::exclusive);
+    // every delegate sums the live power of up to MAX_DELEGATORS delegators
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let config = CONFIG.load(deps.storage)?;
+    let delegates = DELEGATES
+        .keys(deps.storage, min, None, cosmwasm_std::Order::Ascending)
+        .take(limit as usize)
+        .map(|delegate| {
+            let delegate = delegate?;
+            let delegated_power = delegated_power(deps, &config.dao, &delegate)?;
+            Ok(DelegateInfo {
+                delegate,
+                delegated_power,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&DelegateListResponse { delegates })
+}
+
+pub fn query_delegation(deps: Deps, delegator: String) -> StdResult<Binary> {
+    let config = CONFIG.load(deps.storage)?;
+    let delegator = deps.api.addr_validate(&delegator)?;
+    let delegation = DELEGATIONS.may_load(deps.storage, &delegator)?;
+    let delegated_power = match &delegation {
+        Some(delegation) => delegated_share(deps, &config.dao, &delegator, delegation)?,
+        None => Uint128::zero(),
+    };
+
+    to_binary(&DelegationResponse {
+        delegation,
+        delegated_power,
+    })
+}
+
 pub fn query_reverse_proposals(
     deps: Deps,
     env: Env,
@@ -781,7 +1614,7 @@ pub fn query_info(deps: Deps) -> StdResult<Binary> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
     Ok(Response::default())
     //     // Set contract to version to latest
     //     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
@@ -868,7 +1701,7 @@ pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response>
 }
 
 // #[cfg_attr(not(feature = "library"), entry_point)]
-// pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+// pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
 //     let repl = TaggedReplyId::new(msg.id)?;
 //     match repl {
 //         TaggedReplyId::FailedProposalExecution(proposal_id) => {
@@ -0,0 +1,157 @@
+use cosmwasm_std::{Addr, Deps, Empty, Order, StdResult, Storage, Uint128};
+
+use crate::contract::get_voting_power;
+use crate::error::ContractError;
+use crate::proposal::SingleChoiceProposal;
+use crate::state::{
+    Delegation, BALLOTS, DELEGATED_BALLOTS, DELEGATES, DELEGATE_TOTALS, DELEGATIONS, DELEGATORS,
+    VOTED_THROUGH,
+};
+
+/// The most delegators a delegate can have.
+pub const MAX_DELEGATORS: u64 = 100;
+
+/// The part of the delegator's current voting power that goes to
+/// their delegate.
+pub fn delegated_share(
+    deps: Deps,
+    dao: &str,
+    delegator: &Addr,
+    delegation: &Delegation,
+) -> StdResult<Uint128> {
+    let power = get_voting_power(deps, delegator.clone(), dao.to_string())?;
+    Ok(power * delegation.percentage)
+}
+
+/// The current voting power delegated to `delegate` by all of its
+/// delegators.
+pub fn delegated_power(deps: Deps, dao: &str, delegate: &Addr) -> StdResult<Uint128> {
+    let mut power = Uint128::zero();
+    for delegator in DELEGATORS
+        .prefix(delegate)
+        .keys(deps.storage, None, None, Order::Ascending)
+    {
+        let delegator = delegator?;
+        let delegation = DELEGATIONS.load(deps.storage, &delegator)?;
+        power += delegated_share(deps, dao, &delegator, &delegation)?;
+    }
+    Ok(power)
+}
+
+/// Records `delegation` of `delegator`, replacing any previous one, and
+/// keeps the delegate totals in step. Delegates take at most
+/// `MAX_DELEGATORS` delegators so their votes stay cheap to count.
+pub fn save_delegation(
+    store: &mut dyn Storage,
+    delegator: &Addr,
+    delegation: &Delegation,
+) -> Result<(), ContractError> {
+    remove_delegation(store, delegator)?;
+    let mut totals = DELEGATE_TOTALS
+        .may_load(store, &delegation.delegate)?
+        .unwrap_or_default();
+    if totals.delegators >= MAX_DELEGATORS {
+        return Err(ContractError::TooManyDelegators {
+            max: MAX_DELEGATORS,
+        });
+    }
+    totals.delegators += 1;
+    DELEGATE_TOTALS.save(store, &delegation.delegate, &totals)?;
+    DELEGATORS.save(store, (&delegation.delegate, delegator), &Empty {})?;
+    DELEGATIONS.save(store, delegator, delegation)?;
+    Ok(())
+}
+
+/// Removes the delegation of `delegator`, if any, and takes it out of
+/// the delegate totals.
+pub fn remove_delegation(
+    store: &mut dyn Storage,
+    delegator: &Addr,
+) -> StdResult<Option<Delegation>> {
+    let delegation = match DELEGATIONS.may_load(store, delegator)? {
+        Some(delegation) => delegation,
+        None => return Ok(None),
+    };
+    let mut totals = DELEGATE_TOTALS.load(store, &delegation.delegate)?;
+    totals.delegators -= 1;
+    DELEGATE_TOTALS.save(store, &delegation.delegate, &totals)?;
+    DELEGATORS.remove(store, (&delegation.delegate, delegator));
+    DELEGATIONS.remove(store, delegator);
+    Ok(Some(delegation))
+}
+
+/// The shares a delegate votes with on a proposal. Delegators who have
+/// voted on the proposal themselves are left out.
+pub fn collect_delegated_votes(
+    deps: Deps,
+    dao: &str,
+    proposal_id: u64,
+    delegate: &Addr,
+) -> StdResult<Vec<(Addr, Uint128)>> {
+    if !DELEGATES.has(deps.storage, delegate) {
+        return Ok(vec![]);
+    }
+
+    let mut votes = vec![];
+    for delegator in DELEGATORS
+        .prefix(delegate)
+        .keys(deps.storage, None, None, Order::Ascending)
+    {
+        let delegator = delegator?;
+        if BALLOTS.has(deps.storage, (proposal_id, delegator.clone())) {
+            continue;
+        }
+        let delegation = DELEGATIONS.load(deps.storage, &delegator)?;
+        let share = delegated_share(deps, dao, &delegator, &delegation)?;
+        if !share.is_zero() {
+            votes.push((delegator, share));
+        }
+    }
+    Ok(votes)
+}
+
+/// Replaces the shares `delegate` voted with on a proposal.
+pub fn save_delegated_votes(
+    store: &mut dyn Storage,
+    proposal_id: u64,
+    delegate: &Addr,
+    votes: &[(Addr, Uint128)],
+) -> StdResult<()> {
+    let previous = DELEGATED_BALLOTS
+        .prefix((proposal_id, delegate))
+        .keys(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Addr>>>()?;
+    for delegator in previous {
+        DELEGATED_BALLOTS.remove(store, (proposal_id, delegate, &delegator));
+        VOTED_THROUGH.remove(store, (proposal_id, &delegator));
+    }
+
+    for (delegator, share) in votes {
+        DELEGATED_BALLOTS.save(store, (proposal_id, delegate, delegator), share)?;
+        VOTED_THROUGH.save(store, (proposal_id, delegator), delegate)?;
+    }
+    Ok(())
+}
+
+/// Takes the voter's share back from the delegate that voted with it,
+/// so that the voter's own vote overrides the delegate's.
+pub fn revoke_delegated_vote(
+    store: &mut dyn Storage,
+    proposal_id: u64,
+    voter: &Addr,
+    prop: &mut SingleChoiceProposal,
+) -> StdResult<()> {
+    let delegate = match VOTED_THROUGH.may_load(store, (proposal_id, voter))? {
+        Some(delegate) => delegate,
+        None => return Ok(()),
+    };
+    let share = DELEGATED_BALLOTS.load(store, (proposal_id, &delegate, voter))?;
+    DELEGATED_BALLOTS.remove(store, (proposal_id, &delegate, voter));
+    VOTED_THROUGH.remove(store, (proposal_id, voter));
+
+    let mut ballot = BALLOTS.load(store, (proposal_id, delegate.clone()))?;
+    prop.votes.remove_vote(ballot.vote.clone(), share);
+    ballot.power -= share;
+    BALLOTS.save(store, (proposal_id, delegate), &ballot)?;
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+use crate::status::Status;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid voting period")]
+    InvalidVotingPeriod {},
+
+    #[error("Min and max voting period must both be heights or both be times")]
+    VotingPeriodUnitMismatch {},
+
+    #[error("Zero threshold")]
+    ZeroThreshold {},
+
+    #[error("Unreachable threshold")]
+    UnreachableThreshold {},
+
+    #[error("Power is zero can't create proposal")]
+    ZeroProposalPower {},
+
+    #[error("Power is not enough to create proposal")]
+    InsufficientProposalPower {},
+
+    #[error("Proposal size is too large")]
+    ProposalTooLarge {},
+
+    #[error("No such proposal")]
+    NoSuchProposal {},
+
+    #[error("Not registered, sender {sender} has no voting power")]
+    NotRegistered { sender: String },
+
+    #[error("Voting time expired")]
+    VotingExpired {},
+
+    #[error("Proposal is in {status} state")]
+    InvalidProposalStatus { status: Status },
+
+    #[error("Already casted")]
+    AlreadyCast {},
+
+    #[error("Voting time is not expired yet")]
+    VotingNotExpired {},
+
+    #[error("Proposal is not in 'passed' state")]
+    NotPassed {},
+
+    #[error("tokens are hold in the contract kindly wait for the specified time")]
+    TokensLocked {},
+
+    #[error("Only rejected proposals can be closed")]
+    NotRejected {},
+
+    #[error("Proposal is queued until {eta}")]
+    TimelockNotExpired { eta: u64 },
+
+    #[error("Proposal is not in 'queued' state")]
+    NotQueued {},
+
+    #[error("Veto config must allow vetoing at least one stage")]
+    InvalidVetoConfig {},
+
+    #[error("{delegate} is not a registered delegate")]
+    DelegateNotRegistered { delegate: String },
+
+    #[error("Delegate already registered")]
+    DelegateAlreadyRegistered {},
+
+    #[error("Cannot delegate to yourself")]
+    SelfDelegation {},
+
+    #[error("Delegated percentage must be above 0 and at most 1")]
+    InvalidDelegationPercentage {},
+
+    #[error("No delegation found")]
+    NoDelegation {},
+
+    #[error("Cannot delegate without voting power")]
+    NoDelegatedPower {},
+
+    #[error("Delegate already has the maximum of {max} delegators")]
+    TooManyDelegators { max: u64 },
+
+    #[error("Proposals require a deposit of {deposit}")]
+    InvalidDeposit { deposit: String },
+
+    #[error("Deposit amount must be above 0")]
+    InvalidDepositConfig {},
+
+    #[error("Only the native proposal deposit can be sent")]
+    UnexpectedFunds {},
+
+    #[error("Public key must be a compressed secp256k1 key")]
+    InvalidPubkey {},
+
+    #[error("Invalid vote signature")]
+    InvalidSignature {},
+
+    #[error("Nonce must be above {last_nonce}")]
+    InvalidNonce { last_nonce: u64 },
+
+    #[error("Optimistic proposals need at least one proposer")]
+    InvalidOptimisticConfig {},
+}
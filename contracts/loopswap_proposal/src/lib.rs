@@ -1,11 +1,16 @@
+pub mod category;
 pub mod contract;
+pub mod delegation;
 pub mod msg;
 pub mod proposal;
 pub mod query;
+pub mod signature;
 pub mod state;
 pub mod status;
 pub mod threshold;
 pub mod voting;
 
+mod error;
+
 #[cfg(test)]
 mod testing;
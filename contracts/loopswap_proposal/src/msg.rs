@@ -1,6 +1,9 @@
+use crate::category::ProposalCategory;
+use crate::state::{CategoryConfig, DepositConfig, OptimisticConfig, VetoConfig};
 use crate::threshold::Threshold;
 use crate::voting::Vote;
-use cosmwasm_std::{CosmosMsg, Empty, Uint128};
+use cosmwasm_std::{Binary, CosmosMsg, Decimal, Empty, Uint128};
+use cw20::Cw20ReceiveMsg;
 use cw_utils::Duration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -42,11 +45,21 @@ pub struct InstantiateMsg {
     pub proposal_creation_token_limit: Uint128,
 
     pub token_hold_duration: u64,
+
+    /// The optional veto council.
+    pub veto: Option<VetoConfig>,
+
+    /// The optional deposit proposers must send with `Propose`.
+    pub deposit: Option<DepositConfig>,
 }
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    /// Creates a proposal in the module.
+    /// Receives the LOOP deposit of a proposal created through
+    /// `Cw20HookMsg::Propose`.
+    Receive(Cw20ReceiveMsg),
+    /// Creates a proposal in the module. A native deposit, if
+    /// configured, is sent along as funds.
     Propose {
         /// The title of the proposal.
         title: String,
@@ -56,6 +69,19 @@ pub enum ExecuteMsg {
         /// proposal passing.
         msgs: Vec<CosmosMsg<Empty>>,
         voting_period: Duration,
+        /// The category of the proposal. The category worked out from
+        /// `msgs` is used if it is not set or set to a lower one.
+        category: Option<ProposalCategory>,
+    },
+    /// Creates an optimistic proposal. It passes once its voting period
+    /// ends unless "no with veto" votes reach the objection threshold.
+    /// Only whitelisted proposers can call it, no deposit is taken.
+    ProposeOptimistic {
+        title: String,
+        description: String,
+        msgs: Vec<CosmosMsg<Empty>>,
+        voting_period: Duration,
+        category: Option<ProposalCategory>,
     },
     /// Votes on a proposal. Voting power is determined by the DAO's
     /// voting power module.
@@ -65,15 +91,29 @@ pub enum ExecuteMsg {
         /// The senders position on the proposal.
         vote: Vote,
     },
-    /// Causes the messages associated with a passed proposal to be
-    /// executed by the DAO.
+    /// Casts a vote signed off-chain by the voter, submitted by anyone.
+    /// The vote counts exactly like one cast with `Vote`.
+    VoteBySignature {
+        proposal_id: u64,
+        vote: Vote,
+        /// The voter's compressed secp256k1 public key.
+        voter_pubkey: Binary,
+        /// The signature over the sha256 hash of the JSON encoded
+        /// `VotePayload`.
+        signature: Binary,
+        /// Must be above the last nonce the voter used on the proposal.
+        nonce: u64,
+    },
+    /// Queues a passed proposal in the timelock. Once its eta is
+    /// reached, calling this again causes the messages associated
+    /// with the proposal to be executed by the DAO.
     Execute {
         /// The ID of the proposal to execute.
         proposal_id: u64,
     },
     /// Closes a proposal that has failed (either not passed or timed
-    /// out). If applicable this will cause the proposal deposit
-    /// associated wth said proposal to be returned.
+    /// out). Its deposit is refunded or slashed according to the
+    /// refund policy.
     Close {
         /// The ID of the proposal to close.
         proposal_id: u64,
@@ -99,6 +139,80 @@ pub enum ExecuteMsg {
         token_hold_duration: Option<u64>,
         proposal_creation_token_limit: Option<Uint128>,
     },
+    /// Sets the number of seconds passed proposals of a category wait
+    /// in the queue before they can be executed. Categories without a
+    /// delay wait `DEFAULT_TIMELOCK_DELAY`, text proposals none.
+    UpdateTimelockDelay {
+        category: ProposalCategory,
+        delay: u64,
+    },
+    /// Sets the threshold, voting period bounds and deposit of a
+    /// category. Removing them makes the category use the module's
+    /// config again.
+    UpdateCategoryConfig {
+        category: ProposalCategory,
+        config: Option<CategoryConfig>,
+    },
+    /// Sets or removes the guardian allowed to cancel queued
+    /// proposals.
+    UpdateGuardian { guardian: Option<String> },
+    /// Cancels a queued proposal. Only callable by the guardian.
+    Cancel { proposal_id: u64 },
+    /// Sets or removes the veto council.
+    UpdateVetoConfig { veto: Option<VetoConfig> },
+    /// Sets or removes the DAO core. Messages of passed proposals are
+    /// executed by the DAO core if set, otherwise by this module.
+    UpdateDaoCore { dao_core: Option<String> },
+    /// Vetoes a proposal. Only callable by the veto council, at the
+    /// stages its config allows.
+    Veto { proposal_id: u64 },
+    /// Sets or removes the proposal deposit.
+    UpdateDepositConfig { deposit: Option<DepositConfig> },
+    /// Sets or removes the optimistic proposers and objection
+    /// threshold.
+    UpdateOptimisticConfig { optimistic: Option<OptimisticConfig> },
+    /// Registers the sender as a delegate other holders can delegate
+    /// their voting power to.
+    RegisterDelegate {},
+    /// Stops the sender from receiving delegated voting power.
+    UnregisterDelegate {},
+    /// Delegates a part of the sender's voting power to a registered
+    /// delegate, replacing any previous delegation. The sender needs
+    /// voting power and a delegate takes at most `MAX_DELEGATORS`
+    /// delegators. Voting directly on a proposal overrides the
+    /// delegate's vote for it.
+    Delegate {
+        delegate: String,
+        /// The part of the sender's power to delegate, at most 1.
+        percentage: Decimal,
+    },
+    /// Revokes the sender's delegation.
+    Undelegate {},
+}
+
+/// The vote a voter signs for `VoteBySignature`. It is encoded as JSON
+/// with the fields in this order and no whitespace.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct VotePayload {
+    pub chain_id: String,
+    /// The address of this module.
+    pub contract: String,
+    pub proposal_id: u64,
+    pub vote: Vote,
+    pub nonce: u64,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Creates a proposal with the sent tokens as its deposit.
+    Propose {
+        title: String,
+        description: String,
+        msgs: Vec<CosmosMsg<Empty>>,
+        voting_period: Duration,
+        category: Option<ProposalCategory>,
+    },
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
@@ -171,6 +285,32 @@ pub enum QueryMsg {
     HoldAmount {
         address: String,
     },
+    /// Lists the proposals waiting in the timelock queue.
+    ListQueuedProposals {
+        start_after: Option<u64>,
+        limit: Option<u64>,
+    },
+    /// Gets the governance settings every category uses.
+    Categories {},
+    /// Gets the guardian and the timelock delay of every category.
+    Timelock {},
+    /// Gets the veto council config, if any.
+    VetoConfig {},
+    /// Gets the DAO core, if any.
+    DaoCore {},
+    /// Gets the deposit config, if any.
+    DepositConfig {},
+    /// Gets the optimistic proposal config, if any.
+    OptimisticConfig {},
+    /// Lists the registered delegates with their delegated power.
+    ListDelegates {
+        start_after: Option<String>,
+        limit: Option<u64>,
+    },
+    /// Gets a holder's delegation.
+    Delegation { delegator: String },
+    /// Gets the last nonce a voter signed a vote on the proposal with.
+    VoteNonce { proposal_id: u64, voter: String },
 }
 
 // #[cw_serde]
@@ -1,3 +1,4 @@
+use crate::category::ProposalCategory;
 use crate::query::ProposalResponse;
 use crate::state::PROPOSAL_COUNT;
 use crate::state::{
@@ -11,6 +12,7 @@ use cosmwasm_std::{
     Addr, BlockInfo, CosmosMsg, Decimal, Empty, StdError, StdResult, Storage, Uint128,
 };
 use cw_utils::Expiration;
+use loopswap::asset::Asset;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -41,6 +43,20 @@ pub struct SingleChoiceProposal {
     /// proposal's creation.
     pub total_power: Uint128,
     pub voting_start_time: u64,
+    /// The category the proposal was created under.
+    #[serde(default)]
+    pub category: ProposalCategory,
+    /// The time after which a queued proposal may be executed.
+    #[serde(default)]
+    pub eta: Option<u64>,
+    /// The deposit paid with the proposal. Cleared once it is refunded
+    /// or slashed.
+    #[serde(default)]
+    pub deposit: Option<Asset>,
+    /// Passes at expiration unless objected to. `threshold` then holds
+    /// the objection threshold.
+    #[serde(default)]
+    pub optimistic: bool,
 }
 
 pub fn advance_proposal_id(store: &mut dyn Storage) -> StdResult<u64> {
@@ -82,10 +98,55 @@ impl SingleChoiceProposal {
         self.status = new_status
     }
 
+    /// Returns true if enough voting power was cast to meet the quorum.
+    /// Thresholds without a quorum never reach it.
+    pub fn is_quorum_reached(&self) -> bool {
+        match self.threshold {
+            Threshold::ThresholdQuorum { quorum, .. } => does_vote_count_pass(
+                self.votes.total(),
+                self.total_power,
+                self.total_power,
+                quorum,
+            ),
+            _ => false,
+        }
+    }
+
+    /// Returns true if "no with veto" votes reached the threshold of an
+    /// optimistic proposal, counted like "yes" votes of an expired
+    /// regular proposal. Without a quorum a majority is taken of the
+    /// total power, so a few objections alone cannot block it.
+    pub fn is_objected(&self) -> bool {
+        let objections = self.votes.no_with_veto;
+        let options = self.votes.total() - self.votes.abstain;
+        match self.threshold {
+            Threshold::AbsolutePercentage { percentage } => {
+                does_vote_count_pass(objections, self.total_power, self.total_power, percentage)
+            }
+            Threshold::ThresholdQuorum { threshold, .. } => {
+                self.is_quorum_reached()
+                    && does_vote_count_pass(objections, options, self.total_power, threshold)
+            }
+            Threshold::AbsoluteCount { threshold } => objections >= threshold,
+        }
+    }
+
     /// Returns true iff this proposal is sure to pass (even before
     /// expiration if no future sequence of possible votes can cause
     /// it to fail).
     pub fn is_passed(&self, block: &BlockInfo) -> PassedResponse {
+        if self.optimistic {
+            if !self.expiration.is_expired(block) {
+                return PassedResponse {
+                    is_passed: false,
+                    description: "Voting time is not expired yet".to_string(),
+                };
+            }
+            return PassedResponse {
+                is_passed: !self.is_objected(),
+                description: "".to_string(),
+            };
+        }
         // If re-voting is allowed nothing is known until the proposal
         // has expired.
         if self.allow_revoting && !self.expiration.is_expired(block) {
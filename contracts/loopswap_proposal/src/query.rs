@@ -1,6 +1,8 @@
+use crate::category::ProposalCategory;
 use crate::proposal::SingleChoiceProposal;
 
 use crate::voting::Vote;
+use crate::state::{CategoryConfig, Delegation};
 use cosmwasm_std::{Addr, Uint128};
 
 use schemars::JsonSchema;
@@ -43,3 +45,48 @@ pub struct VoteListResponse {
 pub struct ProposalListResponse {
     pub proposals: Vec<ProposalResponse>,
 }
+
+/// The governance settings a category uses.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct CategoryInfo {
+    pub category: ProposalCategory,
+    pub config: CategoryConfig,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct CategoriesResponse {
+    pub categories: Vec<CategoryInfo>,
+}
+
+/// The timelock delay applied to proposals of a category.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct TimelockDelay {
+    pub category: ProposalCategory,
+    pub delay: u64,
+}
+
+/// Information about the timelock returned by `Timelock`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct TimelockResponse {
+    pub guardian: Option<Addr>,
+    pub delays: Vec<TimelockDelay>,
+}
+
+/// A registered delegate and the power currently delegated to it.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct DelegateInfo {
+    pub delegate: Addr,
+    pub delegated_power: Uint128,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct DelegateListResponse {
+    pub delegates: Vec<DelegateInfo>,
+}
+
+/// A holder's delegation and the power it currently carries.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct DelegationResponse {
+    pub delegation: Option<Delegation>,
+    pub delegated_power: Uint128,
+}
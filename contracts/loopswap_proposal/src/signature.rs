@@ -0,0 +1,58 @@
+use cosmwasm_std::{to_vec, Addr, Api, CanonicalAddr, Env, Storage};
+use ripemd160::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+use crate::msg::VotePayload;
+use crate::state::VOTE_NONCES;
+use crate::voting::Vote;
+
+/// The account address of a compressed secp256k1 public key, derived
+/// the same way the chain derives it.
+pub fn pubkey_to_address(api: &dyn Api, pubkey: &[u8]) -> Result<Addr, ContractError> {
+    if pubkey.len() != 33 {
+        return Err(ContractError::InvalidPubkey {});
+    }
+    let hash = Ripemd160::digest(&Sha256::digest(pubkey));
+    Ok(api.addr_humanize(&CanonicalAddr::from(hash.as_slice()))?)
+}
+
+/// Checks a signed vote and returns the voter. The nonce must be above
+/// the last one the voter used on the proposal, so every signature can
+/// only be submitted once.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_vote_signature(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    env: &Env,
+    proposal_id: u64,
+    vote: &Vote,
+    pubkey: &[u8],
+    signature: &[u8],
+    nonce: u64,
+) -> Result<Addr, ContractError> {
+    let voter = pubkey_to_address(api, pubkey)?;
+
+    let payload = to_vec(&VotePayload {
+        chain_id: env.block.chain_id.clone(),
+        contract: env.contract.address.to_string(),
+        proposal_id,
+        vote: vote.clone(),
+        nonce,
+    })?;
+    let verified = api
+        .secp256k1_verify(&Sha256::digest(&payload), signature, pubkey)
+        .unwrap_or(false);
+    if !verified {
+        return Err(ContractError::InvalidSignature {});
+    }
+
+    if let Some(last_nonce) = VOTE_NONCES.may_load(storage, (proposal_id, &voter))? {
+        if nonce <= last_nonce {
+            return Err(ContractError::InvalidNonce { last_nonce });
+        }
+    }
+    VOTE_NONCES.save(storage, (proposal_id, &voter), &nonce)?;
+
+    Ok(voter)
+}
@@ -1,9 +1,10 @@
 use crate::proposal::SingleChoiceProposal;
 use crate::threshold::Threshold;
 use crate::voting::Vote;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal, Empty, Uint128};
 use cw_storage_plus::{Item, Map};
 use cw_utils::Duration;
+use loopswap::asset::AssetInfo;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 pub const PASSED_STATUS: &str = "passed";
@@ -45,6 +46,81 @@ pub struct Config {
 
     pub token_hold_duration: u64,
 }
+/// Governance settings of a proposal category.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct CategoryConfig {
+    pub threshold: Threshold,
+    pub min_voting_period: Duration,
+    pub max_voting_period: Duration,
+    /// The deposit amount for the category, in the deposit token. Uses
+    /// the deposit config amount if not set.
+    pub deposit: Option<Uint128>,
+}
+/// Who may veto proposals and at which stage.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct VetoConfig {
+    /// The council or multisig allowed to veto proposals.
+    pub vetoer: String,
+    /// Allows vetoing proposals that are still open for voting.
+    pub veto_open: bool,
+    /// Allows vetoing proposals that passed but have not been
+    /// executed yet, including proposals waiting in the timelock.
+    pub veto_passed: bool,
+}
+/// Which rejected proposals get their deposit back. Passed proposals
+/// are always refunded and vetoed ones are always slashed.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RefundPolicy {
+    /// Refunds every rejected proposal.
+    Always {},
+    /// Refunds rejected proposals that reached quorum.
+    PassedOrQuorum {},
+    /// Slashes every rejected proposal.
+    OnlyPassed {},
+}
+
+impl RefundPolicy {
+    pub fn refunds_rejected(&self, quorum_reached: bool) -> bool {
+        match self {
+            RefundPolicy::Always {} => true,
+            RefundPolicy::PassedOrQuorum {} => quorum_reached,
+            RefundPolicy::OnlyPassed {} => false,
+        }
+    }
+}
+/// The deposit proposers send along with a proposal.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct DepositConfig {
+    /// The LOOP token or native denom the deposit is paid in.
+    pub token: AssetInfo,
+    pub amount: Uint128,
+    pub refund_policy: RefundPolicy,
+    /// Receives slashed deposits.
+    pub treasury: String,
+}
+/// Who may create optimistic proposals and how much objection
+/// rejects them.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct OptimisticConfig {
+    pub proposers: Vec<String>,
+    /// Applied to "no with veto" votes the way the passing threshold
+    /// is applied to "yes" votes, except that an absolute majority is
+    /// taken of the total power instead of the votes cast.
+    pub objection_threshold: Threshold,
+}
+/// A holder's delegation of voting power.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct Delegation {
+    pub delegate: Addr,
+    /// The part of the holder's power that is delegated.
+    pub percentage: Decimal,
+}
+/// The number of delegators of a delegate.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct DelegateTotals {
+    pub delegators: u64,
+}
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct PassedResponse {
     pub is_passed: bool,
@@ -59,3 +135,35 @@ pub const PROPOSAL_COUNT: Item<u64> = Item::new("proposal_count");
 pub const PROPOSALS: Map<u64, SingleChoiceProposal> = Map::new("proposals_v2");
 pub const BALLOTS: Map<(u64, Addr), Ballot> = Map::new("ballots");
 pub const PROPOSERS_INFO: Map<String, Uint128> = Map::new("Proposer Amount");
+/// The address allowed to cancel queued proposals.
+pub const GUARDIAN: Item<Addr> = Item::new("guardian");
+/// Seconds a passed proposal waits in the queue, keyed by category.
+/// Categories without an entry are executed right away.
+pub const TIMELOCK_DELAYS: Map<String, u64> = Map::new("timelock_delays");
+/// Governance settings of categories that do not use the module's
+/// config, keyed by category.
+pub const CATEGORY_CONFIGS: Map<String, CategoryConfig> = Map::new("category_configs");
+/// The optional veto council.
+pub const VETO: Item<VetoConfig> = Item::new("veto");
+/// The DAO core passed proposals are executed through.
+pub const DAO_CORE: Item<Addr> = Item::new("dao_core");
+/// The optional proposal deposit.
+pub const DEPOSIT: Item<DepositConfig> = Item::new("deposit");
+
+pub const OPTIMISTIC: Item<OptimisticConfig> = Item::new("optimistic");
+/// Addresses registered to receive delegated voting power.
+pub const DELEGATES: Map<&Addr, Empty> = Map::new("delegates");
+/// Delegations keyed by delegator.
+pub const DELEGATIONS: Map<&Addr, Delegation> = Map::new("delegations");
+/// Delegators of every delegate, keyed by (delegate, delegator).
+pub const DELEGATORS: Map<(&Addr, &Addr), Empty> = Map::new("delegators");
+/// Kept up to date on every delegation change, keyed by delegate.
+pub const DELEGATE_TOTALS: Map<&Addr, DelegateTotals> = Map::new("delegate_totals");
+/// Power a delegate voted with on behalf of a delegator, keyed by
+/// (proposal, delegate, delegator).
+pub const DELEGATED_BALLOTS: Map<(u64, &Addr, &Addr), Uint128> = Map::new("delegated_ballots");
+/// The delegate that voted on behalf of a delegator, keyed by
+/// (proposal, delegator).
+pub const VOTED_THROUGH: Map<(u64, &Addr), Addr> = Map::new("voted_through");
+/// The last nonce a voter signed a vote with, keyed by (proposal, voter).
+pub const VOTE_NONCES: Map<(u64, &Addr), u64> = Map::new("vote_nonces");
@@ -10,6 +10,9 @@ pub enum Status {
     Rejected,
     /// The proposal has been passed but has not been executed.
     Passed,
+    /// The proposal has been passed and is waiting in the timelock
+    /// queue until its eta.
+    Queued,
     /// The proposal has been passed and executed.
     Executed,
     /// The proposal has failed or expired and has been closed. A
@@ -17,6 +20,10 @@ pub enum Status {
     Closed,
     /// The proposal's execution failed.
     ExecutionFailed,
+    /// The proposal was cancelled by the guardian while queued.
+    Cancelled,
+    /// The proposal was vetoed by the veto council.
+    Vetoed,
 }
 
 impl std::fmt::Display for Status {
@@ -25,9 +32,12 @@ impl std::fmt::Display for Status {
             Status::Open => write!(f, "open"),
             Status::Rejected => write!(f, "rejected"),
             Status::Passed => write!(f, "passed"),
+            Status::Queued => write!(f, "queued"),
             Status::Executed => write!(f, "executed"),
             Status::Closed => write!(f, "closed"),
             Status::ExecutionFailed => write!(f, "execution failed"),
+            Status::Cancelled => write!(f, "cancelled"),
+            Status::Vetoed => write!(f, "vetoed"),
         }
     }
 }
@@ -1,5 +1,6 @@
 // use crate::contract::{execute_mint, instantiate, query, query_balance};
 use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
 use crate::query::ProposalResponse;
 use crate::threshold::{PercentageThreshold, Threshold};
 use crate::voting::Vote;
@@ -9,17 +10,87 @@ use cosmwasm_std::{to_binary, CosmosMsg, StdError, SubMsg, WasmMsg};
 use cw20_base::msg::ExecuteMsg as Cw20ExecuteMsg;
 use cosmwasm_std::Api;
 
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::OwnedDeps;
 use cw_utils::Duration;
-use loopswap::mock_querier::mock_dependencies;
+use loopswap::mock_querier::{mock_dependencies, WasmMockQuerier};
 use std::str::FromStr;
 // use crate::msg::{InstantiateMsg};
 use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::category::ProposalCategory;
+use crate::state::VetoConfig;
 fn mock_env_time(time: Timestamp) -> Env {
     let mut env = mock_env();
     env.block.time = time;
     env
 }
 
+/// Instantiates the module with `loop_staker1` and `loop_staker2`
+/// holding 1000 and 2000 of the 3000 total voting power.
+fn instantiate_with_stakers(
+    veto: Option<VetoConfig>,
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[(
+        &"Staking".to_string(),
+        &[
+            (&"loop_staker1".to_string(), &Uint128::from(1000u128)),
+            (&"loop_staker2".to_string(), &Uint128::from(2000u128)),
+        ],
+    )]);
+    deps.querier
+        .with_staking_total_balances(&[(1, Uint128::from(3000u128))]);
+
+    let msg = InstantiateMsg {
+        threshold: Threshold::ThresholdQuorum {
+            threshold: PercentageThreshold::Majority {},
+            quorum: PercentageThreshold::Percent(Decimal::percent(1)),
+        },
+        max_voting_period: Duration::Time(300),
+        min_voting_period: Duration::Time(5),
+        only_members_execute: false,
+        allow_revoting: false,
+        close_proposal_on_execution_failure: false,
+        dao: "Staking".to_string(),
+        proposal_creation_token_limit: Uint128::from(1u128),
+        token_hold_duration: 1,
+        veto,
+        deposit: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    deps
+}
+
+fn propose(category: ProposalCategory) -> ExecuteMsg {
+    ExecuteMsg::Propose {
+        title: "test".to_string(),
+        description: "new".to_string(),
+        msgs: vec![CosmosMsg::Bank(BankMsg::Send {
+            to_address: "receiver".to_string(),
+            amount: vec![Coin {
+                amount: Uint128::from(2u128),
+                denom: "uusd".to_string(),
+            }],
+        })],
+        voting_period: Duration::Time(120),
+        category: Some(category),
+    }
+}
+
+/// Executes passed proposals right away instead of queueing them behind
+/// the default timelock delays.
+fn disable_timelock(mut deps: cosmwasm_std::DepsMut) {
+    for category in ProposalCategory::all() {
+        execute(
+            deps.branch(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::UpdateTimelockDelay { category, delay: 0 },
+        )
+        .unwrap();
+    }
+}
+
 mod tests {
     use crate::proposal::SingleChoiceProposal;
 
@@ -46,6 +117,8 @@ mod tests {
             dao: "Staking".to_string(),
             proposal_creation_token_limit: Uint128::from(1u128),
             token_hold_duration: 1,
+            veto: None,
+            deposit: None,
             // marketing: None,
         };
 
@@ -77,6 +150,7 @@ mod tests {
             title: "test".to_string(),
             description: "new".to_string(),
             voting_period: Duration::Time(120),
+            category: None,
         };
 
         println!(
@@ -148,5 +222,1653 @@ mod tests {
         println!("{:?}", _res)
     }
 
-    // #[test]
+    fn init_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            threshold: Threshold::ThresholdQuorum {
+                threshold: PercentageThreshold::Majority {},
+                quorum: PercentageThreshold::Percent(Decimal::percent(1)),
+            },
+            max_voting_period: Duration::Time(300),
+            min_voting_period: Duration::Time(5),
+            only_members_execute: false,
+            allow_revoting: false,
+            close_proposal_on_execution_failure: false,
+            dao: "Staking".to_string(),
+            proposal_creation_token_limit: Uint128::from(1u128),
+            token_hold_duration: 1,
+            veto: None,
+            deposit: None,
+        }
+    }
+
+    #[test]
+    fn typed_errors() {
+        let mut deps = mock_dependencies(&[]);
+
+        let mut msg = init_msg();
+        msg.threshold = Threshold::AbsoluteCount {
+            threshold: Uint128::zero(),
+        };
+        let err = instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::ZeroThreshold {});
+
+        let mut msg = init_msg();
+        msg.min_voting_period = Duration::Height(5);
+        let err = instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::VotingPeriodUnitMismatch {});
+
+        instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), init_msg()).unwrap();
+
+        let propose = |voting_period| ExecuteMsg::Propose {
+            title: "test".to_string(),
+            description: "new".to_string(),
+            msgs: vec![],
+            voting_period,
+            category: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            propose(Duration::Time(1000)),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidVotingPeriod {});
+
+        // the mock staking contract reports no voting power
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            propose(Duration::Time(120)),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ZeroProposalPower {});
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::Vote {
+                proposal_id: 1,
+                vote: Vote::Yes,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NoSuchProposal {});
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::UpdateConfig {
+                threshold: None,
+                max_voting_period: None,
+                min_voting_period: None,
+                dao: None,
+                token_hold_duration: None,
+                proposal_creation_token_limit: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+    }
+}
+
+mod timelock {
+    use super::*;
+    use crate::category::DEFAULT_TIMELOCK_DELAY;
+    use crate::query::{ProposalListResponse, TimelockDelay, TimelockResponse};
+    use crate::status::Status;
+    use cosmwasm_std::{attr, Addr};
+
+    fn pass(deps: cosmwasm_std::DepsMut, proposal_id: u64) {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("loop_staker2", &[]),
+            ExecuteMsg::Vote {
+                proposal_id,
+                vote: Vote::Yes,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_timelock_queue() {
+        let mut deps = instantiate_with_stakers(None);
+
+        let update_delay = ExecuteMsg::UpdateTimelockDelay {
+            category: ProposalCategory::TreasurySpend,
+            delay: 100,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            update_delay.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), update_delay).unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::UpdateTimelockDelay {
+                category: ProposalCategory::Emergency,
+                delay: 0,
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::UpdateGuardian {
+                guardian: Some("guardian".to_string()),
+            },
+        )
+        .unwrap();
+
+        let timelock: TimelockResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Timelock {}).unwrap()).unwrap();
+        assert_eq!(timelock.guardian, Some(Addr::unchecked("guardian")));
+        assert!(timelock.delays.contains(&TimelockDelay {
+            category: ProposalCategory::TreasurySpend,
+            delay: 100,
+        }));
+        assert!(timelock.delays.contains(&TimelockDelay {
+            category: ProposalCategory::Text,
+            delay: 0,
+        }));
+        // categories the admin has not configured are still delayed
+        assert!(timelock.delays.contains(&TimelockDelay {
+            category: ProposalCategory::ParameterChange,
+            delay: DEFAULT_TIMELOCK_DELAY,
+        }));
+
+        for category in [
+            ProposalCategory::TreasurySpend,
+            ProposalCategory::Emergency,
+            ProposalCategory::TreasurySpend,
+        ] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("loop_staker1", &[]),
+                propose(category),
+            )
+            .unwrap();
+        }
+        for id in 1..=3 {
+            pass(deps.as_mut(), id);
+        }
+
+        // a passed proposal is queued instead of executed
+        let now = mock_env().block.time.seconds();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::Execute { proposal_id: 1 },
+        )
+        .unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "queue"),
+                attr("sender", "loop_staker1"),
+                attr("proposal_id", "1"),
+                attr("eta", (now + 100).to_string()),
+            ]
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env_time(mock_env().block.time.plus_seconds(99)),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::Execute { proposal_id: 1 },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::TimelockNotExpired { eta: now + 100 });
+
+        // categories with a zero delay are executed right away
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::Execute { proposal_id: 2 },
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::Execute { proposal_id: 3 },
+        )
+        .unwrap();
+        let queued: ProposalListResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ListQueuedProposals {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            queued.proposals.iter().map(|p| p.id).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert_eq!(queued.proposals[0].proposal.eta, Some(now + 100));
+
+        // only the guardian may cancel, and only queued proposals
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::Cancel { proposal_id: 3 },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("guardian", &[]),
+            ExecuteMsg::Cancel { proposal_id: 2 },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotQueued {});
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("guardian", &[]),
+            ExecuteMsg::Cancel { proposal_id: 3 },
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env_time(mock_env().block.time.plus_seconds(100)),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::Execute { proposal_id: 3 },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotPassed {});
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env_time(mock_env().block.time.plus_seconds(100)),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::Execute { proposal_id: 1 },
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        let prop: ProposalResponse = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::Proposal { proposal_id: 1 }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(prop.proposal.status, Status::Executed);
+        let prop: ProposalResponse = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::Proposal { proposal_id: 3 }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(prop.proposal.status, Status::Cancelled);
+
+        // the proposer's hold is released once the proposals left the vote
+        let hold: Uint128 = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::HoldAmount {
+                    address: "loop_staker1".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(hold, Uint128::zero());
+    }
+}
+
+mod veto {
+    use super::*;
+    use crate::query::ProposalListResponse;
+    use crate::status::Status;
+
+    fn veto_config(veto_open: bool, veto_passed: bool) -> VetoConfig {
+        VetoConfig {
+            vetoer: "council".to_string(),
+            veto_open,
+            veto_passed,
+        }
+    }
+
+    fn hold_amount(deps: cosmwasm_std::Deps) -> Uint128 {
+        from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::HoldAmount {
+                    address: "loop_staker1".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_veto() {
+        let mut deps = instantiate_with_stakers(Some(veto_config(true, false)));
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::UpdateVetoConfig {
+                veto: Some(veto_config(false, false)),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidVetoConfig {});
+
+        for _ in 0..2 {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("loop_staker1", &[]),
+                propose(ProposalCategory::Text),
+            )
+            .unwrap();
+        }
+        assert_eq!(hold_amount(deps.as_ref()), Uint128::from(2u128));
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker2", &[]),
+            ExecuteMsg::Veto { proposal_id: 1 },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // an open proposal can be vetoed and releases the proposer's hold
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("council", &[]),
+            ExecuteMsg::Veto { proposal_id: 1 },
+        )
+        .unwrap();
+        assert_eq!(hold_amount(deps.as_ref()), Uint128::from(1u128));
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker2", &[]),
+            ExecuteMsg::Vote {
+                proposal_id: 1,
+                vote: Vote::Yes,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidProposalStatus {
+                status: Status::Vetoed
+            }
+        );
+
+        // passed proposals are only vetoable when the config allows it
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker2", &[]),
+            ExecuteMsg::Vote {
+                proposal_id: 2,
+                vote: Vote::Yes,
+            },
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("council", &[]),
+            ExecuteMsg::Veto { proposal_id: 2 },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidProposalStatus {
+                status: Status::Passed
+            }
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::UpdateVetoConfig {
+                veto: Some(veto_config(false, true)),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::UpdateTimelockDelay {
+                category: ProposalCategory::Text,
+                delay: 100,
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::Execute { proposal_id: 2 },
+        )
+        .unwrap();
+        assert_eq!(hold_amount(deps.as_ref()), Uint128::zero());
+
+        // vetoing a queued proposal does not release the hold twice
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("council", &[]),
+            ExecuteMsg::Veto { proposal_id: 2 },
+        )
+        .unwrap();
+        assert_eq!(hold_amount(deps.as_ref()), Uint128::zero());
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env_time(mock_env().block.time.plus_seconds(100)),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::Execute { proposal_id: 2 },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotPassed {});
+
+        let list: ProposalListResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ListProposals {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            list.proposals
+                .iter()
+                .map(|p| p.proposal.status.clone())
+                .collect::<Vec<_>>(),
+            vec![Status::Vetoed, Status::Vetoed]
+        );
+    }
+}
+
+mod delegation {
+    use super::*;
+    use crate::delegation::MAX_DELEGATORS;
+    use crate::query::{DelegateInfo, DelegateListResponse, DelegationResponse, VoteResponse};
+    use cosmwasm_std::Addr;
+
+    fn vote(deps: cosmwasm_std::DepsMut, voter: &str, vote: Vote) {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(voter, &[]),
+            ExecuteMsg::Vote {
+                proposal_id: 1,
+                vote,
+            },
+        )
+        .unwrap();
+    }
+
+    fn ballot_power(deps: cosmwasm_std::Deps, voter: &str) -> Option<Uint128> {
+        let res: VoteResponse = from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::GetVote {
+                    proposal_id: 1,
+                    voter: voter.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.vote.map(|vote| vote.power)
+    }
+
+    #[test]
+    fn test_delegation() {
+        let mut deps = instantiate_with_stakers(None);
+        deps.querier.with_token_balances(&[(
+            &"Staking".to_string(),
+            &[
+                (&"loop_staker1".to_string(), &Uint128::from(1000u128)),
+                (&"loop_staker2".to_string(), &Uint128::from(2000u128)),
+                (&"loop_staker3".to_string(), &Uint128::from(500u128)),
+            ],
+        )]);
+        deps.querier
+            .with_staking_total_balances(&[(1, Uint128::from(3500u128))]);
+
+        let delegate = |percentage| ExecuteMsg::Delegate {
+            delegate: "loop_staker1".to_string(),
+            percentage,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker3", &[]),
+            delegate(Decimal::one()),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::DelegateNotRegistered {
+                delegate: "loop_staker1".to_string()
+            }
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::RegisterDelegate {},
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker3", &[]),
+            delegate(Decimal::percent(101)),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidDelegationPercentage {});
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            delegate(Decimal::one()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::SelfDelegation {});
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker4", &[]),
+            delegate(Decimal::one()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NoDelegatedPower {});
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker3", &[]),
+            delegate(Decimal::one()),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker2", &[]),
+            delegate(Decimal::percent(50)),
+        )
+        .unwrap();
+
+        let delegates: DelegateListResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ListDelegates {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            delegates.delegates,
+            vec![DelegateInfo {
+                delegate: Addr::unchecked("loop_staker1"),
+                delegated_power: Uint128::from(1500u128),
+            }]
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            propose(ProposalCategory::Text),
+        )
+        .unwrap();
+
+        // the delegate votes with its own and the delegated power
+        vote(deps.as_mut(), "loop_staker1", Vote::Yes);
+        assert_eq!(
+            ballot_power(deps.as_ref(), "loop_staker1"),
+            Some(Uint128::from(2500u128))
+        );
+
+        // a direct vote takes the delegator's share back
+        vote(deps.as_mut(), "loop_staker2", Vote::No);
+        assert_eq!(
+            ballot_power(deps.as_ref(), "loop_staker1"),
+            Some(Uint128::from(1500u128))
+        );
+        assert_eq!(
+            ballot_power(deps.as_ref(), "loop_staker2"),
+            Some(Uint128::from(2000u128))
+        );
+        let prop: ProposalResponse = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::Proposal { proposal_id: 1 }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(prop.proposal.votes.yes, Uint128::from(1500u128));
+        assert_eq!(prop.proposal.votes.no, Uint128::from(2000u128));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker3", &[]),
+            ExecuteMsg::Undelegate {},
+        )
+        .unwrap();
+        let delegation: DelegationResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Delegation {
+                    delegator: "loop_staker3".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(delegation.delegation, None);
+        let delegation: DelegationResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Delegation {
+                    delegator: "loop_staker2".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(delegation.delegated_power, Uint128::from(1000u128));
+        let delegates: DelegateListResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ListDelegates {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            delegates.delegates[0].delegated_power,
+            Uint128::from(1000u128)
+        );
+
+        // the listed power follows the delegators' current balances
+        deps.querier.with_token_balances(&[(
+            &"Staking".to_string(),
+            &[
+                (&"loop_staker1".to_string(), &Uint128::from(1000u128)),
+                (&"loop_staker2".to_string(), &Uint128::from(4000u128)),
+            ],
+        )]);
+        let delegates: DelegateListResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ListDelegates {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            delegates.delegates[0].delegated_power,
+            Uint128::from(2000u128)
+        );
+    }
+
+    #[test]
+    fn test_max_delegators() {
+        let mut deps = instantiate_with_stakers(None);
+        let stakers = (0..=MAX_DELEGATORS)
+            .map(|i| format!("delegator{}", i))
+            .collect::<Vec<_>>();
+        let power = Uint128::from(10u128);
+        let balances = stakers
+            .iter()
+            .map(|staker| (staker, &power))
+            .collect::<Vec<_>>();
+        deps.querier
+            .with_token_balances(&[(&"Staking".to_string(), &balances)]);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::RegisterDelegate {},
+        )
+        .unwrap();
+        let delegate = ExecuteMsg::Delegate {
+            delegate: "loop_staker1".to_string(),
+            percentage: Decimal::one(),
+        };
+        for staker in &stakers[..MAX_DELEGATORS as usize] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(staker, &[]),
+                delegate.clone(),
+            )
+            .unwrap();
+        }
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(&stakers[MAX_DELEGATORS as usize], &[]),
+            delegate.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::TooManyDelegators {
+                max: MAX_DELEGATORS
+            }
+        );
+
+        // delegating again to the same delegate does not take a new slot
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(&stakers[0], &[]),
+            delegate,
+        )
+        .unwrap();
+        let delegates: DelegateListResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ListDelegates {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            delegates.delegates[0].delegated_power,
+            Uint128::from(10 * MAX_DELEGATORS as u128)
+        );
+    }
+}
+
+mod deposit {
+    use super::*;
+    use crate::msg::Cw20HookMsg;
+    use crate::state::{DepositConfig, RefundPolicy};
+    use cosmwasm_std::{coins, Addr};
+    use cw20::Cw20ReceiveMsg;
+    use loopswap::asset::{Asset, AssetInfo};
+
+    fn set_deposit(deps: cosmwasm_std::DepsMut, token: AssetInfo) {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::UpdateDepositConfig {
+                deposit: Some(DepositConfig {
+                    token,
+                    amount: Uint128::from(100u128),
+                    refund_policy: RefundPolicy::PassedOrQuorum {},
+                    treasury: "treasury".to_string(),
+                }),
+            },
+        )
+        .unwrap();
+    }
+
+    fn bank_send(to_address: &str, denom: &str) -> SubMsg {
+        SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: to_address.to_string(),
+            amount: coins(100, denom),
+        }))
+    }
+
+    #[test]
+    fn test_deposit() {
+        let mut deps = instantiate_with_stakers(None);
+        set_deposit(
+            deps.as_mut(),
+            AssetInfo::NativeToken {
+                denom: "uloop".to_string(),
+            },
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &coins(99, "uloop")),
+            propose(ProposalCategory::Text),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidDeposit {
+                deposit: "100uloop".to_string()
+            }
+        );
+        // other denoms are not kept by the contract
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(
+                "loop_staker1",
+                &[Coin::new(100, "uloop"), Coin::new(1, "uusd")],
+            ),
+            propose(ProposalCategory::Text),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::UnexpectedFunds {});
+        for _ in 0..3 {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("loop_staker1", &coins(100, "uloop")),
+                propose(ProposalCategory::Text),
+            )
+            .unwrap();
+        }
+        let res: ProposalResponse = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::Proposal { proposal_id: 1 }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.proposal.deposit,
+            Some(Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uloop".to_string()
+                },
+                amount: Uint128::from(100u128),
+            })
+        );
+
+        // 2 is rejected after reaching quorum, 3 passes
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::Vote {
+                proposal_id: 2,
+                vote: Vote::No,
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker2", &[]),
+            ExecuteMsg::Vote {
+                proposal_id: 3,
+                vote: Vote::Yes,
+            },
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker2", &[]),
+            ExecuteMsg::Execute { proposal_id: 3 },
+        )
+        .unwrap();
+        assert_eq!(res.messages.last(), Some(&bank_send("loop_staker1", "uloop")));
+
+        let expired = mock_env_time(mock_env().block.time.plus_seconds(121));
+        let res = execute(
+            deps.as_mut(),
+            expired.clone(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Close { proposal_id: 1 },
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![bank_send("treasury", "uloop")]);
+        let res = execute(
+            deps.as_mut(),
+            expired,
+            mock_info("anyone", &[]),
+            ExecuteMsg::Close { proposal_id: 2 },
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![bank_send("loop_staker1", "uloop")]);
+
+        // LOOP deposits come in through the cw20 hook
+        set_deposit(
+            deps.as_mut(),
+            AssetInfo::Token {
+                contract_addr: "loop_token".to_string(),
+            },
+        );
+        let hook = match propose(ProposalCategory::Text) {
+            ExecuteMsg::Propose {
+                title,
+                description,
+                msgs,
+                voting_period,
+                category,
+            } => Cw20HookMsg::Propose {
+                title,
+                description,
+                msgs,
+                voting_period,
+                category,
+            },
+            _ => unreachable!(),
+        };
+        let receive = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "loop_staker2".to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&hook).unwrap(),
+        });
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other_token", &[]),
+            receive.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), mock_env(), mock_info("loop_token", &[]), receive).unwrap();
+        let res: ProposalResponse = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::Proposal { proposal_id: 4 }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.proposal.proposer, Addr::unchecked("loop_staker2"));
+        assert_eq!(res.proposal.deposit.unwrap().amount, Uint128::from(100u128));
+
+        // native funds are refused once the deposit is a cw20
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &coins(100, "uloop")),
+            propose(ProposalCategory::Text),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::UnexpectedFunds {});
+    }
+}
+
+mod categories {
+    use super::*;
+    use crate::query::{CategoriesResponse, CategoryInfo};
+    use crate::state::CategoryConfig;
+    use cosmwasm_std::{coins, Binary};
+
+    fn treasury_config() -> CategoryConfig {
+        CategoryConfig {
+            threshold: Threshold::ThresholdQuorum {
+                threshold: PercentageThreshold::Percent(Decimal::percent(67)),
+                quorum: PercentageThreshold::Percent(Decimal::percent(50)),
+            },
+            min_voting_period: Duration::Time(200),
+            max_voting_period: Duration::Time(600),
+            deposit: None,
+        }
+    }
+
+    #[test]
+    fn infer_category() {
+        let execute_msg = |msg: Binary, funds: Vec<Coin>| {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "contract".to_string(),
+                msg,
+                funds,
+            })
+        };
+        let update = to_binary(&ExecuteMsg::UpdateGuardian { guardian: None }).unwrap();
+        let transfer = to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: "receiver".to_string(),
+            amount: Uint128::from(1u128),
+        })
+        .unwrap();
+        let migrate = CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr: "contract".to_string(),
+            new_code_id: 2,
+            msg: update.clone(),
+        });
+
+        assert_eq!(ProposalCategory::from_msgs(&[]), ProposalCategory::Text);
+        assert_eq!(
+            ProposalCategory::from_msgs(&[execute_msg(update.clone(), vec![])]),
+            ProposalCategory::ParameterChange
+        );
+        assert_eq!(
+            ProposalCategory::from_msgs(&[
+                execute_msg(update.clone(), vec![]),
+                execute_msg(transfer, vec![]),
+            ]),
+            ProposalCategory::TreasurySpend
+        );
+        assert_eq!(
+            ProposalCategory::from_msgs(&[execute_msg(update.clone(), coins(1, "uloop"))]),
+            ProposalCategory::TreasurySpend
+        );
+        assert_eq!(
+            ProposalCategory::from_msgs(&[execute_msg(update, vec![]), migrate]),
+            ProposalCategory::ContractUpgrade
+        );
+    }
+
+    #[test]
+    fn explicit_category_cannot_be_lower() {
+        let mut deps = instantiate_with_stakers(None);
+        let migrate = CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr: "contract".to_string(),
+            new_code_id: 2,
+            msg: to_binary(&ExecuteMsg::UpdateGuardian { guardian: None }).unwrap(),
+        });
+
+        for (category, msgs) in [
+            (ProposalCategory::Text, vec![migrate]),
+            (ProposalCategory::Emergency, vec![]),
+        ] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("loop_staker1", &[]),
+                ExecuteMsg::Propose {
+                    title: "test".to_string(),
+                    description: "new".to_string(),
+                    msgs,
+                    voting_period: Duration::Time(120),
+                    category: Some(category),
+                },
+            )
+            .unwrap();
+        }
+
+        // a text proposal migrating a contract is upgraded, a higher
+        // category than the messages need is kept
+        let category = |proposal_id| {
+            let res: ProposalResponse = from_binary(
+                &query(deps.as_ref(), mock_env(), QueryMsg::Proposal { proposal_id }).unwrap(),
+            )
+            .unwrap();
+            res.proposal.category
+        };
+        assert_eq!(category(1), ProposalCategory::ContractUpgrade);
+        assert_eq!(category(2), ProposalCategory::Emergency);
+    }
+
+    #[test]
+    fn test_category_config() {
+        let mut deps = instantiate_with_stakers(None);
+
+        let update = ExecuteMsg::UpdateCategoryConfig {
+            category: ProposalCategory::TreasurySpend,
+            config: Some(treasury_config()),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            update.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), update).unwrap();
+
+        let res: CategoriesResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Categories {}).unwrap())
+                .unwrap();
+        assert!(res.categories.contains(&CategoryInfo {
+            category: ProposalCategory::TreasurySpend,
+            config: treasury_config(),
+        }));
+        assert!(res.categories.contains(&CategoryInfo {
+            category: ProposalCategory::Text,
+            config: CategoryConfig {
+                threshold: Threshold::ThresholdQuorum {
+                    threshold: PercentageThreshold::Majority {},
+                    quorum: PercentageThreshold::Percent(Decimal::percent(1)),
+                },
+                min_voting_period: Duration::Time(5),
+                max_voting_period: Duration::Time(300),
+                deposit: None,
+            },
+        }));
+
+        // the bank send makes this a treasury spend, which must be open
+        // for at least 200 seconds
+        let mut msg = propose(ProposalCategory::Text);
+        if let ExecuteMsg::Propose { category, .. } = &mut msg {
+            *category = None;
+        }
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidVotingPeriod {});
+
+        if let ExecuteMsg::Propose { voting_period, .. } = &mut msg {
+            *voting_period = Duration::Time(250);
+        }
+        execute(deps.as_mut(), mock_env(), mock_info("loop_staker1", &[]), msg).unwrap();
+        let res: ProposalResponse = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::Proposal { proposal_id: 1 }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.proposal.category, ProposalCategory::TreasurySpend);
+        assert_eq!(res.proposal.threshold, treasury_config().threshold);
+
+        // 2000 of 3000 is a majority but not the 67% a treasury spend needs
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker2", &[]),
+            ExecuteMsg::Vote {
+                proposal_id: 1,
+                vote: Vote::Yes,
+            },
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env_time(mock_env().block.time.plus_seconds(251)),
+            mock_info("loop_staker2", &[]),
+            ExecuteMsg::Execute { proposal_id: 1 },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotPassed {});
+    }
+}
+
+mod dao_core {
+    use super::*;
+    use cosmwasm_std::Addr;
+    use loopswap::dao::ExecuteMsg as DaoExecuteMsg;
+
+    #[test]
+    fn test_execute_through_dao_core() {
+        let mut deps = instantiate_with_stakers(None);
+
+        let update = ExecuteMsg::UpdateDaoCore {
+            dao_core: Some("dao_core".to_string()),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            update.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), update).unwrap();
+        disable_timelock(deps.as_mut());
+        let dao_core: Option<Addr> =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::DaoCore {}).unwrap()).unwrap();
+        assert_eq!(dao_core, Some(Addr::unchecked("dao_core")));
+
+        let msg = propose(ProposalCategory::TreasurySpend);
+        let proposal_msgs = match &msg {
+            ExecuteMsg::Propose { msgs, .. } => msgs.clone(),
+            _ => unreachable!(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("loop_staker1", &[]), msg).unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker2", &[]),
+            ExecuteMsg::Vote {
+                proposal_id: 1,
+                vote: Vote::Yes,
+            },
+        )
+        .unwrap();
+
+        // the messages are handed to the DAO core instead of being
+        // executed by the module
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker2", &[]),
+            ExecuteMsg::Execute { proposal_id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "dao_core".to_string(),
+                msg: to_binary(&DaoExecuteMsg::ExecuteProposalHook {
+                    msgs: proposal_msgs
+                })
+                .unwrap(),
+                funds: vec![],
+            }))]
+        );
+    }
+}
+
+mod signature_vote {
+    use super::*;
+    use crate::msg::VotePayload;
+    use crate::query::VoteResponse;
+    use crate::signature::pubkey_to_address;
+    use cosmwasm_std::{
+        Addr, Binary, CanonicalAddr, RecoverPubkeyError, StdResult, VerificationError,
+    };
+    use k256::ecdsa::signature::Signer;
+    use k256::ecdsa::{Signature, SigningKey};
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+    use std::marker::PhantomData;
+
+    /// `MockApi` can't humanize 20 byte addresses like the chain does.
+    struct ChainApi(MockApi);
+
+    impl Api for ChainApi {
+        fn addr_validate(&self, human: &str) -> StdResult<Addr> {
+            self.0.addr_validate(human)
+        }
+        fn addr_canonicalize(&self, human: &str) -> StdResult<CanonicalAddr> {
+            self.0.addr_canonicalize(human)
+        }
+        fn addr_humanize(&self, canonical: &CanonicalAddr) -> StdResult<Addr> {
+            if canonical.len() == 20 {
+                return Ok(Addr::unchecked(format!(
+                    "loop{}",
+                    canonical.to_string().to_lowercase()
+                )));
+            }
+            self.0.addr_humanize(canonical)
+        }
+        fn secp256k1_verify(
+            &self,
+            message_hash: &[u8],
+            signature: &[u8],
+            public_key: &[u8],
+        ) -> Result<bool, VerificationError> {
+            self.0.secp256k1_verify(message_hash, signature, public_key)
+        }
+        fn secp256k1_recover_pubkey(
+            &self,
+            message_hash: &[u8],
+            signature: &[u8],
+            recovery_param: u8,
+        ) -> Result<Vec<u8>, RecoverPubkeyError> {
+            self.0
+                .secp256k1_recover_pubkey(message_hash, signature, recovery_param)
+        }
+        fn ed25519_verify(
+            &self,
+            message: &[u8],
+            signature: &[u8],
+            public_key: &[u8],
+        ) -> Result<bool, VerificationError> {
+            self.0.ed25519_verify(message, signature, public_key)
+        }
+        fn ed25519_batch_verify(
+            &self,
+            messages: &[&[u8]],
+            signatures: &[&[u8]],
+            public_keys: &[&[u8]],
+        ) -> Result<bool, VerificationError> {
+            self.0.ed25519_batch_verify(messages, signatures, public_keys)
+        }
+        fn debug(&self, message: &str) {
+            self.0.debug(message)
+        }
+    }
+
+    fn sign(key: &SigningKey, proposal_id: u64, vote: Vote, nonce: u64) -> ExecuteMsg {
+        let env = mock_env();
+        let payload = cosmwasm_std::to_vec(&VotePayload {
+            chain_id: env.block.chain_id,
+            contract: env.contract.address.to_string(),
+            proposal_id,
+            vote: vote.clone(),
+            nonce,
+        })
+        .unwrap();
+        let signature: Signature = key.sign(&payload);
+        ExecuteMsg::VoteBySignature {
+            proposal_id,
+            vote,
+            voter_pubkey: Binary::from(key.verifying_key().to_bytes().as_slice()),
+            signature: Binary::from(signature.as_ref()),
+            nonce,
+        }
+    }
+
+    #[test]
+    fn test_vote_by_signature() {
+        let deps = instantiate_with_stakers(None);
+        let mut deps = OwnedDeps {
+            storage: deps.storage,
+            api: ChainApi(deps.api),
+            querier: deps.querier,
+            custom_query_type: PhantomData,
+        };
+
+        let key = SigningKey::from_bytes(&[7u8; 32]).unwrap();
+        let voter =
+            pubkey_to_address(&deps.api, key.verifying_key().to_bytes().as_slice()).unwrap();
+        deps.querier.with_token_balances(&[(
+            &"Staking".to_string(),
+            &[
+                (&"loop_staker1".to_string(), &Uint128::from(1000u128)),
+                (&voter.to_string(), &Uint128::from(500u128)),
+            ],
+        )]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            propose(ProposalCategory::Text),
+        )
+        .unwrap();
+
+        // anyone can relay the signed vote, it is cast for the signer
+        let signed = sign(&key, 1, Vote::No, 1);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("relayer", &[]),
+            signed.clone(),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .contains(&cosmwasm_std::attr("sender", voter.to_string())));
+        let vote: VoteResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetVote {
+                    proposal_id: 1,
+                    voter: voter.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let vote = vote.vote.unwrap();
+        assert_eq!(vote.vote, Vote::No);
+        assert_eq!(vote.power, Uint128::from(500u128));
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), signed)
+            .unwrap_err();
+        assert_eq!(err, ContractError::InvalidNonce { last_nonce: 1 });
+
+        // the signature does not cover another vote
+        let mut forged = sign(&key, 1, Vote::No, 2);
+        if let ExecuteMsg::VoteBySignature { vote, .. } = &mut forged {
+            *vote = Vote::Yes;
+        }
+        let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), forged)
+            .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSignature {});
+
+        let mut uncompressed = sign(&key, 1, Vote::No, 2);
+        if let ExecuteMsg::VoteBySignature { voter_pubkey, .. } = &mut uncompressed {
+            *voter_pubkey = Binary::from(key.verifying_key().to_encoded_point(false).as_bytes());
+        }
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("relayer", &[]),
+            uncompressed,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidPubkey {});
+    }
+}
+
+mod optimistic {
+    use super::*;
+    use crate::state::OptimisticConfig;
+    use crate::status::Status;
+
+    fn optimistic_config(proposers: Vec<String>) -> OptimisticConfig {
+        OptimisticConfig {
+            proposers,
+            objection_threshold: Threshold::AbsolutePercentage {
+                percentage: PercentageThreshold::Percent(Decimal::percent(50)),
+            },
+        }
+    }
+
+    fn propose_optimistic() -> ExecuteMsg {
+        match propose(ProposalCategory::ParameterChange) {
+            ExecuteMsg::Propose {
+                title,
+                description,
+                msgs,
+                voting_period,
+                category,
+            } => ExecuteMsg::ProposeOptimistic {
+                title,
+                description,
+                msgs,
+                voting_period,
+                category,
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    fn status(deps: cosmwasm_std::Deps, env: Env, proposal_id: u64) -> Status {
+        let res: ProposalResponse =
+            from_binary(&query(deps, env, QueryMsg::Proposal { proposal_id }).unwrap()).unwrap();
+        res.proposal.status
+    }
+
+    #[test]
+    fn majority_objection_counts_total_power() {
+        let mut deps = instantiate_with_stakers(None);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::UpdateOptimisticConfig {
+                optimistic: Some(OptimisticConfig {
+                    proposers: vec!["farming_bot".to_string()],
+                    objection_threshold: Threshold::AbsolutePercentage {
+                        percentage: PercentageThreshold::Majority {},
+                    },
+                }),
+            },
+        )
+        .unwrap();
+        for _ in 0..2 {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("farming_bot", &[]),
+                propose_optimistic(),
+            )
+            .unwrap();
+        }
+
+        // the only objector holds 1000 of 3000, which is all votes cast
+        // but not a majority of the total power
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::Vote {
+                proposal_id: 1,
+                vote: Vote::NoWithVeto,
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker2", &[]),
+            ExecuteMsg::Vote {
+                proposal_id: 2,
+                vote: Vote::NoWithVeto,
+            },
+        )
+        .unwrap();
+        let expired = mock_env_time(mock_env().block.time.plus_seconds(121));
+        assert_eq!(status(deps.as_ref(), expired.clone(), 1), Status::Passed);
+        assert_eq!(status(deps.as_ref(), expired, 2), Status::Rejected);
+    }
+
+    #[test]
+    fn test_optimistic_proposal() {
+        let mut deps = instantiate_with_stakers(None);
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("farming_bot", &[]),
+            propose_optimistic(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::UpdateOptimisticConfig {
+                optimistic: Some(optimistic_config(vec![])),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidOptimisticConfig {});
+        let update = ExecuteMsg::UpdateOptimisticConfig {
+            optimistic: Some(optimistic_config(vec!["farming_bot".to_string()])),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            update.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), update).unwrap();
+        disable_timelock(deps.as_mut());
+        let config: Option<OptimisticConfig> = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::OptimisticConfig {}).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            Some(optimistic_config(vec!["farming_bot".to_string()]))
+        );
+
+        // only whitelisted proposers
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker1", &[]),
+            propose_optimistic(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        for _ in 0..2 {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("farming_bot", &[]),
+                propose_optimistic(),
+            )
+            .unwrap();
+        }
+
+        // 1000 of 3000 objecting stays below the threshold, 2000 reaches it
+        for (voter, proposal_id) in [("loop_staker1", 1), ("loop_staker2", 2)] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(voter, &[]),
+                ExecuteMsg::Vote {
+                    proposal_id,
+                    vote: Vote::NoWithVeto,
+                },
+            )
+            .unwrap();
+        }
+        assert_eq!(status(deps.as_ref(), mock_env(), 1), Status::Open);
+        assert_eq!(status(deps.as_ref(), mock_env(), 2), Status::Open);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_staker2", &[]),
+            ExecuteMsg::Execute { proposal_id: 1 },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::VotingNotExpired {});
+
+        let expired = mock_env_time(mock_env().block.time.plus_seconds(121));
+        assert_eq!(status(deps.as_ref(), expired.clone(), 1), Status::Passed);
+        assert_eq!(status(deps.as_ref(), expired.clone(), 2), Status::Rejected);
+
+        let res = execute(
+            deps.as_mut(),
+            expired.clone(),
+            mock_info("loop_staker2", &[]),
+            ExecuteMsg::Execute { proposal_id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "receiver".to_string(),
+                amount: vec![Coin {
+                    amount: Uint128::from(2u128),
+                    denom: "uusd".to_string(),
+                }],
+            }))]
+        );
+        execute(
+            deps.as_mut(),
+            expired.clone(),
+            mock_info("loop_staker1", &[]),
+            ExecuteMsg::Close { proposal_id: 2 },
+        )
+        .unwrap();
+        assert_eq!(status(deps.as_ref(), expired, 2), Status::Closed);
+    }
 }
@@ -4,6 +4,7 @@ use cw_utils::Duration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::error::ContractError;
 use crate::threshold::PercentageThreshold;
 
 // We multiply by this when calculating needed_votes in order to round
@@ -16,6 +17,9 @@ pub struct Votes {
     pub yes: Uint128,
     pub no: Uint128,
     pub abstain: Uint128,
+    /// Opposition that also objects to optimistic proposals.
+    #[serde(default)]
+    pub no_with_veto: Uint128,
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
@@ -28,6 +32,9 @@ pub enum Vote {
     /// Marks participation but does not count towards the ratio of
     /// support / opposed.
     Abstain,
+    /// Marks opposition to the proposal. Rejects an optimistic
+    /// proposal once enough power objects this way.
+    NoWithVeto,
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
@@ -163,6 +170,7 @@ impl Votes {
             yes: Uint128::zero(),
             no: Uint128::zero(),
             abstain: Uint128::zero(),
+            no_with_veto: Uint128::zero(),
         }
     }
 
@@ -174,6 +182,7 @@ impl Votes {
             yes,
             no: Uint128::zero(),
             abstain: Uint128::zero(),
+            no_with_veto: Uint128::zero(),
         }
     }
 
@@ -183,6 +192,7 @@ impl Votes {
             Vote::Yes => self.yes += power,
             Vote::No => self.no += power,
             Vote::Abstain => self.abstain += power,
+            Vote::NoWithVeto => self.no_with_veto += power,
         }
     }
 
@@ -194,6 +204,7 @@ impl Votes {
             Vote::Yes => self.yes -= power,
             Vote::No => self.no -= power,
             Vote::Abstain => self.abstain -= power,
+            Vote::NoWithVeto => self.no_with_veto -= power,
         }
     }
 
@@ -205,7 +216,7 @@ impl Votes {
     /// `Uint128` from this function. A missbehaving voting power
     /// module may break this invariant.
     pub fn total(&self) -> Uint128 {
-        self.yes + self.no + self.abstain
+        self.yes + self.no + self.abstain + self.no_with_veto
     }
 }
 
@@ -215,6 +226,7 @@ impl std::fmt::Display for Vote {
             Vote::Yes => write!(f, "yes"),
             Vote::No => write!(f, "no"),
             Vote::Abstain => write!(f, "abstain"),
+            Vote::NoWithVeto => write!(f, "no_with_veto"),
         }
     }
 }
@@ -246,17 +258,20 @@ impl std::fmt::Display for Vote {
 
 /// Validates that the min voting period is less than the max voting
 /// period. Passes arguments through the function.
-pub fn validate_voting_period(min: Duration, max: Duration) -> StdResult<(Duration, Duration)> {
+pub fn validate_voting_period(
+    min: Duration,
+    max: Duration,
+) -> Result<(Duration, Duration), ContractError> {
     let min = {
         let valid = match (min, max) {
             (Duration::Time(min), Duration::Time(max)) => min <= max,
             (Duration::Height(min), Duration::Height(max)) => min <= max,
-            _ => return Err(StdError::generic_err("Duration Minutes conflict")),
+            _ => return Err(ContractError::VotingPeriodUnitMismatch {}),
         };
         if valid {
             min
         } else {
-            return Err(StdError::generic_err("Invalid voting period"));
+            return Err(ContractError::InvalidVotingPeriod {});
         }
     };
 
@@ -1,3 +1,4 @@
+use crate::error::ContractError;
 use crate::minter::{
     execute_mint, query_balance, query_balance_by_duration, query_minter, query_token_info,
     query_total_balance, update_token_info,
@@ -31,7 +32,7 @@ pub fn instantiate(
     env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config = Config {
         owner_addr: info.clone().sender,
         token_addr: deps.api.addr_validate(&msg.token)?,
@@ -72,7 +73,12 @@ pub fn instantiate(
 
 //Execute the handle messages.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::UpdateConfig { owner } => execute_update_config(deps, env, info, owner),
         ExecuteMsg::UpdateSecondOwner { second_owner } => {
@@ -82,7 +88,7 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             execute_update_community_addr(deps, env, info, community_addr)
         }
         ExecuteMsg::UpdateTokenInfo { name, symbol } => {
-            update_token_info(deps, env, info, name, symbol)
+            Ok(update_token_info(deps, env, info, name, symbol)?)
         }
         ExecuteMsg::UpdateReward { amount } => execute_update_reward(deps, env, info, amount),
         ExecuteMsg::Distribute {} => execute_distribute(deps, env, info),
@@ -130,10 +136,10 @@ pub fn execute_deposit_in_vault_address(
     env: Env,
     info: MessageInfo,
     amount: Uint128,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
     if info.sender != config.owner_addr {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
     let callback: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
         //sending reward to user
@@ -155,7 +161,7 @@ pub fn receive_cw20(
     env: Env,
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let contract_addr = info.sender.to_string();
     // let contract_addr = cw20_msg.sender.clone();
     match from_binary(&cw20_msg.msg)? {
@@ -169,7 +175,7 @@ pub fn receive_cw20(
             }
 
             if !authorized {
-                return Err(StdError::generic_err("unauthorized"));
+                return Err(ContractError::Unauthorized {});
             }
 
             execute_stake(
@@ -190,7 +196,7 @@ pub fn receive_cw20(
             }
 
             if !authorized {
-                return Err(StdError::generic_err("unauthorized"));
+                return Err(ContractError::Unauthorized {});
             }
             execute_deposit(deps, env, cw20_msg.amount)
         }
@@ -203,12 +209,12 @@ pub fn execute_update_config(
     _env: Env,
     info: MessageInfo,
     owner: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
     if info.sender != config.owner_addr {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     config.owner_addr = deps.api.addr_validate(&owner)?;
@@ -223,12 +229,12 @@ pub fn execute_update_second_owner(
     _env: Env,
     info: MessageInfo,
     second_owner: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
     if info.sender != config.owner_addr {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     config.second_owner = Some(deps.api.addr_validate(&second_owner)?.to_string());
@@ -242,12 +248,12 @@ pub fn execute_update_community_addr(
     _env: Env,
     info: MessageInfo,
     community_addr: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
     if info.sender != config.owner_addr {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     config.community_addr = Some(deps.api.addr_validate(&community_addr)?);
@@ -262,12 +268,12 @@ pub fn execute_update_freeze_flag(
     env: Env,
     info: MessageInfo,
     freeze_flag: bool,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
     if info.sender != config.owner_addr {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     config.freeze = freeze_flag;
@@ -284,12 +290,12 @@ pub fn execute_update_loop_power_constant(
     deps: DepsMut,
     info: MessageInfo,
     loop_power_constant: u128,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
     if info.sender != config.owner_addr {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     config.loop_power_constant = loop_power_constant;
@@ -305,12 +311,12 @@ pub fn execute_update_freeze_lock_time(
     _env: Env,
     info: MessageInfo,
     freeze_lock_time: u64,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
     if info.sender != config.owner_addr {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     config.freeze_lock_time = freeze_lock_time;
@@ -325,12 +331,12 @@ pub fn execute_update_lock_time_frame(
     _env: Env,
     info: MessageInfo,
     lock_time_frame: u64,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
     if info.sender != config.owner_addr {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     config.lock_time_frame = lock_time_frame;
@@ -344,12 +350,12 @@ pub fn execute_add_new_duration(
     deps: DepsMut,
     info: MessageInfo,
     duration: u64,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
     if info.sender != config.owner_addr {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
     if !config.duration_values_vector.contains(&duration) {
         config.duration_values_vector.push(duration);
@@ -364,12 +370,12 @@ pub fn execute_update_wait_time_for_distributions(
     _env: Env,
     info: MessageInfo,
     wait_time_for_distribution_in_seconds: u64,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
     if info.sender != config.owner_addr {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
     config.wait_time_for_distribution_in_seconds = wait_time_for_distribution_in_seconds;
     CONFIG.save(deps.storage, &config)?;
@@ -383,12 +389,12 @@ pub fn execute_update_restake_reset_flag(
     _env: Env,
     info: MessageInfo,
     flag: bool,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
     if info.sender != config.owner_addr {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
     config.restake_reset_flag = flag;
@@ -398,7 +404,11 @@ pub fn execute_update_restake_reset_flag(
 }
 
 // Allow admin to deposit reward tokens.
-pub fn execute_deposit(deps: DepsMut, _env: Env, amount: Uint128) -> StdResult<Response> {
+pub fn execute_deposit(
+    deps: DepsMut,
+    _env: Env,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
     let mut total_reward_in_contract = TOTAL_REWARD_IN_CONTRACT.load(deps.storage)?;
     total_reward_in_contract += amount;
     TOTAL_REWARD_IN_CONTRACT.save(deps.storage, &total_reward_in_contract)?;
@@ -413,7 +423,7 @@ pub fn execute_stake(
     sender: Addr,
     amount: Uint128,
     duration: u64,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let current_reward_index = REWARD_INDEX.load(deps.storage)?;
     let key: String = sender.to_string();
     let mut config = CONFIG.load(deps.storage)?;
@@ -426,9 +436,7 @@ pub fn execute_stake(
             config.latest_loop_power_date + config.day_factor_in_seconds * previous_days;
     }
     if !config.duration_values_vector.contains(&duration) {
-        return Err(StdError::generic_err(
-            "Invalid duration parameter is passed",
-        ));
+        return Err(ContractError::InvalidDuration {});
     }
     //reward token issued to be used during evaluation of user share in queries
     let user_info = MINT_TIME
@@ -540,7 +548,7 @@ pub fn execute_restake(
     env: Env,
     sender: Addr,
     duration: u64,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
     let current_reward_index = REWARD_INDEX.load(deps.storage)?;
     let key: String = sender.to_string();
@@ -578,7 +586,7 @@ pub fn execute_restake(
     user_reward_info.reward_index = current_reward_index.pool_reward_index;
     let reward_to_add = user_reward_response.user_reward + user_reward_info.pending_reward;
     if reward_to_add.is_zero() {
-        return Err(StdError::generic_err("None reward for restaking."));
+        return Err(ContractError::NoRestakeReward {});
     }
 
     TOTAL_REWARD.update(deps.storage, |mut reward| -> StdResult<_> {
@@ -655,15 +663,13 @@ pub fn execute_unstake(
     env: Env,
     receiver: Addr,
     duration: u64,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
     if config.clone().freeze
         && env.block.time.seconds()
             < config.clone().freeze_start_time + config.clone().freeze_lock_time
     {
-        return Err(StdError::generic_err(
-            "Sorry for inconvenience, system is under maintenance. Kindly check again later",
-        ));
+        return Err(ContractError::Frozen {});
     }
 
     let key: String = receiver.to_string();
@@ -672,13 +678,11 @@ pub fn execute_unstake(
         if let Some(user_info) = MINT_TIME.may_load(deps.storage, (&receiver, duration))? {
             user_info
         } else {
-            return Err(StdError::generic_err("No staked amount found"));
+            return Err(ContractError::NoStakedAmount {});
         };
 
     if env.block.time.seconds() - user_info.mint_time < config.clone().lock_time_frame * duration {
-        return Err(StdError::generic_err(
-            "The rewards are still locked. Please wait patiently for the specified time",
-        ));
+        return Err(ContractError::RewardsLocked {});
     }
 
     let contract_addr = config.clone().token_addr;
@@ -803,15 +807,13 @@ pub fn execute_claim(
     env: Env,
     receiver: Addr,
     duration: u64,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
     if config.clone().freeze
         && env.block.time.seconds()
             < config.clone().freeze_start_time + config.clone().freeze_lock_time
     {
-        return Err(StdError::generic_err(
-            "Sorry for inconvenience, system is under maintenance. Kindly check again later",
-        ));
+        return Err(ContractError::Frozen {});
     }
 
     let key: String = receiver.to_string();
@@ -820,7 +822,7 @@ pub fn execute_claim(
         if let Some(user_info) = MINT_TIME.may_load(deps.storage, (&receiver, duration))? {
             user_info
         } else {
-            return Err(StdError::generic_err("No Staked Amount found"));
+            return Err(ContractError::NoStakedAmount {});
         };
 
     let contract_addr = config.clone().token_addr;
@@ -931,7 +933,11 @@ pub fn execute_claim(
 }
 
 //This will distribute the reward.
-pub fn execute_distribute(deps: DepsMut, env: Env, _info: MessageInfo) -> StdResult<Response> {
+pub fn execute_distribute(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
     //let mut total_staked = TOTAL_ACTIVE_STAKED.load(deps.storage)?;
 
@@ -945,9 +951,7 @@ pub fn execute_distribute(deps: DepsMut, env: Env, _info: MessageInfo) -> StdRes
         + config.wait_time_for_distribution_in_seconds * non_distributed_days;
     // distribution_reward = distribution_reward.multiply_ratio(non_distributed_days, 1u64);
     if non_distributed_days <= 0 {
-        return Err(StdError::generic_err(
-            "please wait patiently for the specified time",
-        ));
+        return Err(ContractError::DistributionNotReady {});
     }
     let previous_days =
         (env.block.time.seconds() - config.latest_loop_power_date) / config.day_factor_in_seconds;
@@ -1026,7 +1030,7 @@ pub fn execute_distribute(deps: DepsMut, env: Env, _info: MessageInfo) -> StdRes
                 REWARD_INDEX.save(deps.storage, &reward_index)?;
                 TOTAL_REWARD.save(deps.storage, &total_reward)?;
             } else {
-                return Err(StdError::generic_err("insufficient funds"));
+                return Err(ContractError::InsufficientFunds {});
             }
 
             if config.latest_loop_power_date + config.day_factor_in_seconds
@@ -1064,14 +1068,14 @@ pub fn execute_update_reward(
     _env: Env,
     info: MessageInfo,
     amount: Uint128,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
     if info.sender != config.owner_addr
         && (config.second_owner.clone().unwrap().is_empty()
             || (!config.second_owner.clone().unwrap().is_empty()
                 && info.sender != config.clone().second_owner.unwrap()))
     {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
     DISTRIBUTION_REWARD.save(deps.storage, &amount)?;
 
@@ -1082,10 +1086,10 @@ pub fn execute_update_day_factor_in_seconds(
     deps: DepsMut,
     info: MessageInfo,
     day_factor_in_seconds: u64,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
     if info.sender != config.owner_addr {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
     config.day_factor_in_seconds = day_factor_in_seconds;
     CONFIG.save(deps.storage, &config)?;
@@ -1596,6 +1600,6 @@ pub fn query_config(deps: Deps) -> StdResult<Config> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     Ok(Response::default())
 }
\ No newline at end of file
@@ -9,30 +9,24 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
-    #[error("Cannot set to own account")]
-    CannotSetOwnAccount {},
+    #[error("Invalid duration parameter is passed")]
+    InvalidDuration {},
 
-    #[error("Invalid zero amount")]
-    InvalidZeroAmount {},
+    #[error("None reward for restaking")]
+    NoRestakeReward {},
 
-    #[error("Allowance is expired")]
-    Expired {},
+    #[error("Sorry for inconvenience, system is under maintenance. Kindly check again later")]
+    Frozen {},
 
-    #[error("No allowance for this account")]
-    NoAllowance {},
+    #[error("No staked amount found")]
+    NoStakedAmount {},
 
-    #[error("Minting cannot exceed the cap")]
-    CannotExceedCap {},
+    #[error("The rewards are still locked. Please wait patiently for the specified time")]
+    RewardsLocked {},
 
-    #[error("Logo binary data exceeds 5KB limit")]
-    LogoTooBig {},
+    #[error("Please wait patiently for the specified time")]
+    DistributionNotReady {},
 
-    #[error("Invalid xml preamble for SVG")]
-    InvalidXmlPreamble {},
-
-    #[error("Invalid png header")]
-    InvalidPngHeader {},
-
-    #[error("Duplicate initial balance addresses")]
-    DuplicateInitialBalanceAddresses {},
+    #[error("Insufficient funds")]
+    InsufficientFunds {},
 }
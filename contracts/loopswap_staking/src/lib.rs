@@ -3,5 +3,7 @@ pub mod minter;
 pub mod msg;
 pub mod state;
 
+mod error;
+
 #[cfg(test)]
 mod testing;
@@ -1,11 +1,12 @@
 use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
 use crate::msg::{
     Cw20HookMsg, Cw20QueryMsg, ExecuteMsg, InstantiateMsg, QueryMsg, TokenInstantiateMsg,
 };
 use crate::state::UserRewardResponse;
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{from_binary, Env, Timestamp, Uint128};
-use cosmwasm_std::{to_binary, CosmosMsg, StdError, SubMsg, WasmMsg};
+use cosmwasm_std::{to_binary, CosmosMsg, SubMsg, WasmMsg};
 use cw20::{BalanceResponse, Cw20Coin, Cw20ReceiveMsg, MinterResponse};
 use loopswap::mock_querier::mock_dependencies;
 
@@ -91,7 +92,7 @@ mod tests {
             update_config_msg.clone(),
         );
         match res {
-            Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+            Err(ContractError::Unauthorized {}) => {}
             _ => panic!("Invalid error"),
         }
 
@@ -145,6 +146,19 @@ mod tests {
             amount: Uint128::from(100u128),
         });
 
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("loop_token", &[]),
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "loop_staker1".to_string(),
+                msg: to_binary(&Cw20HookMsg::Stake { duration: 2u64 }).unwrap(),
+                amount: Uint128::from(100u128),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidDuration {});
+
         let _ = execute(
             deps.as_mut(),
             mock_env(),
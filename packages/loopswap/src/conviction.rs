@@ -0,0 +1,125 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::asset::AssetInfo;
+
+use cosmwasm_std::{Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Token holders stake on the requests they support
+    pub lopo_token: String,
+    /// Asset requests are paid in, out of the balance of this contract
+    pub funding_asset: AssetInfo,
+    /// Share of the conviction kept every second, between 0 and 1
+    pub decay: Decimal,
+    /// Largest share of the funds a single request can ask for, below 1
+    pub max_ratio: Decimal,
+    /// Share of the lopo supply a request asking for nothing needs as steady support
+    pub weight: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    CreateRequest {
+        title: String,
+        description: String,
+        beneficiary: String,
+        amount: Uint128,
+    },
+    /// Returns staked lopo to the sender, all of it when `amount` is not set
+    WithdrawSupport {
+        request_id: u64,
+        amount: Option<Uint128>,
+    },
+    /// Anyone can pay out a request whose conviction exceeds its threshold
+    ExecuteRequest {
+        request_id: u64,
+    },
+    /// Only the proposer or the owner, supporters can withdraw afterwards
+    CancelRequest {
+        request_id: u64,
+    },
+    UpdateConfig {
+        owner: Option<String>,
+        decay: Option<Decimal>,
+        max_ratio: Option<Decimal>,
+        weight: Option<Decimal>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Stakes the sent lopo on an open request
+    StakeSupport { request_id: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    /// Request with its conviction and threshold at the current block
+    Request {
+        request_id: u64,
+    },
+    /// Requests ordered by id
+    Requests {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Lopo a staker has staked on a request
+    Support {
+        request_id: u64,
+        staker: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub lopo_token: String,
+    pub funding_asset: AssetInfo,
+    pub decay: Decimal,
+    pub max_ratio: Decimal,
+    pub weight: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestStatus {
+    Open,
+    Executed,
+    Cancelled,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RequestResponse {
+    pub id: u64,
+    pub title: String,
+    pub description: String,
+    pub proposer: String,
+    pub beneficiary: String,
+    pub amount: Uint128,
+    pub status: RequestStatus,
+    pub total_support: Uint128,
+    pub conviction: Uint128,
+    /// Conviction to exceed, none while the request asks for `max_ratio` of the funds or more
+    pub threshold: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RequestsResponse {
+    pub requests: Vec<RequestResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SupportResponse {
+    pub amount: Uint128,
+}
@@ -0,0 +1,75 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::asset::{Asset, AssetInfo};
+
+use cosmwasm_std::{CosmosMsg, Empty};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub name: String,
+    /// Proposal modules allowed to execute messages through the DAO
+    pub proposal_modules: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Executes the messages of a passed proposal as the DAO,
+    /// only callable by an enabled proposal module
+    ExecuteProposalHook { msgs: Vec<CosmosMsg<Empty>> },
+    /// Only callable by the DAO itself, at least one module must stay enabled
+    UpdateProposalModules {
+        to_add: Vec<String>,
+        to_disable: Vec<String>,
+    },
+    /// Only callable by the DAO itself
+    UpdateConfig { name: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    /// Proposal modules ordered by address
+    ProposalModules {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Treasury balances of the given assets
+    Treasury {
+        asset_infos: Vec<AssetInfo>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ConfigResponse {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalModuleStatus {
+    Enabled,
+    /// Kept so past executions stay traceable, can be enabled again
+    Disabled,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ProposalModuleResponse {
+    pub address: String,
+    pub status: ProposalModuleStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ProposalModulesResponse {
+    pub proposal_modules: Vec<ProposalModuleResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct TreasuryResponse {
+    pub balances: Vec<Asset>,
+}
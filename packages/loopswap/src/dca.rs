@@ -0,0 +1,112 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::asset::{Asset, AssetInfo};
+
+use cosmwasm_std::{Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Factory used to find the pair of a schedule
+    pub loopswap_factory: String,
+    /// Share of every execution paid to the keeper who triggers it
+    pub keeper_tip_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    /// Escrow the native `budget` and swap `amount_per_swap` of it into `ask_asset_info`
+    /// every `interval` seconds, the first swap is due at `start_time` or right away.
+    /// Every swap returns at least `1 / belief_price` per offered unit less `max_spread`
+    CreateSchedule {
+        budget: Asset,
+        ask_asset_info: AssetInfo,
+        amount_per_swap: Uint128,
+        interval: u64,
+        belief_price: Decimal,
+        max_spread: Decimal,
+        start_time: Option<u64>,
+    },
+    /// Anyone can execute a due schedule and earns the keeper tip
+    ExecuteSchedule {
+        id: u64,
+    },
+    /// Owner of the schedule gets the unspent budget back
+    CancelSchedule {
+        id: u64,
+    },
+    /// Owner of the schedule moves its price limit
+    UpdateSchedule {
+        id: u64,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+    },
+    UpdateConfig {
+        owner: Option<String>,
+        loopswap_factory: Option<String>,
+        keeper_tip_rate: Option<Decimal>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// CreateSchedule with the sent token as budget
+    CreateSchedule {
+        ask_asset_info: AssetInfo,
+        amount_per_swap: Uint128,
+        interval: u64,
+        belief_price: Decimal,
+        max_spread: Decimal,
+        start_time: Option<u64>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Schedule {
+        id: u64,
+    },
+    /// Schedules ordered by id, only the ones of `owner` when given
+    Schedules {
+        owner: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub loopswap_factory: String,
+    pub keeper_tip_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ScheduleResponse {
+    pub id: u64,
+    pub owner: String,
+    pub offer_asset_info: AssetInfo,
+    pub ask_asset_info: AssetInfo,
+    /// Unspent budget still escrowed
+    pub remaining: Uint128,
+    pub amount_per_swap: Uint128,
+    pub interval: u64,
+    pub belief_price: Decimal,
+    pub max_spread: Decimal,
+    pub next_execution_time: u64,
+    pub executions: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SchedulesResponse {
+    pub schedules: Vec<ScheduleResponse>,
+}
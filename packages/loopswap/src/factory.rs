@@ -3,6 +3,8 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::asset::{AssetInfo, PairInfo};
+use crate::lbp::LbpInfo;
+use cosmwasm_std::{Binary, Decimal};
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct InstantiateMsg {
     /// Pair contract code ID, which is used to
@@ -10,7 +12,8 @@ pub struct InstantiateMsg {
     pub token_code_id: u64,
     pub extra_commission_contract_addr: Option<String>,
     pub admin: String,
-
+    /// LBP contract code ID, required to create liquidity bootstrapping pools
+    pub lbp_code_id: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -22,6 +25,7 @@ pub enum ExecuteMsg {
         token_code_id: Option<u64>,
         pair_code_id: Option<u64>,
         admin: Option<String>,
+        lbp_code_id: Option<u64>,
     },
     /// CreatePair instantiates pair contract
     CreatePair {
@@ -30,7 +34,40 @@ pub enum ExecuteMsg {
         /// stable air
         is_stable_pair: bool,
     },
-    
+    /// CreateLbp instantiates a liquidity bootstrapping pool, only the factory owner can
+    /// execute it so a project's single LBP slot can't be squatted
+    CreateLbp {
+        asset_infos: [AssetInfo; 2],
+        /// Owner of the pool, the project running the sale. Defaults to the sender
+        owner: Option<String>,
+        start_time: u64,
+        end_time: u64,
+        start_weights: [Decimal; 2],
+        end_weights: [Decimal; 2],
+        commission_rate: Decimal,
+    },
+    /// Pause a page of pairs, walk `start_after` to cover every pair
+    PauseAllPairs {
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+    },
+    /// Unpause a page of pairs
+    UnpauseAllPairs {
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+    },
+    /// Migrate a page of pairs to `code_id`, walk `start_after` to cover every pair.
+    /// Pairs already reporting `version` through cw2 are skipped.
+    MigratePairs {
+        code_id: u64,
+        /// Pair MigrateMsg
+        msg: Binary,
+        version: Option<String>,
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+    },
+    /// Move the wasm admin of a pair the factory is admin of to `admin`
+    UpdatePairAdmin { pair: String, admin: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -44,6 +81,15 @@ pub enum QueryMsg {
         start_after: Option<[AssetInfo; 2]>,
         limit: Option<u32>,
     },
+    Lbp {
+        asset_infos: [AssetInfo; 2],
+    },
+    Lbps {
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+    },
+    /// Progress of the last MigratePairs run
+    PairMigration {},
 }
 
 // We define a custom struct for each query response
@@ -53,6 +99,7 @@ pub struct ConfigResponse {
     pub pair_code_id: u64,
     pub token_code_id: u64,
     pub admin: String,
+    pub lbp_code_id: Option<u64>,
 }
 
 /// We currently take no arguments for migrations
@@ -64,3 +111,21 @@ pub struct MigrateMsg {}
 pub struct PairsResponse {
     pub pairs: Vec<PairInfo>,
 }
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct LbpsResponse {
+    pub lbps: Vec<LbpInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PairMigrationResponse {
+    pub code_id: u64,
+    pub version: Option<String>,
+    pub migrated: u64,
+    pub skipped: u64,
+    /// Pair contracts whose migration failed
+    pub failed: Vec<String>,
+    /// Last pair processed, the `start_after` of the next page
+    pub last_pair: Option<[AssetInfo; 2]>,
+}
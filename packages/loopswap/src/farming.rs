@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::asset::StakeableToken;
 
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Decimal, Uint128};
 use cw20::Cw20ReceiveMsg;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -61,6 +61,30 @@ pub enum ExecuteMsg {
     ClaimReward {
         pool_address: String,
     },
+    /// Claims the rewards of every pool the sender has staked in, skipping pools still locked
+    ClaimAll {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Staking contract whose voting power boosts rewards, None turns boosting off
+    UpdateBoostStakingContract {
+        staking_contract: Option<String>,
+    },
+    /// Lowers the working amount of a user whose voting power decayed
+    Kick {
+        user: String,
+        pool_address: String,
+    },
+    /// Adds or replaces a lock tier stakers can pick, the multiplier must be at least 1
+    UpdateLockTier {
+        tier: u64,
+        lock_period: u64,
+        multiplier: Decimal,
+    },
+    /// Existing stakes keep the multiplier and unlock time they were made with
+    RemoveLockTier {
+        tier: u64,
+    },
     
     OptForAutoCompound {
         pool_address: String,
@@ -68,6 +92,15 @@ pub enum ExecuteMsg {
     UpdateReserveAddress {
         reserve_addr: String,
     },
+    /// Rewrites the next `limit` legacy storage entries after a migration from the string key
+    /// layout, the only message accepted until it is done. `pool_dists` are (pool, distribution
+    /// token) pairs to match on top of the current ones, e.g. deleted distribution tokens.
+    /// Passing them restarts the run so skipped entries are matched again. A run with skipped
+    /// entries stays open until it is called again without `pool_dists`, which drops them
+    MigrateKeys {
+        pool_dists: Option<Vec<(String, String)>>,
+        limit: Option<u32>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -127,6 +160,38 @@ pub enum QueryMsg {
     QueryFlpTokenFromPoolAddress { 
         pool_address: String,
     },
+    /// Pools the wallet has staked in, with the staked amount in each
+    QueryPoolsOfUser {
+        wallet: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Wallets staked in the pool, with the staked amount of each
+    QueryStakersInPool {
+        pool: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Everything the dashboard shows for a wallet, one entry per pool it has staked in
+    QueryUserPositions {
+        wallet: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Boosted stake the wallet earns rewards on in the pool
+    QueryWorkingAmount {
+        wallet: String,
+        pool: String,
+    },
+    QueryTotalWorkingAmount {
+        pool: String,
+    },
+    QueryLockTiers {},
+    /// Stakes of the wallet in the pool per lock tier
+    QueryUserLockTiers {
+        wallet: String,
+        pool: String,
+    },
 }
 
 // We define a custom struct for each query response
@@ -143,6 +208,33 @@ pub struct QueryUserRewardInPoolResponse {
     pub rewards_info: Vec<(String, Uint128)>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct QueryUserPositionResponse {
+    pub pool: String,
+    pub staked: Uint128,
+    pub pending_rewards: Vec<(String, Uint128)>,
+    pub auto_compound: bool,
+    /// Time in seconds from which rewards of the pool can be claimed
+    pub unlock_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct LockTier {
+    pub tier: u64,
+    /// Seconds a stake in this tier stays locked
+    pub lock_period: u64,
+    /// Reward weight of a staked token in this tier
+    pub multiplier: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct UserLockTierResponse {
+    pub tier: u64,
+    pub amount: Uint128,
+    pub multiplier: Decimal,
+    pub unlock_time: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct StakeableResponse {
     pub stakes: Vec<StakeableToken>,
@@ -151,8 +243,8 @@ pub struct StakeableResponse {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Cw20HookMsg {
-    /// Stake a given amount of asset
-    Stake {},
+    /// Stake a given amount of asset, optionally locked in one of the lock tiers
+    Stake { lock_tier: Option<u64> },
 
     UnstakeAndClaim{},
 
@@ -0,0 +1,108 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::asset::{Asset, AssetInfo};
+
+use cosmwasm_std::Decimal;
+use cw20::Cw20ReceiveMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Asset infos
+    pub asset_infos: [AssetInfo; 2],
+    pub asset_decimals: [u8; 2],
+    /// Owner who seeds the pool and withdraws it after the sale
+    pub owner: String,
+    /// Swaps are allowed from start_time (inclusive) to end_time (exclusive), in seconds
+    pub start_time: u64,
+    pub end_time: u64,
+    /// Weights applied at start_time and end_time, e.g. [0.96, 0.04] => [0.5, 0.5]
+    pub start_weights: [Decimal; 2],
+    pub end_weights: [Decimal; 2],
+    pub commission_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    /// Owner seeds the pool before the sale ends
+    ProvideLiquidity {
+        assets: [Asset; 2],
+    },
+    /// Swap an offer asset to the other
+    Swap {
+        offer_asset: Asset,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+    /// Owner withdraws the whole pool once the sale has ended
+    WithdrawLiquidity {
+        receiver: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Sell a given amount of asset
+    Swap {
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Lbp {},
+    Pool {},
+    Config {},
+    /// Weights at the given time, or at the current block time if omitted
+    Weights {
+        time: Option<u64>,
+    },
+    Simulation {
+        offer_asset: Asset,
+    },
+    ReverseSimulation {
+        ask_asset: Asset,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct LbpInfo {
+    pub asset_infos: [AssetInfo; 2],
+    pub contract_addr: String,
+    pub asset_decimals: [u8; 2],
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub start_weights: [Decimal; 2],
+    pub end_weights: [Decimal; 2],
+    pub commission_rate: Decimal,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PoolResponse {
+    pub assets: [Asset; 2],
+    pub weights: [Decimal; 2],
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct WeightsResponse {
+    pub weights: [Decimal; 2],
+}
+
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MigrateMsg {}
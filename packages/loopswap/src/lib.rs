@@ -1,5 +1,10 @@
+pub mod aggregator;
 pub mod asset;
+pub mod conviction;
+pub mod dao;
+pub mod dca;
 pub mod factory;
+pub mod lbp;
 pub mod pair;
 pub mod querier;
 pub mod router;
@@ -1,7 +1,8 @@
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, from_slice, to_binary, Api, Binary, Coin, ContractResult, Empty, OwnedDeps,
-    Querier, QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+    from_binary, from_slice, to_binary, Api, Binary, Coin, ContractResult, Decimal, Empty,
+    OwnedDeps, Querier, QuerierResult, QueryRequest, SystemError, SystemResult, Uint128,
+    WasmQuery,
 };
 use std::collections::HashMap;
 use std::marker::PhantomData;
@@ -11,6 +12,8 @@ use crate::asset::{AssetInfo, AssetInfoRaw, PairInfo, PairInfoRaw};
 use crate::factory::QueryMsg as FactoryQueryMsg;
 use crate::pair::QueryMsg as PairQueryMsg;
 use crate::pair::{ReverseSimulationResponse, SimulationResponse};
+use crate::staking::{DurationsResponse, QueryMsg as StakingQueryMsg};
+use cw2::ContractVersion;
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
 
 use std::iter::FromIterator;
@@ -35,6 +38,14 @@ pub struct WasmMockQuerier {
     base: MockQuerier<Empty>,
     token_querier: TokenQuerier,
     terraswap_factory_querier: TerraswapFactoryQuerier,
+    contract_versions: HashMap<String, ContractVersion>,
+    staking_querier: StakingQuerier,
+}
+
+#[derive(Clone, Default)]
+pub struct StakingQuerier {
+    // total voting power per lock duration, user power is answered from the token balances
+    total_balances: Vec<(u64, Uint128)>,
 }
 
 #[derive(Clone, Default)]
@@ -141,11 +152,13 @@ impl WasmMockQuerier {
                             liquidity_token: "liquidity0000".to_string(),
                         })))
                     }
-                    Ok(PairQueryMsg::Simulation { offer_asset }) => {
+                    Ok(PairQueryMsg::Simulation { offer_asset, .. }) => {
                         SystemResult::Ok(ContractResult::from(to_binary(&SimulationResponse {
                             return_amount: offer_asset.amount,
                             commission_amount: Uint128::zero(),
                             spread_amount: Uint128::zero(),
+                            commission_rate: Decimal::zero(),
+                            referral_amount: Uint128::zero(),
                         })))
                     }
                     Ok(PairQueryMsg::ReverseSimulation { ask_asset }) => SystemResult::Ok(
@@ -153,73 +166,98 @@ impl WasmMockQuerier {
                             offer_amount: ask_asset.amount,
                             commission_amount: Uint128::zero(),
                             spread_amount: Uint128::zero(),
+                            commission_rate: Decimal::zero(),
                         })),
                     ),
-                    _ => match from_binary(msg).unwrap() {
-                        Cw20QueryMsg::TokenInfo {} => {
-                            let balances: &HashMap<String, Uint128> =
-                                match self.token_querier.balances.get(contract_addr) {
-                                    Some(balances) => balances,
-                                    None => {
-                                        return SystemResult::Err(SystemError::InvalidRequest {
-                                            error: format!(
-                                                "No balance info exists for the contract {}",
-                                                contract_addr
-                                            ),
-                                            request: msg.as_slice().into(),
-                                        })
-                                    }
-                                };
+                    _ => match from_binary(msg) {
+                        Ok(StakingQueryMsg::QueryConfig {}) => {
+                            SystemResult::Ok(ContractResult::from(to_binary(&DurationsResponse {
+                                duration_values_vector: self
+                                    .staking_querier
+                                    .total_balances
+                                    .iter()
+                                    .map(|(duration, _)| *duration)
+                                    .collect(),
+                            })))
+                        }
+                        Ok(StakingQueryMsg::TotalBalance { duration }) => {
+                            let balance = self
+                                .staking_querier
+                                .total_balances
+                                .iter()
+                                .find(|(d, _)| *d == duration)
+                                .map(|(_, balance)| *balance)
+                                .unwrap_or_default();
+                            SystemResult::Ok(ContractResult::from(to_binary(
+                                &Cw20BalanceResponse { balance },
+                            )))
+                        }
+                        _ => match from_binary(msg).unwrap() {
+                            Cw20QueryMsg::TokenInfo {} => {
+                                let balances: &HashMap<String, Uint128> =
+                                    match self.token_querier.balances.get(contract_addr) {
+                                        Some(balances) => balances,
+                                        None => {
+                                            return SystemResult::Err(SystemError::InvalidRequest {
+                                                error: format!(
+                                                    "No balance info exists for the contract {}",
+                                                    contract_addr
+                                                ),
+                                                request: msg.as_slice().into(),
+                                            })
+                                        }
+                                    };
 
-                            let mut total_supply = Uint128::zero();
+                                let mut total_supply = Uint128::zero();
 
-                            for balance in balances {
-                                total_supply += *balance.1;
+                                for balance in balances {
+                                    total_supply += *balance.1;
+                                }
+
+                                SystemResult::Ok(ContractResult::Ok(
+                                    to_binary(&TokenInfoResponse {
+                                        name: "mAAPL".to_string(),
+                                        symbol: "mAAPL".to_string(),
+                                        decimals: 8,
+                                        total_supply,
+                                    })
+                                    .unwrap(),
+                                ))
                             }
+                            Cw20QueryMsg::Balance { address } => {
+                                let balances: &HashMap<String, Uint128> =
+                                    match self.token_querier.balances.get(contract_addr) {
+                                        Some(balances) => balances,
+                                        None => {
+                                            return SystemResult::Err(SystemError::InvalidRequest {
+                                                error: format!(
+                                                    "No balance info exists for the contract {}",
+                                                    contract_addr
+                                                ),
+                                                request: msg.as_slice().into(),
+                                            })
+                                        }
+                                    };
 
-                            SystemResult::Ok(ContractResult::Ok(
-                                to_binary(&TokenInfoResponse {
-                                    name: "mAAPL".to_string(),
-                                    symbol: "mAAPL".to_string(),
-                                    decimals: 8,
-                                    total_supply,
-                                })
-                                .unwrap(),
-                            ))
-                        }
-                        Cw20QueryMsg::Balance { address } => {
-                            let balances: &HashMap<String, Uint128> =
-                                match self.token_querier.balances.get(contract_addr) {
-                                    Some(balances) => balances,
+                                let balance = match balances.get(&address) {
+                                    Some(v) => *v,
                                     None => {
-                                        return SystemResult::Err(SystemError::InvalidRequest {
-                                            error: format!(
-                                                "No balance info exists for the contract {}",
-                                                contract_addr
-                                            ),
-                                            request: msg.as_slice().into(),
-                                        })
+                                        return SystemResult::Ok(ContractResult::Ok(
+                                            to_binary(&Cw20BalanceResponse {
+                                                balance: Uint128::zero(),
+                                            })
+                                            .unwrap(),
+                                        ));
                                     }
                                 };
 
-                            let balance = match balances.get(&address) {
-                                Some(v) => *v,
-                                None => {
-                                    return SystemResult::Ok(ContractResult::Ok(
-                                        to_binary(&Cw20BalanceResponse {
-                                            balance: Uint128::zero(),
-                                        })
-                                        .unwrap(),
-                                    ));
-                                }
-                            };
-
-                            SystemResult::Ok(ContractResult::Ok(
-                                to_binary(&Cw20BalanceResponse { balance }).unwrap(),
-                            ))
-                        }
+                                SystemResult::Ok(ContractResult::Ok(
+                                    to_binary(&Cw20BalanceResponse { balance }).unwrap(),
+                                ))
+                            }
 
-                        _ => panic!("DO NOT ENTER HERE"),
+                            _ => panic!("DO NOT ENTER HERE"),
+                        },
                     },
                 },
             },
@@ -257,6 +295,11 @@ impl WasmMockQuerier {
                         ],
                         asset_decimals: [6u8, 6u8],
                     })))
+                } else if key == b"contract_info" {
+                    match self.contract_versions.get(contract_addr) {
+                        Some(v) => SystemResult::Ok(ContractResult::from(to_binary(v))),
+                        None => SystemResult::Ok(ContractResult::Ok(Binary::default())),
+                    }
                 } else {
                     panic!("DO NOT ENTER HERE")
                 }
@@ -272,6 +315,8 @@ impl WasmMockQuerier {
             base,
             token_querier: TokenQuerier::default(),
             terraswap_factory_querier: TerraswapFactoryQuerier::default(),
+            contract_versions: HashMap::new(),
+            staking_querier: StakingQuerier::default(),
         }
     }
 
@@ -285,6 +330,19 @@ impl WasmMockQuerier {
         self.terraswap_factory_querier = TerraswapFactoryQuerier::new(pairs);
     }
 
+    // configure the cw2 versions returned by raw queries
+    pub fn with_contract_versions(&mut self, versions: &[(&String, &ContractVersion)]) {
+        for (contract_addr, version) in versions {
+            self.contract_versions
+                .insert(contract_addr.to_string(), (*version).clone());
+        }
+    }
+
+    // configure the total voting power of the staking contract per duration
+    pub fn with_staking_total_balances(&mut self, total_balances: &[(u64, Uint128)]) {
+        self.staking_querier.total_balances = total_balances.to_vec();
+    }
+
     pub fn with_balance(&mut self, balances: &[(&String, Vec<Coin>)]) {
         for (addr, balance) in balances {
             self.base.update_balance(addr.to_string(), balance.clone());
@@ -35,6 +35,9 @@ pub enum ExecuteMsg {
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
         to: Option<String>,
+        /// Block time in seconds after which the swap fails
+        deadline: Option<u64>,
+        referral: Option<Referral>,
     },
     /// Withdraw extra commission fee raised for stakers
     WithdrawExtraCommissionFee {},
@@ -49,6 +52,36 @@ pub enum ExecuteMsg {
     UpdateAdmin{ new_admin: String},
 
     UpdateCommissionRate{ new_rate: String},
+    /// Cap of the referral commission in basis points, admin only
+    UpdateMaxReferralBps { max_referral_bps: u64 },
+    /// Block swaps and deposits, admin or factory only
+    Pause {},
+    /// Resume swaps and deposits, admin or factory only
+    Unpause {},
+    /// Set the max price move a single swap may cause, swaps above it are rejected
+    UpdateCircuitBreaker { max_price_impact: Option<Decimal> },
+    /// Enable the volatility based fee, or go back to the fixed commission rate with None
+    UpdateDynamicFee { dynamic_fee: Option<DynamicFeeConfig> },
+    /// Return the tokens of a pending deposit, anyone can refund an expired one to its depositor
+    RefundDeposit { depositor: Option<String> },
+}
+
+/// Referrer paid `commission_bps` of the swap output, capped by the pair max_referral_bps
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Referral {
+    pub address: String,
+    pub commission_bps: u64,
+}
+
+/// The commission rate follows the realized volatility of recent swap prices,
+/// min_fee + volatility * volatility_multiplier, capped at max_fee
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DynamicFeeConfig {
+    pub min_fee: Decimal,
+    pub max_fee: Decimal,
+    pub volatility_multiplier: Decimal,
+    /// Length of the rolling price window in seconds
+    pub window: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -59,8 +92,17 @@ pub enum Cw20HookMsg {
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
         to: Option<String>,
+        deadline: Option<u64>,
+        referral: Option<Referral>,
     },
     WithdrawLiquidity {},
+    /// Deposit one side of `assets`, the other side completes it with a Send
+    /// or a ProvideLiquidity with the same assets before the deposit expires
+    ProvideLiquidity {
+        assets: [Asset; 2],
+        slippage_tolerance: Option<Decimal>,
+        receiver: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -68,11 +110,20 @@ pub enum Cw20HookMsg {
 pub enum QueryMsg {
     Pair {},
     Pool {},
-    Simulation { offer_asset: Asset },
+    Simulation {
+        offer_asset: Asset,
+        referral: Option<Referral>,
+    },
     ReverseSimulation { ask_asset: Asset },
     ExtraCommissionFee {},
     ExtraCommissionInfo {},
     QueryConfig {},
+    PauseInfo {},
+    DynamicFee {},
+    /// Swap volume, fees and estimated LP APR over the period
+    Statistics { period: StatisticsPeriod },
+    /// Returns null when the address has no pending deposit
+    PendingDeposit { address: String },
 }
 
 // We define a custom struct for each query response
@@ -88,6 +139,12 @@ pub struct SimulationResponse {
     pub return_amount: Uint128,
     pub spread_amount: Uint128,
     pub commission_amount: Uint128,
+    /// Commission rate the swap would pay
+    #[serde(default)]
+    pub commission_rate: Decimal,
+    /// Part of the output paid to the referrer, already deducted from return_amount
+    #[serde(default)]
+    pub referral_amount: Uint128,
 }
 
 /// ReverseSimulationResponse returns reverse swap simulation response
@@ -96,6 +153,9 @@ pub struct ReverseSimulationResponse {
     pub offer_amount: Uint128,
     pub spread_amount: Uint128,
     pub commission_amount: Uint128,
+    /// Commission rate the swap would pay
+    #[serde(default)]
+    pub commission_rate: Decimal,
 }
 
 // We define a custom struct for each query response
@@ -105,6 +165,54 @@ pub struct ExtraCommissionFeeResponse {
 }
 
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PauseInfoResponse {
+    pub paused: bool,
+    pub max_price_impact: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DynamicFeeResponse {
+    pub dynamic_fee: Option<DynamicFeeConfig>,
+    /// Realized volatility over the current window
+    pub volatility: Decimal,
+    /// Commission rate a swap pays now
+    pub commission_rate: Decimal,
+}
+
+/// Statistics periods, hour and day add up hourly buckets, week and month daily ones
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StatisticsPeriod {
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct StatisticsResponse {
+    pub period: StatisticsPeriod,
+    /// Offered amount of each asset
+    pub volume: [Asset; 2],
+    /// Commission taken in each asset
+    pub fees: [Asset; 2],
+    pub swap_count: u64,
+    /// LP share of the fees over the pool value, annualized
+    pub apr: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PendingDepositResponse {
+    /// Assets of the whole deposit
+    pub assets: [Asset; 2],
+    /// Assets received so far
+    pub deposited: [Asset; 2],
+    pub slippage_tolerance: Option<Decimal>,
+    pub receiver: Option<String>,
+    pub expires_at: u64,
+}
+
 /// We currently take no arguments for migrations
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct MigrateMsg {
@@ -1,12 +1,14 @@
 use crate::asset::{Asset, AssetInfo, PairInfo};
-use crate::factory::QueryMsg as FactoryQueryMsg;
+use crate::factory::{PairsResponse, QueryMsg as FactoryQueryMsg};
 use crate::pair::{QueryMsg as PairQueryMsg, ReverseSimulationResponse, SimulationResponse};
+use crate::staking::{DurationsResponse, QueryMsg as StakingQueryMsg};
 
 use cosmwasm_std::{
-    to_binary, Addr, AllBalanceResponse, BalanceResponse, BankQuery, Coin, Empty, QuerierWrapper,
+    from_slice, to_binary, Addr, AllBalanceResponse, BalanceResponse, BankQuery, Coin, Empty, QuerierWrapper,
     QueryRequest, StdResult, Uint128, WasmQuery,
 };
 
+use cw2::ContractVersion;
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
 
 pub fn query_balance(
@@ -50,6 +52,43 @@ pub fn query_token_balance(
     Ok(res.balance)
 }
 
+/// Current voting power of the account in the staking contract, summed over all durations
+pub fn query_voting_power(
+    querier: &QuerierWrapper<Empty>,
+    staking_contract: Addr,
+    account_addr: Addr,
+) -> StdResult<Uint128> {
+    let res: Cw20BalanceResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: staking_contract.to_string(),
+        msg: to_binary(&StakingQueryMsg::Balance {
+            address: account_addr.to_string(),
+        })?,
+    }))?;
+
+    Ok(res.balance)
+}
+
+/// Current voting power of all stakers in the staking contract
+pub fn query_total_voting_power(
+    querier: &QuerierWrapper<Empty>,
+    staking_contract: Addr,
+) -> StdResult<Uint128> {
+    let config: DurationsResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: staking_contract.to_string(),
+        msg: to_binary(&StakingQueryMsg::QueryConfig {})?,
+    }))?;
+
+    let mut total_power = Uint128::zero();
+    for duration in config.duration_values_vector {
+        let res: Cw20BalanceResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: staking_contract.to_string(),
+            msg: to_binary(&StakingQueryMsg::TotalBalance { duration })?,
+        }))?;
+        total_power += res.balance;
+    }
+    Ok(total_power)
+}
+
 pub fn query_token_info(
     querier: &QuerierWrapper<Empty>,
     contract_addr: Addr,
@@ -75,6 +114,18 @@ pub fn query_pair_info(
     }))
 }
 
+pub fn query_pairs(
+    querier: &QuerierWrapper<Empty>,
+    factory_contract: Addr,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+) -> StdResult<PairsResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: factory_contract.to_string(),
+        msg: to_binary(&FactoryQueryMsg::Pairs { start_after, limit })?,
+    }))
+}
+
 pub fn simulate(
     querier: &QuerierWrapper<Empty>,
     pair_contract: Addr,
@@ -84,6 +135,7 @@ pub fn simulate(
         contract_addr: pair_contract.to_string(),
         msg: to_binary(&PairQueryMsg::Simulation {
             offer_asset: offer_asset.clone(),
+            referral: None,
         })?,
     }))
 }
@@ -101,6 +153,17 @@ pub fn reverse_simulate(
     }))
 }
 
+/// Reads the cw2 version of a contract, None if it never recorded one
+pub fn query_contract_version(
+    querier: &QuerierWrapper<Empty>,
+    contract_addr: Addr,
+) -> StdResult<Option<ContractVersion>> {
+    match querier.query_wasm_raw(contract_addr, b"contract_info".to_vec())? {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
 pub fn query_pair_info_from_pair(
     querier: &QuerierWrapper<Empty>,
     pair_contract: Addr,
@@ -180,6 +180,13 @@ pub enum Cw20HookMsg {
     Deposit {},
 }
 
+/// The part of the `QueryConfig` response other contracts need to sum voting power
+/// over every lock duration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DurationsResponse {
+    pub duration_values_vector: Vec<u64>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug,  PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {